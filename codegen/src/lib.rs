@@ -1,5 +1,6 @@
 use std::collections::HashMap;
-use parser::Expr;
+use parser::{BinOp, CmpOp, Expr, ExprKind, UnOp};
+use scanner::Span;
 
 struct RegisterAllocator {
     next_reg: i64,
@@ -17,19 +18,50 @@ impl RegisterAllocator {
     }
 }
 
-fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table: &mut HashMap<String, i64>, reg_alloc: &mut RegisterAllocator) -> Vec<String> {
+// String constants are declared in the data area at the top of the block that uses them.
+// Their labels start with `.`, which no variable name can, so they never clash with variables
+fn string_constant(reg: i64, text: &str) -> (String, String) {
+    let label = format!(".str{}", reg);
+    (format!("DATA @{} {:?}", label, text), label)
+}
+
+// ILOC has no instruction for `^` or `//`
+fn arithmetic_opcode(op: BinOp, is_float: bool) -> Option<String> {
+    let name = match op {
+        BinOp::Add => "ADD",
+        BinOp::Sub => "SUB",
+        BinOp::Mul => "MUL",
+        BinOp::Div => "DIV",
+        BinOp::IntDiv | BinOp::Pow => return None,
+    };
+    Some(format!("{}.{}", name, if is_float { "f" } else { "i" }))
+}
+
+fn comparison_opcode(op: CmpOp, is_float: bool) -> String {
+    let name = match op {
+        CmpOp::Eq => "EQ",
+        CmpOp::Ne => "NE",
+        CmpOp::Lt => "LT",
+        CmpOp::Gt => "GT",
+        CmpOp::Le => "LE",
+        CmpOp::Ge => "GE",
+    };
+    format!("{}.{}", name, if is_float { "f" } else { "i" })
+}
+
+fn generate_binary_arithmetic(left: &ExprKind, right: &ExprKind, op: BinOp, _symbol_table: &mut HashMap<String, i64>, reg_alloc: &mut RegisterAllocator) -> Vec<String> {
     let mut instructions = Vec::new();
     
     // Helper function to get the value of a unary operation
-    fn get_unary_value(expr: &Expr) -> Option<(bool, Expr)> {
+    fn get_unary_value(expr: &ExprKind) -> Option<(bool, ExprKind)> {
         match expr {
-            Expr::UnaryOp(op, value) => {
-                match (op.as_str(), value.as_ref()) {
-                    ("-", value @ (Expr::Int(_) | Expr::Float(_))) => Some((true, value.clone())),
+            ExprKind::UnaryOp(op, value) => {
+                match (op, &value.kind) {
+                    (UnOp::Neg, value @ (ExprKind::Int(_) | ExprKind::Float(_))) => Some((true, value.clone())),
                     _ => None
                 }
             }
-            value @ (Expr::Int(_) | Expr::Float(_)) => Some((false, value.clone())),
+            value @ (ExprKind::Int(_) | ExprKind::Float(_)) => Some((false, value.clone())),
             _ => None
         }
     }
@@ -41,13 +73,13 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             let (is_neg2, val2) = get_unary_value(right).unwrap();
             
             // Check for division by zero first
-            if op == "/" {
+            if op.is_division() {
                 match val2 {
-                    Expr::Int(n) => if (if is_neg2 { -n } else { n }) == 0 {
+                    ExprKind::Int(n) => if (if is_neg2 { -n } else { n }) == 0 {
                         instructions.push("ERROR".to_string());
                         return instructions;
                     },
-                    Expr::Float(n) => if (if is_neg2 { -n } else { n }) == 0.0 {
+                    ExprKind::Float(n) => if (if is_neg2 { -n } else { n }) == 0.0 {
                         instructions.push("ERROR".to_string());
                         return instructions;
                     },
@@ -60,18 +92,18 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             let r2 = reg_alloc.get_next_reg();
             
             // Determine if we need float operations
-            let is_float = matches!(val1, Expr::Float(_)) || matches!(val2, Expr::Float(_));
+            let is_float = matches!(val1, ExprKind::Float(_)) || matches!(val2, ExprKind::Float(_));
             
             // Load first value
             match val1 {
-                Expr::Int(n) => {
+                ExprKind::Int(n) => {
                     let n = if is_neg1 { -n } else { n };
                     instructions.push(format!("LD R{} #{}", r0, n));
                     if is_float {
                         instructions.push(format!("FL.i R{} R{}", r0, r0));
                     }
                 }
-                Expr::Float(n) => {
+                ExprKind::Float(n) => {
                     let n = if is_neg1 { -n } else { n };
                     if n.fract() == 0.0 {
                         instructions.push(format!("LD R{} #{}.0", r0, n));
@@ -84,14 +116,14 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             
             // Load second value
             match val2 {
-                Expr::Int(n) => {
+                ExprKind::Int(n) => {
                     let n = if is_neg2 { -n } else { n };
                     instructions.push(format!("LD R{} #{}", r1, n));
                     if is_float {
                         instructions.push(format!("FL.i R{} R{}", r1, r1));
                     }
                 }
-                Expr::Float(n) => {
+                ExprKind::Float(n) => {
                     let n = if is_neg2 { -n } else { n };
                     if n.fract() == 0.0 {
                         instructions.push(format!("LD R{} #{}.0", r1, n));
@@ -102,33 +134,17 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
                 _ => unreachable!()
             }
             
-            let op_code = match (op, is_float) {
-                ("+", false) => "ADD.i",
-                ("-", false) => "SUB.i",
-                ("*", false) => "MUL.i",
-                ("/", false) => "DIV.i",
-                ("+", true) => "ADD.f",
-                ("-", true) => "SUB.f",
-                ("*", true) => "MUL.f",
-                ("/", true) => "DIV.f",
-                ("==", _) => if is_float { "EQ.f" } else { "EQ.i" },
-                ("!=", _) => if is_float { "NE.f" } else { "NE.i" },
-                ("<", _) => if is_float { "LT.f" } else { "LT.i" },
-                (">", _) => if is_float { "GT.f" } else { "GT.i" },
-                ("<=", _) => if is_float { "LE.f" } else { "LE.i" },
-                (">=", _) => if is_float { "GE.f" } else { "GE.i" },
-                _ => {
-                    instructions.push("ERROR".to_string());
-                    return instructions;
-                }
+            let Some(op_code) = arithmetic_opcode(op, is_float) else {
+                instructions.push("ERROR".to_string());
+                return instructions;
             };
             
             instructions.push(format!("{} R{} R{} R{}", op_code, r2, r0, r1));
             instructions.push(format!("ST @print R{}", r2));
         }
-        (Expr::Int(n1), Expr::Int(n2)) => {
+        (ExprKind::Int(n1), ExprKind::Int(n2)) => {
             // Check for division by zero first
-            if op == "/" && *n2 == 0 {
+            if op.is_division() && *n2 == 0 {
                 instructions.push("ERROR".to_string());
                 return instructions;
             }
@@ -140,27 +156,15 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             instructions.push(format!("LD R{} #{}", r0, n1));
             instructions.push(format!("LD R{} #{}", r1, n2));
             
-            let op_code = match op {
-                "+" => "ADD.i",
-                "-" => "SUB.i",
-                "*" => "MUL.i",
-                "/" => "DIV.i",
-                "==" => "EQ.i",
-                "!=" => "NE.i",
-                "<" => "LT.i",
-                ">" => "GT.i",
-                "<=" => "LE.i",
-                ">=" => "GE.i",
-                _ => {
-                    instructions.push("ERROR".to_string());
-                    return instructions;
-                }
+            let Some(op_code) = arithmetic_opcode(op, false) else {
+                instructions.push("ERROR".to_string());
+                return instructions;
             };
             
             instructions.push(format!("{} R{} R{} R{}", op_code, r2, r0, r1));
             instructions.push(format!("ST @print R{}", r2));
         }
-        (Expr::Int(n1), Expr::Float(n2)) => {
+        (ExprKind::Int(n1), ExprKind::Float(n2)) => {
             let r0 = reg_alloc.get_next_reg();
             let r1 = reg_alloc.get_next_reg();
             let r2 = reg_alloc.get_next_reg();
@@ -169,21 +173,15 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             instructions.push(format!("FL.i R{} R{}", r0, r0));
             instructions.push(format!("LD R{} #{}.0", r1, n2));
             
-            let op_code = match op {
-                "+" => "ADD.f",
-                "-" => "SUB.f",
-                "*" => "MUL.f",
-                "/" => "DIV.f",
-                _ => {
-                    instructions.push("ERROR".to_string());
-                    return instructions;
-                }
+            let Some(op_code) = arithmetic_opcode(op, true) else {
+                instructions.push("ERROR".to_string());
+                return instructions;
             };
             
             instructions.push(format!("{} R{} R{} R{}", op_code, r2, r0, r1));
             instructions.push(format!("ST @print R{}", r2));
         }
-        (Expr::Float(n1), Expr::Int(n2)) => {
+        (ExprKind::Float(n1), ExprKind::Int(n2)) => {
             let r0 = reg_alloc.get_next_reg();
             let r1 = reg_alloc.get_next_reg();
             let r2 = reg_alloc.get_next_reg();
@@ -192,27 +190,15 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             instructions.push(format!("LD R{} #{}", r1, n2));
             instructions.push(format!("FL.i R{} R{}", r1, r1));
             
-            let op_code = match op {
-                "+" => "ADD.f",
-                "-" => "SUB.f",
-                "*" => "MUL.f",
-                "/" => "DIV.f",
-                "==" => "EQ.f",
-                "!=" => "NE.f",
-                "<" => "LT.f",
-                ">" => "GT.f",
-                "<=" => "LE.f",
-                ">=" => "GE.f",
-                _ => {
-                    instructions.push("ERROR".to_string());
-                    return instructions;
-                }
+            let Some(op_code) = arithmetic_opcode(op, true) else {
+                instructions.push("ERROR".to_string());
+                return instructions;
             };
             
             instructions.push(format!("{} R{} R{} R{}", op_code, r2, r0, r1));
             instructions.push(format!("ST @print R{}", r2));
         }
-        (Expr::Int(n), Expr::Variable(var)) | (Expr::Variable(var), Expr::Int(n)) => {
+        (ExprKind::Int(n), ExprKind::Variable(var)) | (ExprKind::Variable(var), ExprKind::Int(n)) => {
             let r0 = reg_alloc.get_next_reg();
             let r1 = reg_alloc.get_next_reg();
             let r2 = reg_alloc.get_next_reg();
@@ -221,27 +207,15 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             instructions.push(format!("LD R{} #{}", r0, n));
             instructions.push(format!("LD R{} @{}", r1, var));
             
-            let op_code = match op {
-                "+" => "ADD.i",
-                "-" => "SUB.i",
-                "*" => "MUL.i",
-                "/" => "DIV.i",
-                "==" => "EQ.i",
-                "!=" => "NE.i",
-                "<" => "LT.i",
-                ">" => "GT.i",
-                "<=" => "LE.i",
-                ">=" => "GE.i",
-                _ => {
-                    instructions.push("ERROR".to_string());
-                    return instructions;
-                }
+            let Some(op_code) = arithmetic_opcode(op, false) else {
+                instructions.push("ERROR".to_string());
+                return instructions;
             };
             
             instructions.push(format!("{} R{} R{} R{}", op_code, r2, r0, r1));
             instructions.push(format!("ST @print R{}", r2));
         }
-        (Expr::Float(n1), Expr::Float(n2)) => {
+        (ExprKind::Float(n1), ExprKind::Float(n2)) => {
             let r0 = reg_alloc.get_next_reg();
             let r1 = reg_alloc.get_next_reg();
             let r2 = reg_alloc.get_next_reg();
@@ -249,21 +223,15 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             instructions.push(format!("LD R{} #{}.0", r0, n1));
             instructions.push(format!("LD R{} #{}.0", r1, n2));
             
-            let op_code = match op {
-                "+" => "ADD.f",
-                "-" => "SUB.f",
-                "*" => "MUL.f",
-                "/" => "DIV.f",
-                _ => {
-                    instructions.push("ERROR".to_string());
-                    return instructions;
-                }
+            let Some(op_code) = arithmetic_opcode(op, true) else {
+                instructions.push("ERROR".to_string());
+                return instructions;
             };
             
             instructions.push(format!("{} R{} R{} R{}", op_code, r2, r0, r1));
             instructions.push(format!("ST @print R{}", r2));
         }
-        (Expr::Variable(var1), Expr::Variable(var2)) => {
+        (ExprKind::Variable(var1), ExprKind::Variable(var2)) => {
             let r0 = reg_alloc.get_next_reg();
             let r1 = reg_alloc.get_next_reg();
             let r2 = reg_alloc.get_next_reg();
@@ -271,27 +239,15 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             instructions.push(format!("LD R{} @{}", r0, var1));
             instructions.push(format!("LD R{} @{}", r1, var2));
             
-            let op_code = match op {
-                "+" => "ADD.i",
-                "-" => "SUB.i",
-                "*" => "MUL.i",
-                "/" => "DIV.i",
-                "==" => "EQ.i",
-                "!=" => "NE.i",
-                "<" => "LT.i",
-                ">" => "GT.i",
-                "<=" => "LE.i",
-                ">=" => "GE.i",
-                _ => {
-                    instructions.push("ERROR".to_string());
-                    return instructions;
-                }
+            let Some(op_code) = arithmetic_opcode(op, false) else {
+                instructions.push("ERROR".to_string());
+                return instructions;
             };
             
             instructions.push(format!("{} R{} R{} R{}", op_code, r2, r0, r1));
             instructions.push(format!("ST @print R{}", r2));
         }
-        (Expr::ListAccess(list_name1, idx1), Expr::ListAccess(list_name2, idx2)) => {
+        (ExprKind::ListAccess(list_name1, idx1), ExprKind::ListAccess(list_name2, idx2)) => {
             let r0 = reg_alloc.get_next_reg();
             let r1 = reg_alloc.get_next_reg();
             let r2 = reg_alloc.get_next_reg();
@@ -301,8 +257,8 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             let r6 = reg_alloc.get_next_reg();
             let r7 = reg_alloc.get_next_reg();
 
-            let idx1_value = if let Expr::Int(n) = **idx1 { n } else { panic!("Expected integer index") };
-            let idx2_value = if let Expr::Int(n) = **idx2 { n } else { panic!("Expected integer index") };
+            let idx1_value = if let ExprKind::Int(n) = idx1.kind { n } else { panic!("Expected integer index") };
+            let idx2_value = if let ExprKind::Int(n) = idx2.kind { n } else { panic!("Expected integer index") };
 
             // Load first list element
             instructions.push(format!("LD R{} @{}", r0, list_name1));
@@ -321,15 +277,9 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
             instructions.push(format!("LD R{} R{}", r6, r4));
 
             // Perform the operation
-            let op_code = match op {
-                "+" => "ADD.i",
-                "-" => "SUB.i",
-                "*" => "MUL.i",
-                "/" => "DIV.i",
-                _ => {
-                    instructions.push("ERROR".to_string());
-                    return instructions;
-                }
+            let Some(op_code) = arithmetic_opcode(op, false) else {
+                instructions.push("ERROR".to_string());
+                return instructions;
             };
 
             instructions.push(format!("{} R{} R{} R{}", op_code, r7, r5, r6));
@@ -342,7 +292,7 @@ fn generate_binary_arithmetic(left: &Expr, right: &Expr, op: &str, _symbol_table
 }
 
 fn generate_instructions(
-    expr: &Expr,
+    expr: &ExprKind,
     reg_alloc: &mut RegisterAllocator,
     symbol_table: &mut HashMap<String, i64>,
     instructions: &mut Vec<String>
@@ -350,15 +300,11 @@ fn generate_instructions(
     println!("DEBUG [Codegen]: Starting instruction generation for expr: {:?}", expr);
     let mut temp_instructions = Vec::new();
     match expr {
-        Expr::Assignment(var, expr) => {
-            // Check if this is a list element assignment
-            if var.contains('[') && var.contains(']') {
-                let parts: Vec<&str> = var.split('[').collect();
-                let list_name = parts[0];
-                let index = parts[1].trim_end_matches(']').parse::<i64>().unwrap();
-                
-                match expr.as_ref() {
-                    Expr::Int(n) => {
+        ExprKind::ElementAssignment(list_name, index, expr) => {
+            // Elements are only assigned at a constant index
+            if let ExprKind::Int(index) = index.kind {
+                match &expr.kind {
+                    ExprKind::Int(n) => {
                         let r0 = reg_alloc.get_next_reg();
                         let r1 = reg_alloc.get_next_reg();
                         let r2 = reg_alloc.get_next_reg();
@@ -373,7 +319,7 @@ fn generate_instructions(
                         temp_instructions.push(format!("ADD.i R{} R{} R{}", r2, r1, r4));
                         temp_instructions.push(format!("ST R{} R{}", r2, r0));
                     }
-                    Expr::Float(n) => {
+                    ExprKind::Float(n) => {
                         let r0 = reg_alloc.get_next_reg();
                         let r1 = reg_alloc.get_next_reg();
                         let r2 = reg_alloc.get_next_reg();
@@ -388,8 +334,8 @@ fn generate_instructions(
                         temp_instructions.push(format!("ADD.i R{} R{} R{}", r2, r1, r4));
                         temp_instructions.push(format!("ST R{} R{}", r2, r0));
                     }
-                    Expr::ListAccess(src_list_name, idx) => {
-                        if let Expr::Int(src_index) = idx.as_ref() {
+                    ExprKind::ListAccess(src_list_name, idx) => {
+                        if let ExprKind::Int(src_index) = &idx.kind {
                             let r0 = reg_alloc.get_next_reg();
                             let r1 = reg_alloc.get_next_reg();
                             let r2 = reg_alloc.get_next_reg();
@@ -416,9 +362,9 @@ fn generate_instructions(
                             temp_instructions.push("ERROR".to_string());
                         }
                     }
-                    Expr::UnaryOp(op, expr) => {
-                        match (op.as_str(), expr.as_ref()) {
-                            ("-", Expr::Int(n)) => {
+                    ExprKind::UnaryOp(op, expr) => {
+                        match (op, &expr.kind) {
+                            (UnOp::Neg, ExprKind::Int(n)) => {
                                 let r0 = reg_alloc.get_next_reg();
                                 let r1 = reg_alloc.get_next_reg();
                                 let r2 = reg_alloc.get_next_reg();
@@ -433,7 +379,7 @@ fn generate_instructions(
                                 temp_instructions.push(format!("ADD.i R{} R{} R{}", r2, r1, r4));
                                 temp_instructions.push(format!("ST R{} R{}", r2, r0));
                             }
-                            ("-", Expr::Float(n)) => {
+                            (UnOp::Neg, ExprKind::Float(n)) => {
                                 let r0 = reg_alloc.get_next_reg();
                                 let r1 = reg_alloc.get_next_reg();
                                 let r2 = reg_alloc.get_next_reg();
@@ -454,249 +400,237 @@ fn generate_instructions(
                     _ => temp_instructions.push("ERROR".to_string())
                 }
             } else {
-                match expr.as_ref() {
-                    Expr::Int(n) => {
-                        let r0 = reg_alloc.get_next_reg();
-                        temp_instructions.push(format!("LD R{} #{}", r0, n));
-                        temp_instructions.push(format!("ST @{} R{}", var, r0));
-                        symbol_table.insert(var.clone(), *n);
-                    }
-                    Expr::Float(n) => {
-                        let r0 = reg_alloc.get_next_reg();
-                        temp_instructions.push(format!("LD R{} #{}", r0, n));
-                        temp_instructions.push(format!("ST @{} R{}", var, r0));
-                        symbol_table.insert(var.clone(), n.to_bits() as i64);
-                    }
-                    Expr::Variable(name) => {
-                        let r0 = reg_alloc.get_next_reg();
-                        temp_instructions.push(format!("LD R{} @{}", r0, name));
-                        temp_instructions.push(format!("ST @{} R{}", var, r0));
-                    }
-                    Expr::List(_) => {
+                temp_instructions.push("ERROR".to_string());
+            }
+        }
+        ExprKind::Assignment(var, expr) => {
+            match &expr.kind {
+                ExprKind::Int(n) => {
+                    let r0 = reg_alloc.get_next_reg();
+                    temp_instructions.push(format!("LD R{} #{}", r0, n));
+                    temp_instructions.push(format!("ST @{} R{}", var, r0));
+                    symbol_table.insert(var.clone(), *n);
+                }
+                ExprKind::Float(n) => {
+                    let r0 = reg_alloc.get_next_reg();
+                    temp_instructions.push(format!("LD R{} #{}", r0, n));
+                    temp_instructions.push(format!("ST @{} R{}", var, r0));
+                    symbol_table.insert(var.clone(), n.to_bits() as i64);
+                }
+                ExprKind::Str(text) => {
+                    let r0 = reg_alloc.get_next_reg();
+                    let (data, label) = string_constant(r0, text);
+                    temp_instructions.push(data);
+                    temp_instructions.push(format!("LD R{} @{}", r0, label));
+                    temp_instructions.push(format!("ST @{} R{}", var, r0));
+                }
+                ExprKind::Variable(name) => {
+                    let r0 = reg_alloc.get_next_reg();
+                    temp_instructions.push(format!("LD R{} @{}", r0, name));
+                    temp_instructions.push(format!("ST @{} R{}", var, r0));
+                }
+                ExprKind::List(_) => {
+                    let r0 = reg_alloc.get_next_reg();
+                    let r1 = reg_alloc.get_next_reg();
+                    let r2 = reg_alloc.get_next_reg();
+                    let r3 = reg_alloc.get_next_reg();
+                    let r4 = reg_alloc.get_next_reg();
+                    let r5 = reg_alloc.get_next_reg();
+                    
+                    temp_instructions.push(format!("LD R{} #0", r0));
+                    temp_instructions.push(format!("LD R{} @{}", r1, var));
+                    temp_instructions.push(format!("LD R{} #0", r2));
+                    temp_instructions.push(format!("LD R{} #4", r3));
+                    temp_instructions.push(format!("MUL.i R{} R{} R{}", r4, r2, r3));
+                    temp_instructions.push(format!("ADD.i R{} R{} R{}", r5, r1, r4));
+                    temp_instructions.push(format!("ST R{} R{}", r5, r0));
+                    
+                    temp_instructions.push(format!("LD R{} #1", r2));
+                    temp_instructions.push(format!("LD R{} #4", r3));
+                    temp_instructions.push(format!("MUL.i R{} R{} R{}", r4, r2, r3));
+                    temp_instructions.push(format!("ADD.i R{} R{} R{}", r5, r1, r4));
+                    temp_instructions.push(format!("ST R{} R{}", r5, r0));
+                    
+                    println!("DEBUG [Codegen]: List assignment instructions generated: {:?}", temp_instructions);
+                    symbol_table.insert(var.clone(), 0);
+                }
+                ExprKind::ListAccess(list_name, idx) => {
+                    if let ExprKind::Int(index) = &idx.kind {
                         let r0 = reg_alloc.get_next_reg();
                         let r1 = reg_alloc.get_next_reg();
                         let r2 = reg_alloc.get_next_reg();
                         let r3 = reg_alloc.get_next_reg();
                         let r4 = reg_alloc.get_next_reg();
-                        let r5 = reg_alloc.get_next_reg();
                         
-                        temp_instructions.push(format!("LD R{} #0", r0));
-                        temp_instructions.push(format!("LD R{} @{}", r1, var));
-                        temp_instructions.push(format!("LD R{} #0", r2));
-                        temp_instructions.push(format!("LD R{} #4", r3));
-                        temp_instructions.push(format!("MUL.i R{} R{} R{}", r4, r2, r3));
-                        temp_instructions.push(format!("ADD.i R{} R{} R{}", r5, r1, r4));
-                        temp_instructions.push(format!("ST R{} R{}", r5, r0));
-                        
-                        temp_instructions.push(format!("LD R{} #1", r2));
-                        temp_instructions.push(format!("LD R{} #4", r3));
-                        temp_instructions.push(format!("MUL.i R{} R{} R{}", r4, r2, r3));
-                        temp_instructions.push(format!("ADD.i R{} R{} R{}", r5, r1, r4));
-                        temp_instructions.push(format!("ST R{} R{}", r5, r0));
-                        
-                        println!("DEBUG [Codegen]: List assignment instructions generated: {:?}", temp_instructions);
-                        symbol_table.insert(var.clone(), 0);
-                    }
-                    Expr::ListAccess(list_name, idx) => {
-                        if let Expr::Int(index) = idx.as_ref() {
-                            let r0 = reg_alloc.get_next_reg();
-                            let r1 = reg_alloc.get_next_reg();
-                            let r2 = reg_alloc.get_next_reg();
-                            let r3 = reg_alloc.get_next_reg();
-                            let r4 = reg_alloc.get_next_reg();
-                            
-                            temp_instructions.push(format!("LD R{} @{}", r0, list_name));
-                            temp_instructions.push(format!("LD R{} #{}", r1, index));
-                            temp_instructions.push(format!("LD R{} #4", r2));
-                            temp_instructions.push(format!("MUL.i R{} R{} R{}", r3, r1, r2));
-                            temp_instructions.push(format!("ADD.i R{} R{} R{}", r4, r0, r3));
-                            temp_instructions.push(format!("LD R{} R{}", r0, r4));
-                            temp_instructions.push(format!("ST @{} R{}", var, r0));
-                        } else {
-                            temp_instructions.push("ERROR".to_string());
-                        }
+                        temp_instructions.push(format!("LD R{} @{}", r0, list_name));
+                        temp_instructions.push(format!("LD R{} #{}", r1, index));
+                        temp_instructions.push(format!("LD R{} #4", r2));
+                        temp_instructions.push(format!("MUL.i R{} R{} R{}", r3, r1, r2));
+                        temp_instructions.push(format!("ADD.i R{} R{} R{}", r4, r0, r3));
+                        temp_instructions.push(format!("LD R{} R{}", r0, r4));
+                        temp_instructions.push(format!("ST @{} R{}", var, r0));
+                    } else {
+                        temp_instructions.push("ERROR".to_string());
                     }
-                    _ => temp_instructions.push("ERROR".to_string())
                 }
+                _ => temp_instructions.push("ERROR".to_string())
             }
         }
-        Expr::Int(n) => {
+        ExprKind::Int(n) => {
             println!("DEBUG [Codegen]: Generating instructions for integer: {}", n);
             let r0 = reg_alloc.get_next_reg();
             temp_instructions.push(format!("LD R{} #{}", r0, n));
             temp_instructions.push(format!("ST @print R{}", r0));
         }
-        Expr::Float(n) => {
+        ExprKind::Float(n) => {
             println!("DEBUG [Codegen]: Generating instructions for float: {}", n);
             let r0 = reg_alloc.get_next_reg();
             temp_instructions.push(format!("LD R{} #{}", r0, n));
             temp_instructions.push(format!("ST @print R{}", r0));
         }
-        Expr::BinaryOp(left, op, right) => {
+        ExprKind::Str(text) => {
+            let r0 = reg_alloc.get_next_reg();
+            let (data, label) = string_constant(r0, text);
+            temp_instructions.push(data);
+            temp_instructions.push(format!("LD R{} @{}", r0, label));
+            temp_instructions.push(format!("ST @print R{}", r0));
+        }
+        ExprKind::BinaryOp(left, op, right) => {
             println!("DEBUG [Codegen]: Generating instructions for binary op: {} {:?} {:?}", op, left, right);
-            match op.as_str() {
-                "+" | "-" | "*" | "/" | "==" | "!=" | "<" | ">" | "<=" | ">=" => {
-                    println!("DEBUG [Codegen]: {} operation", match op.as_str() {
-                        "+" => "Addition",
-                        "-" => "Subtraction",
-                        "*" => "Multiplication",
-                        "/" => "Division",
-                        "==" => "Equality",
-                        "!=" => "Inequality",
-                        "<" => "Less than",
-                        ">" => "Greater than",
-                        "<=" => "Less than or equal",
-                        ">=" => "Greater than or equal",
-                        _ => unreachable!()
-                    });
-                    temp_instructions.extend(generate_binary_arithmetic(left, right, op, symbol_table, reg_alloc))
-                },
-                "^" | "POW" => {
-                    println!("DEBUG [Codegen]: Power operation detected");
-                    temp_instructions.push("ERROR".to_string())
+            match op {
+                BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div => {
+                    temp_instructions.extend(generate_binary_arithmetic(&left.kind, &right.kind, *op, symbol_table, reg_alloc))
                 }
-                _ => {
-                    println!("DEBUG [Codegen]: Unknown binary operator: {}", op);
-                    temp_instructions.push("ERROR".to_string())
-                },
+                // ILOC has no instruction for `//` or `^`
+                BinOp::IntDiv | BinOp::Pow => temp_instructions.push("ERROR".to_string()),
             }
         }
-        Expr::Boolean(left, op, right) => {
+        ExprKind::Boolean(left, op, right) => {
             let r0 = reg_alloc.get_next_reg();
             let r1 = reg_alloc.get_next_reg();
             let r2 = reg_alloc.get_next_reg();
 
             // Helper function to check if an expression is a float or negative float
-            fn is_float_expr(expr: &Expr) -> bool {
+            fn is_float_expr(expr: &ExprKind) -> bool {
                 match expr {
-                    Expr::Float(_) => true,
-                    Expr::UnaryOp(op, inner) if op == "-" => matches!(inner.as_ref(), Expr::Float(_)),
+                    ExprKind::Float(_) => true,
+                    ExprKind::UnaryOp(UnOp::Neg, inner) => matches!(&inner.kind, ExprKind::Float(_)),
                     _ => false
                 }
             }
 
             // Determine if we need float operations
-            let needs_float = is_float_expr(left) || is_float_expr(right) || op == "!=";
+            let needs_float = is_float_expr(&left.kind) || is_float_expr(&right.kind) || *op == CmpOp::Ne;
 
             // Load the operands in the correct order
-            match (left.as_ref(), right.as_ref()) {
-                (Expr::Variable(var_name), Expr::Int(val)) => {
+            match (&left.kind, &right.kind) {
+                (ExprKind::Variable(var_name), ExprKind::Int(val)) => {
                     if needs_float {
                         temp_instructions.push(format!("LD R0 @{}", var_name));
                         temp_instructions.push(format!("LD R1 #{}", val));
-                        temp_instructions.push(format!("FL.i R0 R0"));
-                        temp_instructions.push(format!("FL.i R1 R1"));
+                        temp_instructions.push("FL.i R0 R0".to_string());
+                        temp_instructions.push("FL.i R1 R1".to_string());
                     } else {
                         temp_instructions.push(format!("LD R0 @{}", var_name));
                         temp_instructions.push(format!("LD R1 #{}", val));
                     }
                 },
-                (Expr::Int(val), Expr::Variable(var_name)) => {
+                (ExprKind::Int(val), ExprKind::Variable(var_name)) => {
                     if needs_float {
                         temp_instructions.push(format!("LD R0 #{}", val));
                         temp_instructions.push(format!("LD R1 @{}", var_name));
-                        temp_instructions.push(format!("FL.i R0 R0"));
-                        temp_instructions.push(format!("FL.i R1 R1"));
+                        temp_instructions.push("FL.i R0 R0".to_string());
+                        temp_instructions.push("FL.i R1 R1".to_string());
                     } else {
                         temp_instructions.push(format!("LD R0 #{}", val));
                         temp_instructions.push(format!("LD R1 @{}", var_name));
                     }
                 },
-                (Expr::Variable(var_name1), Expr::Variable(var_name2)) => {
+                (ExprKind::Variable(var_name1), ExprKind::Variable(var_name2)) => {
                     temp_instructions.push(format!("LD R0 @{}", var_name1));
                     temp_instructions.push(format!("LD R1 @{}", var_name2));
                     if needs_float {
-                        temp_instructions.push(format!("FL.i R0 R0"));
-                        temp_instructions.push(format!("FL.i R1 R1"));
+                        temp_instructions.push("FL.i R0 R0".to_string());
+                        temp_instructions.push("FL.i R1 R1".to_string());
                     }
                 },
-                (Expr::Int(val), Expr::Float(float_val)) => {
+                (ExprKind::Int(val), ExprKind::Float(float_val)) => {
                     temp_instructions.push(format!("LD R0 #{}", val));
-                    temp_instructions.push(format!("FL.i R0 R0"));
+                    temp_instructions.push("FL.i R0 R0".to_string());
                     temp_instructions.push(format!("LD R1 #{}", float_val));
                 },
-                (Expr::Float(float_val), Expr::Int(val)) => {
+                (ExprKind::Float(float_val), ExprKind::Int(val)) => {
                     temp_instructions.push(format!("LD R0 #{}", float_val));
                     temp_instructions.push(format!("LD R1 #{}", val));
-                    temp_instructions.push(format!("FL.i R1 R1"));
+                    temp_instructions.push("FL.i R1 R1".to_string());
                 },
-                (Expr::Variable(var_name), Expr::Float(val)) => {
+                (ExprKind::Variable(var_name), ExprKind::Float(val)) => {
                     temp_instructions.push(format!("LD R0 @{}", var_name));
-                    temp_instructions.push(format!("FL.i R0 R0"));
+                    temp_instructions.push("FL.i R0 R0".to_string());
                     temp_instructions.push(format!("LD R1 #{}", val));
                 },
-                (Expr::Float(val), Expr::Variable(var_name)) => {
+                (ExprKind::Float(val), ExprKind::Variable(var_name)) => {
                     temp_instructions.push(format!("LD R0 #{}", val));
                     temp_instructions.push(format!("LD R1 @{}", var_name));
-                    temp_instructions.push(format!("FL.i R1 R1"));
+                    temp_instructions.push("FL.i R1 R1".to_string());
                 },
-                (Expr::UnaryOp(op, inner), right) if op == "-" => {
-                    match inner.as_ref() {
-                        Expr::Variable(var) => {
+                (ExprKind::UnaryOp(UnOp::Neg, inner), right) => {
+                    match &inner.kind {
+                        ExprKind::Variable(var) => {
                             temp_instructions.push(format!("LD R0 @{}", var));
-                            temp_instructions.push(format!("NEG.i R0 R0"));
+                            temp_instructions.push("NEG.i R0 R0".to_string());
                         }
-                        Expr::Float(n) => {
+                        ExprKind::Float(n) => {
                             temp_instructions.push(format!("LD R0 #{}", -n));
                         }
-                        Expr::Int(n) => {
+                        ExprKind::Int(n) => {
                             temp_instructions.push(format!("LD R0 #{}", -n));
                         }
                         _ => {
-                            temp_instructions.push("ERROR".to_string());
+                            instructions.push("ERROR".to_string());
                             return;
                         }
                     }
                     match right {
-                        Expr::Variable(var) => {
+                        ExprKind::Variable(var) => {
                             temp_instructions.push(format!("LD R1 @{}", var));
                         }
-                        Expr::Int(n) => {
+                        ExprKind::Int(n) => {
                             temp_instructions.push(format!("LD R1 #{}", n));
                         }
-                        Expr::Float(n) => {
+                        ExprKind::Float(n) => {
                             temp_instructions.push(format!("LD R1 #{}", n));
                         }
                         _ => {
-                            temp_instructions.push("ERROR".to_string());
+                            // Drop what was generated for the left operand
+                            instructions.push("ERROR".to_string());
                             return;
                         }
                     }
                     if needs_float {
-                        if !is_float_expr(inner) {
-                            temp_instructions.push(format!("FL.i R0 R0"));
+                        if !is_float_expr(&inner.kind) {
+                            temp_instructions.push("FL.i R0 R0".to_string());
                         }
                         if !is_float_expr(right) {
-                            temp_instructions.push(format!("FL.i R1 R1"));
+                            temp_instructions.push("FL.i R1 R1".to_string());
                         }
                     }
                 }
                 _ => {
-                    temp_instructions.push("ERROR".to_string());
+                    instructions.push("ERROR".to_string());
                     return;
                 }
             };
 
             // Generate comparison instruction with correct operand order per ILOC spec
-            let op_code = match op.as_str() {
-                ">" => if needs_float { "GT.f" } else { "GT.i" },
-                "<" => if needs_float { "LT.f" } else { "LT.i" },
-                ">=" => if needs_float { "GE.f" } else { "GE.i" },
-                "<=" => if needs_float { "LE.f" } else { "LE.i" },
-                "==" => if needs_float { "EQ.f" } else { "EQ.i" },
-                "!=" => if needs_float { "NE.f" } else { "NE.i" },
-                _ => {
-                    temp_instructions.push("ERROR".to_string());
-                    return;
-                }
-            };
+            let op_code = comparison_opcode(*op, needs_float);
             temp_instructions.push(format!("{} R{} R{} R{}", op_code, r2, r0, r1));
             temp_instructions.push(format!("ST @print R{}", r2));
         }
-        Expr::ListAccess(var, index) => {
+        ExprKind::ListAccess(var, index) => {
             println!("DEBUG [Codegen]: Processing List access for var: {}", var);
-            match index.as_ref() {
-                Expr::Int(idx) => {
+            match &index.kind {
+                ExprKind::Int(idx) => {
                     println!("DEBUG [Codegen]: List access index: {}", idx);
                     let r0 = reg_alloc.get_next_reg();
                     let r1 = reg_alloc.get_next_reg();
@@ -734,17 +668,38 @@ pub fn generate_assembly(expr: &Expr) -> Vec<String> {
     let mut reg_alloc = RegisterAllocator::new();
     let mut symbol_table = HashMap::new();
     let mut instructions = Vec::new();
-    generate_instructions(expr, &mut reg_alloc, &mut symbol_table, &mut instructions);
+    generate_instructions(&expr.kind, &mut reg_alloc, &mut symbol_table, &mut instructions);
     println!("DEBUG [Codegen]: Final assembly: {:?}", instructions);
     instructions
 }
 
+/// The assembly of an expression preceded by a `// line:column-line:column`
+/// comment giving the source text it was generated from. The emulator skips
+/// comment lines, so the mapping doesn't change what runs.
+pub fn generate_mapped_assembly(expr: &Expr) -> Vec<String> {
+    let mut instructions = vec![mapping_comment(&expr.span)];
+    instructions.extend(generate_assembly(expr));
+    instructions
+}
+
+// The block of a line that failed to parse, mapped back to the whole line
+pub fn generate_mapped_error(line: &Span) -> Vec<String> {
+    vec![mapping_comment(line), "ERROR".to_string()]
+}
+
+fn mapping_comment(span: &Span) -> String {
+    format!(
+        "// {}:{}-{}:{}",
+        span.line, span.column, span.end_line, span.end_column
+    )
+}
+
 #[test]
 fn test_undefined_variable() {
-    let expr = Expr::Assignment(
+    let expr: Expr = ExprKind::Assignment(
         String::from("x"),
-        Box::new(Expr::Variable(String::from("y")))
-    );
+        Box::new(ExprKind::Variable(String::from("y")).into())
+    ).into();
     let expected = vec![
         "LD R0 @y",
         "ST @x R0"
@@ -755,11 +710,11 @@ fn test_undefined_variable() {
 #[test]
 fn test_variable_arithmetic() {
     // Test variable addition
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from("+"),
-        Box::new(Expr::Variable(String::from("y")))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        BinOp::Add,
+        Box::new(ExprKind::Variable(String::from("y")).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "LD R1 @y",
@@ -769,11 +724,11 @@ fn test_variable_arithmetic() {
     assert_eq!(generate_assembly(&expr), expected);
 
     // Test variable subtraction
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Variable(String::from("a"))),
-        String::from("-"),
-        Box::new(Expr::Variable(String::from("b")))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Variable(String::from("a")).into()),
+        BinOp::Sub,
+        Box::new(ExprKind::Variable(String::from("b")).into())
+    ).into();
     let expected = vec![
         "LD R0 @a",
         "LD R1 @b",
@@ -783,11 +738,11 @@ fn test_variable_arithmetic() {
     assert_eq!(generate_assembly(&expr), expected);
 
     // Test variable multiplication
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from("*"),
-        Box::new(Expr::Variable(String::from("y")))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        BinOp::Mul,
+        Box::new(ExprKind::Variable(String::from("y")).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "LD R1 @y",
@@ -800,11 +755,11 @@ fn test_variable_arithmetic() {
 #[test]
 fn test_variable_comparison() {
     // Test variable equality
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from("=="),
-        Box::new(Expr::Variable(String::from("y")))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        CmpOp::Eq,
+        Box::new(ExprKind::Variable(String::from("y")).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "LD R1 @y",
@@ -814,11 +769,11 @@ fn test_variable_comparison() {
     assert_eq!(generate_assembly(&expr), expected);
 
     // Test variable less than
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from("<"),
-        Box::new(Expr::Variable(String::from("y")))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        CmpOp::Lt,
+        Box::new(ExprKind::Variable(String::from("y")).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "LD R1 @y",
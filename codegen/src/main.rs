@@ -1,6 +1,4 @@
 use parser::Parser;
-use scanner;
-use codegen;
 
 const DEFAULT_OUTPUT_FILE: &str = "hiwkhao.asm";
 
@@ -22,20 +20,23 @@ fn main() {
     // Generate assembly for each parsed expression
     let mut result: Vec<String> = Vec::new();
 
-    for parsed_expr in parsed_results {
+    for (line, parsed_expr) in parsed_results {
         match parsed_expr {
             Ok(expr) => {
-                let instructions = codegen::generate_assembly(&expr);
+                let instructions = codegen::generate_mapped_assembly(&expr);
                 if !result.is_empty() {
                     result.push(String::new());
                 }
                 result.extend(instructions);
             }
-            Err(_) => {
-                if !result.is_empty() && result.last() != Some(&String::new()) {
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("{}", error);
+                }
+                if !result.is_empty() {
                     result.push(String::new());
                 }
-                result.push("ERROR".to_string());
+                result.extend(codegen::generate_mapped_error(&line));
             }
         }
     }
@@ -1,15 +1,15 @@
-use parser::Expr;
+use parser::{BinOp, CmpOp, Expr, ExprKind, UnOp};
 use parser;
 use scanner;
 use codegen;
 
 #[test]
 fn test_integer_addition() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(23)),
-        String::from("+"),
-        Box::new(Expr::Int(8))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(23).into()),
+        BinOp::Add,
+        Box::new(ExprKind::Int(8).into())
+    ).into();
     let expected = vec![
         "LD R0 #23",
         "LD R1 #8",
@@ -21,11 +21,11 @@ fn test_integer_addition() {
 
 #[test]
 fn test_float_multiplication() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Float(2.5)),
-        String::from("*"),
-        Box::new(Expr::Int(0))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Float(2.5).into()),
+        BinOp::Mul,
+        Box::new(ExprKind::Int(0).into())
+    ).into();
     let expected = vec![
         "LD R0 #2.5",
         "LD R1 #0",
@@ -38,10 +38,10 @@ fn test_float_multiplication() {
 
 #[test]
 fn test_variable_assignment() {
-    let expr = Expr::Assignment(
+    let expr: Expr = ExprKind::Assignment(
         String::from("x"),
-        Box::new(Expr::Int(5))
-    );
+        Box::new(ExprKind::Int(5).into())
+    ).into();
     let expected = vec![
         "LD R0 #5",
         "ST @x R0"
@@ -51,11 +51,11 @@ fn test_variable_assignment() {
 
 #[test]
 fn test_variable_multiplication() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(10)),
-        String::from("*"),
-        Box::new(Expr::Variable(String::from("x")))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(10).into()),
+        BinOp::Mul,
+        Box::new(ExprKind::Variable(String::from("x")).into())
+    ).into();
     let expected = vec![
         "LD R0 #10",
         "LD R1 @x",
@@ -67,11 +67,11 @@ fn test_variable_multiplication() {
 
 #[test]
 fn test_float_variable_multiplication() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Variable(String::from("z"))),
-        String::from("*"),
-        Box::new(Expr::Int(2))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Variable(String::from("z")).into()),
+        BinOp::Mul,
+        Box::new(ExprKind::Int(2).into())
+    ).into();
     let expected = vec![
         "LD R0 #2",
         "LD R1 @z",
@@ -83,11 +83,11 @@ fn test_float_variable_multiplication() {
 
 #[test]
 fn test_variable_addition() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Variable(String::from("y"))),
-        String::from("+"),
-        Box::new(Expr::Int(5))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Variable(String::from("y")).into()),
+        BinOp::Add,
+        Box::new(ExprKind::Int(5).into())
+    ).into();
     let expected = vec![
         "LD R0 #5",
         "LD R1 @y",
@@ -99,21 +99,21 @@ fn test_variable_addition() {
 
 #[test]
 fn test_invalid_operation() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(5)),
-        String::from("^"),
-        Box::new(Expr::Int(2))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(5).into()),
+        BinOp::Pow,
+        Box::new(ExprKind::Int(2).into())
+    ).into();
     let expected = vec!["ERROR"];
     assert_eq!(codegen::generate_assembly(&expr), expected);
 }
 
 #[test]
 fn test_undefined_variable() {
-    let expr = Expr::Assignment(
+    let expr: Expr = ExprKind::Assignment(
         String::from("x"),
-        Box::new(Expr::Variable(String::from("y")))
-    );
+        Box::new(ExprKind::Variable(String::from("y")).into())
+    ).into();
     let expected = vec![
         "LD R0 @y",
         "ST @x R0"
@@ -123,10 +123,10 @@ fn test_undefined_variable() {
 
 #[test]
 fn test_list_assignment() {
-    let expr = Expr::Assignment(
+    let expr: Expr = ExprKind::Assignment(
         String::from("x"),
-        Box::new(Expr::List(vec![]))
-    );
+        Box::new(ExprKind::List(0).into())
+    ).into();
     let expected = vec![
         "LD R0 #0",
         "LD R1 @x",
@@ -144,24 +144,13 @@ fn test_list_assignment() {
     assert_eq!(codegen::generate_assembly(&expr), expected);
 }
 
-#[test]
-fn test_list_element_access() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from("[]"),
-        Box::new(Expr::Int(1))
-    );
-    let expected = vec!["ERROR"];
-    assert_eq!(codegen::generate_assembly(&expr), expected);
-}
-
 #[test]
 fn test_float_int_multiplication() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Float(2.5)),
-        String::from("*"),
-        Box::new(Expr::Int(3))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Float(2.5).into()),
+        BinOp::Mul,
+        Box::new(ExprKind::Int(3).into())
+    ).into();
     let expected = vec![
         "LD R0 #2.5",
         "LD R1 #3",
@@ -174,11 +163,11 @@ fn test_float_int_multiplication() {
 
 #[test]
 fn test_float_int_subtraction() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Float(2.5)),
-        String::from("-"),
-        Box::new(Expr::Int(3))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Float(2.5).into()),
+        BinOp::Sub,
+        Box::new(ExprKind::Int(3).into())
+    ).into();
     let expected = vec![
         "LD R0 #2.5",
         "LD R1 #3",
@@ -191,33 +180,33 @@ fn test_float_int_subtraction() {
 
 #[test]
 fn test_invalid_list_operation() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::List(vec![])),
-        String::from("+"),
-        Box::new(Expr::Int(1))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::List(0).into()),
+        BinOp::Add,
+        Box::new(ExprKind::Int(1).into())
+    ).into();
     let expected = vec!["ERROR"];
     assert_eq!(codegen::generate_assembly(&expr), expected);
 }
 
 #[test]
 fn test_unsupported_operation() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(5)),
-        String::from("&"),
-        Box::new(Expr::Int(2))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(5).into()),
+        BinOp::IntDiv,
+        Box::new(ExprKind::Int(2).into())
+    ).into();
     let expected = vec!["ERROR"];
     assert_eq!(codegen::generate_assembly(&expr), expected);
 }
 
 #[test]
 fn test_int_float_addition() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(1)),
-        String::from("+"),
-        Box::new(Expr::Float(2.5))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(1).into()),
+        BinOp::Add,
+        Box::new(ExprKind::Float(2.5).into())
+    ).into();
     let expected = vec![
         "LD R0 #1",
         "FL.i R0 R0",
@@ -230,11 +219,11 @@ fn test_int_float_addition() {
 
 #[test]
 fn test_float_int_addition() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Float(2.5)),
-        String::from("+"),
-        Box::new(Expr::Int(1))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Float(2.5).into()),
+        BinOp::Add,
+        Box::new(ExprKind::Int(1).into())
+    ).into();
     let expected = vec![
         "LD R0 #2.5",
         "LD R1 #1",
@@ -247,11 +236,11 @@ fn test_float_int_addition() {
 
 #[test]
 fn test_integer_division() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(1)),
-        String::from("/"),
-        Box::new(Expr::Int(1))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(1).into()),
+        BinOp::Div,
+        Box::new(ExprKind::Int(1).into())
+    ).into();
     let expected = vec![
         "LD R0 #1",
         "LD R1 #1",
@@ -263,25 +252,25 @@ fn test_integer_division() {
 
 #[test]
 fn test_division_by_zero() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(1)),
-        String::from("/"),
-        Box::new(Expr::Int(0))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(1).into()),
+        BinOp::Div,
+        Box::new(ExprKind::Int(0).into())
+    ).into();
     let expected = vec!["ERROR"];
     assert_eq!(codegen::generate_assembly(&expr), expected);
 }
 
 #[test]
 fn test_division_with_negative() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(1)),
-        String::from("/"),
-        Box::new(Expr::UnaryOp(
-            String::from("-"),
-            Box::new(Expr::Int(1))
-        ))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(1).into()),
+        BinOp::Div,
+        Box::new(ExprKind::UnaryOp(
+            UnOp::Neg,
+            Box::new(ExprKind::Int(1).into())
+        ).into())
+    ).into();
     let expected = vec![
         "LD R0 #1",
         "LD R1 #-1",
@@ -293,14 +282,14 @@ fn test_division_with_negative() {
 
 #[test]
 fn test_division_with_negative_float() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(1)),
-        String::from("/"),
-        Box::new(Expr::UnaryOp(
-            String::from("-"),
-            Box::new(Expr::Float(1.1))
-        ))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(1).into()),
+        BinOp::Div,
+        Box::new(ExprKind::UnaryOp(
+            UnOp::Neg,
+            Box::new(ExprKind::Float(1.1).into())
+        ).into())
+    ).into();
     let expected = vec![
         "LD R0 #1",
         "FL.i R0 R0",
@@ -328,7 +317,7 @@ x[1]";
     let parsed_results = parser.parse_tokens(tokens);
 
     let mut result = Vec::new();
-    for expr in parsed_results {
+    for (_, expr) in parsed_results {
         match expr {
             Ok(expr) => {
                 let asm = codegen::generate_assembly(&expr);
@@ -409,10 +398,10 @@ x[1]";
 
 #[test]
 fn test_float_variable_assignment() {
-    let expr = Expr::Assignment(
+    let expr: Expr = ExprKind::Assignment(
         String::from("z"),
-        Box::new(Expr::Float(2.5))
-    );
+        Box::new(ExprKind::Float(2.5).into())
+    ).into();
     let expected = vec![
         "LD R0 #2.5",
         "ST @z R0"
@@ -422,11 +411,11 @@ fn test_float_variable_assignment() {
 
 #[test]
 fn test_greater_than_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from(">"),
-        Box::new(Expr::Int(0))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        CmpOp::Gt,
+        Box::new(ExprKind::Int(0).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "LD R1 #0",
@@ -438,11 +427,11 @@ fn test_greater_than_comparison() {
 
 #[test]
 fn test_less_than_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from("<"),
-        Box::new(Expr::Int(10))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        CmpOp::Lt,
+        Box::new(ExprKind::Int(10).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "LD R1 #10",
@@ -454,11 +443,11 @@ fn test_less_than_comparison() {
 
 #[test]
 fn test_equal_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from("=="),
-        Box::new(Expr::Int(5))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        CmpOp::Eq,
+        Box::new(ExprKind::Int(5).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "LD R1 #5",
@@ -470,11 +459,11 @@ fn test_equal_comparison() {
 
 #[test]
 fn test_not_equal_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from("!="),
-        Box::new(Expr::Int(6))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        CmpOp::Ne,
+        Box::new(ExprKind::Int(6).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "LD R1 #6",
@@ -488,11 +477,11 @@ fn test_not_equal_comparison() {
 
 #[test]
 fn test_greater_than_equal_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from(">="),
-        Box::new(Expr::Variable(String::from("y")))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        CmpOp::Ge,
+        Box::new(ExprKind::Variable(String::from("y")).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "LD R1 @y",
@@ -504,11 +493,11 @@ fn test_greater_than_equal_comparison() {
 
 #[test]
 fn test_less_than_equal_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from("<="),
-        Box::new(Expr::Variable(String::from("y")))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        CmpOp::Le,
+        Box::new(ExprKind::Variable(String::from("y")).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "LD R1 @y",
@@ -520,13 +509,14 @@ fn test_less_than_equal_comparison() {
 
 #[test]
 fn test_list_element_assignment_negative() {
-    let expr = Expr::Assignment(
-        String::from("mylist[0]"),
-        Box::new(Expr::UnaryOp(
-            String::from("-"),
-            Box::new(Expr::Int(10))
-        ))
-    );
+    let expr: Expr = ExprKind::ElementAssignment(
+        String::from("mylist"),
+        Box::new(ExprKind::Int(0).into()),
+        Box::new(ExprKind::UnaryOp(
+            UnOp::Neg,
+            Box::new(ExprKind::Int(10).into())
+        ).into())
+    ).into();
     let expected = vec![
         "LD R0 #-10",
         "LD R1 @mylist",
@@ -541,10 +531,11 @@ fn test_list_element_assignment_negative() {
 
 #[test]
 fn test_list_element_assignment_float() {
-    let expr = Expr::Assignment(
-        String::from("mylist[1]"),
-        Box::new(Expr::Float(10.5))
-    );
+    let expr: Expr = ExprKind::ElementAssignment(
+        String::from("mylist"),
+        Box::new(ExprKind::Int(1).into()),
+        Box::new(ExprKind::Float(10.5).into())
+    ).into();
     let expected = vec![
         "LD R0 #10.5",
         "LD R1 @mylist",
@@ -559,13 +550,14 @@ fn test_list_element_assignment_float() {
 
 #[test]
 fn test_list_element_assignment_from_list() {
-    let expr = Expr::Assignment(
-        String::from("mylist[0]"),
-        Box::new(Expr::ListAccess(
+    let expr: Expr = ExprKind::ElementAssignment(
+        String::from("mylist"),
+        Box::new(ExprKind::Int(0).into()),
+        Box::new(ExprKind::ListAccess(
             String::from("testa"),
-            Box::new(Expr::Int(1))
-        ))
-    );
+            Box::new(ExprKind::Int(1).into())
+        ).into())
+    ).into();
     let expected = vec![
         "LD R0 @testa",
         "LD R1 #1",
@@ -585,14 +577,14 @@ fn test_list_element_assignment_from_list() {
 
 #[test]
 fn test_negative_variable_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::UnaryOp(
-            String::from("-"),
-            Box::new(Expr::Variable(String::from("x")))
-        )),
-        String::from(">="),
-        Box::new(Expr::Variable(String::from("y")))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::UnaryOp(
+            UnOp::Neg,
+            Box::new(ExprKind::Variable(String::from("x")).into())
+        ).into()),
+        CmpOp::Ge,
+        Box::new(ExprKind::Variable(String::from("y")).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "NEG.i R0 R0",
@@ -603,16 +595,29 @@ fn test_negative_variable_comparison() {
     assert_eq!(codegen::generate_assembly(&expr), expected);
 }
 
+#[test]
+fn test_unsupported_comparison_is_an_error() {
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::UnaryOp(
+            UnOp::Neg,
+            Box::new(ExprKind::Variable(String::from("x")).into())
+        ).into()),
+        CmpOp::Lt,
+        Box::new(ExprKind::Str(String::from("a")).into())
+    ).into();
+    assert_eq!(codegen::generate_assembly(&expr), vec!["ERROR"]);
+}
+
 #[test]
 fn test_negative_float_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::UnaryOp(
-            String::from("-"),
-            Box::new(Expr::Float(10.5))
-        )),
-        String::from("<="),
-        Box::new(Expr::Int(5))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::UnaryOp(
+            UnOp::Neg,
+            Box::new(ExprKind::Float(10.5).into())
+        ).into()),
+        CmpOp::Le,
+        Box::new(ExprKind::Int(5).into())
+    ).into();
     let expected = vec![
         "LD R0 #-10.5",
         "LD R1 #5",
@@ -625,11 +630,11 @@ fn test_negative_float_comparison() {
 
 #[test]
 fn test_float_int_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::Int(2)),
-        String::from(">="),
-        Box::new(Expr::Float(1.5))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Int(2).into()),
+        CmpOp::Ge,
+        Box::new(ExprKind::Float(1.5).into())
+    ).into();
     let expected = vec![
         "LD R0 #2",
         "FL.i R0 R0",
@@ -642,11 +647,11 @@ fn test_float_int_comparison() {
 
 #[test]
 fn test_float_variable_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::Variable(String::from("x"))),
-        String::from(">="),
-        Box::new(Expr::Float(2.5))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Variable(String::from("x")).into()),
+        CmpOp::Ge,
+        Box::new(ExprKind::Float(2.5).into())
+    ).into();
     let expected = vec![
         "LD R0 @x",
         "FL.i R0 R0",
@@ -659,11 +664,11 @@ fn test_float_variable_comparison() {
 
 #[test]
 fn test_float_equality_comparison() {
-    let expr = Expr::Boolean(
-        Box::new(Expr::Float(2.5)),
-        String::from("=="),
-        Box::new(Expr::Variable(String::from("x")))
-    );
+    let expr: Expr = ExprKind::Boolean(
+        Box::new(ExprKind::Float(2.5).into()),
+        CmpOp::Eq,
+        Box::new(ExprKind::Variable(String::from("x")).into())
+    ).into();
     let expected = vec![
         "LD R0 #2.5",
         "LD R1 @x",
@@ -676,13 +681,13 @@ fn test_float_equality_comparison() {
 
 #[test]
 fn test_list_element_access_assignment() {
-    let expr = Expr::Assignment(
+    let expr: Expr = ExprKind::Assignment(
         String::from("x"),
-        Box::new(Expr::ListAccess(
+        Box::new(ExprKind::ListAccess(
             String::from("mylist"),
-            Box::new(Expr::Int(1))
-        ))
-    );
+            Box::new(ExprKind::Int(1).into())
+        ).into())
+    ).into();
     let expected = vec![
         "LD R0 @mylist",
         "LD R1 #1",
@@ -697,11 +702,11 @@ fn test_list_element_access_assignment() {
 
 #[test]
 fn test_simple_subtraction() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(15)),
-        String::from("-"),
-        Box::new(Expr::Int(7))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(15).into()),
+        BinOp::Sub,
+        Box::new(ExprKind::Int(7).into())
+    ).into();
     let expected = vec![
         "LD R0 #15",
         "LD R1 #7",
@@ -713,11 +718,11 @@ fn test_simple_subtraction() {
 
 #[test]
 fn test_float_float_multiplication() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Float(2.5)),
-        String::from("*"),
-        Box::new(Expr::Float(3.0))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Float(2.5).into()),
+        BinOp::Mul,
+        Box::new(ExprKind::Float(3.0).into())
+    ).into();
     let expected = vec![
         "LD R0 #2.5",
         "LD R1 #3.0",
@@ -729,11 +734,11 @@ fn test_float_float_multiplication() {
 
 #[test]
 fn test_float_float_addition() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Float(1.5)),
-        String::from("+"),
-        Box::new(Expr::Float(2.5))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Float(1.5).into()),
+        BinOp::Add,
+        Box::new(ExprKind::Float(2.5).into())
+    ).into();
     let expected = vec![
         "LD R0 #1.5",
         "LD R1 #2.5",
@@ -745,11 +750,11 @@ fn test_float_float_addition() {
 
 #[test]
 fn test_float_float_division() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Float(6.0)),
-        String::from("/"),
-        Box::new(Expr::Float(2.0))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Float(6.0).into()),
+        BinOp::Div,
+        Box::new(ExprKind::Float(2.0).into())
+    ).into();
     let expected = vec![
         "LD R0 #6.0",
         "LD R1 #2.0",
@@ -761,11 +766,11 @@ fn test_float_float_division() {
 
 #[test]
 fn test_negative_number_subtraction() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(-10)),
-        String::from("-"),
-        Box::new(Expr::Int(-1))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(-10).into()),
+        BinOp::Sub,
+        Box::new(ExprKind::Int(-1).into())
+    ).into();
     let expected = vec![
         "LD R0 #-10",
         "LD R1 #-1",
@@ -777,11 +782,11 @@ fn test_negative_number_subtraction() {
 
 #[test]
 fn test_negative_number_subtraction_positive() {
-    let expr = Expr::BinaryOp(
-        Box::new(Expr::Int(-10)),
-        String::from("-"),
-        Box::new(Expr::Int(1))
-    );
+    let expr: Expr = ExprKind::BinaryOp(
+        Box::new(ExprKind::Int(-10).into()),
+        BinOp::Sub,
+        Box::new(ExprKind::Int(1).into())
+    ).into();
     let expected = vec![
         "LD R0 #-10",
         "LD R1 #1",
@@ -789,4 +794,48 @@ fn test_negative_number_subtraction_positive() {
         "ST @print R2"
     ];
     assert_eq!(codegen::generate_assembly(&expr), expected);
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_string_print() {
+    let expr: Expr = ExprKind::Str(String::from("total:\t")).into();
+    let expected = vec![
+        "DATA @.str0 \"total:\\t\"",
+        "LD R0 @.str0",
+        "ST @print R0"
+    ];
+    assert_eq!(codegen::generate_assembly(&expr), expected);
+}
+
+#[test]
+fn test_string_assignment() {
+    let expr: Expr = ExprKind::Assignment(
+        String::from("label"),
+        Box::new(ExprKind::Str(String::from("sum")).into())
+    ).into();
+    let expected = vec![
+        "DATA @.str0 \"sum\"",
+        "LD R0 @.str0",
+        "ST @label R0"
+    ];
+    assert_eq!(codegen::generate_assembly(&expr), expected);
+}
+
+#[test]
+fn test_source_mapping_comment() {
+    let input = "x = 5\n  y = x * 2";
+    let mut parser = parser::Parser::new(vec![]);
+    let parsed = parser.parse_spanned_tokens(scanner::tokenize_spanned(input));
+    let asm = codegen::generate_mapped_assembly(parsed[1].1.as_ref().unwrap());
+    assert_eq!(asm[0], "// 2:3-2:12");
+    assert_eq!(asm[1..], codegen::generate_assembly(parsed[1].1.as_ref().unwrap())[..]);
+}
+
+#[test]
+fn test_error_mapping_comment() {
+    let input = "x = 5\n  y = 1 +";
+    let mut parser = parser::Parser::new(vec![]);
+    let parsed = parser.parse_spanned_tokens(scanner::tokenize_spanned(input));
+    assert!(parsed[1].1.is_err());
+    let asm = codegen::generate_mapped_error(&parsed[1].0);
+    assert_eq!(asm, vec!["// 2:3-2:10", "ERROR"]);
+}
@@ -5,6 +5,7 @@ use std::fmt;
 pub enum Value {
     Int(i32),
     Float(f64),
+    Str(String),
 }
 
 impl fmt::Display for Value {
@@ -12,6 +13,7 @@ impl fmt::Display for Value {
         match self {
             Value::Int(i) => write!(f, "{}", i),
             Value::Float(fl) => write!(f, "{}", fl),
+            Value::Str(s) => write!(f, "{}", s),
         }
     }
 }
@@ -21,6 +23,7 @@ impl Value {
         match self {
             Value::Int(i) => *i,
             Value::Float(f) => *f as i32,
+            Value::Str(_) => 0,
         }
     }
 
@@ -28,10 +31,39 @@ impl Value {
         match self {
             Value::Int(i) => *i as f64,
             Value::Float(f) => *f,
+            Value::Str(_) => 0.0,
         }
     }
 }
 
+// Reads a string constant written as a quoted literal with backslash escapes
+fn parse_string_literal(literal: &str) -> Option<String> {
+    let inner = literal.strip_prefix('"')?.strip_suffix('"')?;
+    let mut text = String::new();
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => text.push('\n'),
+            't' => text.push('\t'),
+            'r' => text.push('\r'),
+            '0' => text.push('\0'),
+            'u' => {
+                // \u{XXXX}
+                let code: String = chars.by_ref().skip(1).take_while(|c| *c != '}').collect();
+                text.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+            }
+            other => text.push(other),
+        }
+    }
+
+    Some(text)
+}
+
 pub struct VM {
     registers: HashMap<String, Value>,
     memory: Vec<u8>,
@@ -40,6 +72,8 @@ pub struct VM {
     program: Vec<String>,
     output: Vec<String>,
     next_addr: usize,
+    // Data area holding string constants and string variables by name
+    strings: HashMap<String, String>,
 }
 
 impl VM {
@@ -52,6 +86,7 @@ impl VM {
             program: Vec::new(),
             output: Vec::new(),
             next_addr: 0,
+            strings: HashMap::new(),
         }
     }
 
@@ -77,6 +112,19 @@ impl VM {
     fn execute(&mut self, instruction: &str) {
         let parts: Vec<&str> = instruction.split_whitespace().collect();
         match parts[0] {
+            "DATA" => {
+                // DATA @label "text"
+                let label = parts[1].trim_start_matches('@').to_string();
+                let literal = instruction
+                    .trim()
+                    .splitn(3, char::is_whitespace)
+                    .nth(2)
+                    .unwrap_or("")
+                    .trim();
+                let text = parse_string_literal(literal)
+                    .unwrap_or_else(|| panic!("Invalid string constant: {}", literal));
+                self.strings.insert(label, text);
+            }
             "LD" => {
                 // LD R1 #123 (immediate)
                 // LD R1 @var (memory)
@@ -96,6 +144,9 @@ impl VM {
                         let val: i32 = value.parse().unwrap();
                         self.registers.insert(dst_reg, Value::Int(val));
                     }
+                } else if let Some(text) = src.strip_prefix('@').and_then(|name| self.strings.get(name)) {
+                    // String in the data area
+                    self.registers.insert(dst_reg, Value::Str(text.clone()));
                 } else if src.starts_with('@') {
                     // Memory location
                     let var_name = &src[1..];
@@ -154,8 +205,14 @@ impl VM {
                         println!("{}", output);
                         self.output.push(output);
                     }
+                } else if let (Some(var_name), Some(Value::Str(text))) =
+                    (dst.strip_prefix('@'), self.registers.get(src_reg))
+                {
+                    // Strings live in the data area instead of memory
+                    self.strings.insert(var_name.to_string(), text.clone());
                 } else if dst.starts_with('@') {
                     let var_name = &dst[1..];
+                    self.strings.remove(var_name);
                     let addr = if let Ok(numeric_addr) = var_name.parse::<usize>() {
                         numeric_addr
                     } else {
@@ -194,6 +251,7 @@ impl VM {
                                     self.memory[addr..addr + bytes.len()].copy_from_slice(&bytes);
                                 }
                             }
+                            Value::Str(_) => unreachable!(),
                         }
                     }
                 } else {
@@ -215,6 +273,7 @@ impl VM {
                                             self.memory[addr..addr + bytes.len()].copy_from_slice(&bytes);
                                         }
                                     }
+                                    Value::Str(_) => panic!("Cannot store a string in a list"),
                                 }
                             }
                         }
@@ -309,6 +368,7 @@ impl VM {
                     let float_val = match value {
                         Value::Int(i) => Value::Float(*i as f64),
                         Value::Float(f) => Value::Float(*f),
+                        Value::Str(_) => panic!("Cannot convert a string to a float"),
                     };
                     self.registers.insert(dst_reg, float_val);
                 }
@@ -496,6 +556,7 @@ impl VM {
                     let result = match value {
                         Value::Int(i) => Value::Int(-*i),
                         Value::Float(f) => Value::Int(-(*f as i32)),
+                        Value::Str(_) => panic!("Cannot negate a string"),
                     };
                     self.registers.insert(dst_reg, result);
                 }
@@ -509,6 +570,7 @@ impl VM {
                     let result = match value {
                         Value::Int(i) => Value::Float(-(*i as f64)),
                         Value::Float(f) => Value::Float(-*f),
+                        Value::Str(_) => panic!("Cannot negate a string"),
                     };
                     self.registers.insert(dst_reg, result);
                 }
@@ -539,6 +601,7 @@ impl VM {
         let bytes = match value {
             Value::Int(i) => i.to_le_bytes(),
             Value::Float(f) => (*f as i32).to_le_bytes(),
+            Value::Str(_) => panic!("Cannot store a string in memory"),
         };
         self.memory[addr..addr + 4].copy_from_slice(&bytes);
     }
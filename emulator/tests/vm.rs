@@ -0,0 +1,68 @@
+use emulator::vm::VM;
+
+fn run(program: &[&str]) -> Vec<String> {
+    let mut vm = VM::new(1024);
+    vm.load_program(program.iter().map(|line| line.to_string()).collect());
+    vm.run();
+    vm.get_output().to_vec()
+}
+
+#[test]
+fn print_string_constant() {
+    // `"total:\t"`
+    let program = ["DATA @.str0 \"total:\\t\"", "LD R0 @.str0", "ST @print R0"];
+    assert_eq!(run(&program), vec!["total:\t"]);
+}
+
+#[test]
+fn string_escapes() {
+    let program = [
+        "DATA @.str0 \"a \\\"b\\\" \\\\ \\u{e9}\\n\"",
+        "LD R0 @.str0",
+        "ST @print R0",
+    ];
+    assert_eq!(run(&program), vec!["a \"b\" \\ \u{e9}\n"]);
+}
+
+#[test]
+fn string_variable() {
+    // `s = "ab"` then `s`
+    let program = [
+        "DATA @.str0 \"ab\"",
+        "LD R0 @.str0",
+        "ST @s R0",
+        "LD R0 @s",
+        "ST @print R0",
+    ];
+    assert_eq!(run(&program), vec!["ab"]);
+}
+
+#[test]
+fn constants_do_not_shadow_variables() {
+    // `str0 = 5`, `s = "x"`, `str0`
+    let program = [
+        "LD R0 #5",
+        "ST @str0 R0",
+        "DATA @.str0 \"x\"",
+        "LD R0 @.str0",
+        "ST @s R0",
+        "LD R0 @str0",
+        "ST @print R0",
+    ];
+    assert_eq!(run(&program), vec!["5"]);
+}
+
+#[test]
+fn number_replaces_string_variable() {
+    // `s = "x"`, `s = 2`, `s`
+    let program = [
+        "DATA @.str0 \"x\"",
+        "LD R0 @.str0",
+        "ST @s R0",
+        "LD R0 #2",
+        "ST @s R0",
+        "LD R0 @s",
+        "ST @print R0",
+    ];
+    assert_eq!(run(&program), vec!["2"]);
+}
@@ -0,0 +1,77 @@
+use preprocessor::bnf::{Grammar, Symbol};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// The productions `Parser::build` turns into expressions, as a nonterminal
+// and its symbols. `_` matches any nonterminal or symbol and `<_>` any
+// nonterminal symbol. A production of another shape fails the build rather
+// than panicking while a line is parsed.
+const SHAPES: &[(&str, &[&str])] = &[
+    ("_", &["<_>"]),
+    ("_", &["INT"]),
+    ("_", &["REAL"]),
+    ("_", &["STRING"]),
+    ("_", &["VAR"]),
+    ("atom", &["\"(\"", "_", "\")\""]),
+    ("atom", &["\"-\"", "_"]),
+    ("assignment", &["VAR", "\"=\"", "_"]),
+    ("assignment", &["<index_access>", "\"=\"", "_"]),
+    ("index_access", &["VAR", "\"[\"", "INT", "\"]\""]),
+    ("list_access", &["_", "\"[\"", "INT", "\"]\""]),
+    ("boolean_expr", &["_", "<comparison_op>", "_"]),
+    ("comparison_op", &["\"==\""]),
+    ("comparison_op", &["\"!=\""]),
+    ("comparison_op", &["\"<\""]),
+    ("comparison_op", &["\">\""]),
+    ("comparison_op", &["\"<=\""]),
+    ("comparison_op", &["\">=\""]),
+    ("_", &["_", "\"+\"", "_"]),
+    ("_", &["_", "\"-\"", "_"]),
+    ("_", &["_", "\"*\"", "_"]),
+    ("_", &["_", "\"/\"", "_"]),
+    ("_", &["_", "\"//\"", "_"]),
+    ("_", &["_", "\"^\"", "_"]),
+];
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let root = Path::new(&manifest_dir).join("..");
+    let grammar_file = root.join("hiwkhao.grammar");
+    let lex_file = root.join("hiwkhao.lex");
+    println!("cargo:rerun-if-changed={}", grammar_file.display());
+    println!("cargo:rerun-if-changed={}", lex_file.display());
+
+    let rust_code = preprocessor::generate_parser_from_files(&grammar_file, &lex_file)
+        .unwrap_or_else(|err| panic!("{}", err));
+
+    let grammar = preprocessor::read_grammar(&grammar_file).unwrap_or_else(|err| panic!("{}", err));
+    for (index, production) in grammar.productions.iter().enumerate() {
+        if !SHAPES.iter().any(|shape| matches(&grammar, index, shape)) {
+            panic!(
+                "{}:{}: `{}` has no case in `Parser::build`",
+                grammar_file.display(),
+                production.line,
+                grammar.production_text(index)
+            );
+        }
+    }
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("grammar.rs"), rust_code)
+        .expect("Failed to write the generated Rust file");
+}
+
+fn matches(grammar: &Grammar, production: usize, (nonterminal, symbols): &(&str, &[&str])) -> bool {
+    let production = &grammar.productions[production];
+    (*nonterminal == "_" || grammar.nonterminals[production.lhs] == *nonterminal)
+        && production.rhs.len() == symbols.len()
+        && production
+            .rhs
+            .iter()
+            .zip(symbols.iter())
+            .all(|(&symbol, &pattern)| match (pattern, symbol) {
+                ("_", _) | ("<_>", Symbol::NonTerminal(_)) => true,
+                _ => grammar.symbol_name(symbol) == pattern,
+            })
+}
@@ -0,0 +1,180 @@
+//! Leftmost derivations and concrete parse trees of the lines of a program,
+//! named after the nonterminals of `hiwkhao.grammar`.
+//!
+//! Expanding the nodes of the tree the generated parser builds in preorder
+//! gives the leftmost derivation, so each step rewrites the leftmost
+//! nonterminal by the production the parser reduced it with.
+
+use crate::grammar::{self, Node, SyntaxError, TERMINALS};
+use crate::Parser;
+use scanner::grammar::Token;
+use scanner::SpannedToken;
+
+/// The derivation and parse tree of one source line, or the syntax error
+/// that stopped it.
+pub struct LineDerivation {
+    pub line: usize,
+    pub tokens: Vec<Token>,
+    pub tree: Result<Node, SyntaxError>,
+}
+
+impl LineDerivation {
+    /// Each sentential form from `<program>` to the tokens of the line,
+    /// written with `=>` before every form but the first.
+    pub fn derivation_text(&self) -> String {
+        match &self.tree {
+            Ok(tree) => {
+                let forms: Vec<String> = leftmost_derivation(tree, &self.tokens);
+                let mut text = String::new();
+                for (index, form) in forms.iter().enumerate() {
+                    let arrow = if index == 0 { "   " } else { "=> " };
+                    text.push_str(&format!("{}{}\n", arrow, form));
+                }
+                text
+            }
+            Err(error) => format!("{}\n", self.error_text(error)),
+        }
+    }
+
+    /// The parse tree indented by two spaces per level.
+    pub fn tree_text(&self) -> String {
+        match &self.tree {
+            Ok(tree) => tree_text(tree, &self.tokens),
+            Err(error) => format!("{}\n", self.error_text(error)),
+        }
+    }
+
+    fn error_text(&self, error: &SyntaxError) -> String {
+        let found = self
+            .tokens
+            .get(error.position)
+            .map_or("the end of the line".to_string(), |token| {
+                format!("`{}`", token)
+            });
+        format!(
+            "no derivation: syntax error at {}, expected {}",
+            found,
+            error.expected.join(" ")
+        )
+    }
+}
+
+/// Parses every line of the tokens on its own, as the parser does.
+pub fn derive_lines(tokens: Vec<SpannedToken>) -> Vec<LineDerivation> {
+    Parser::split_into_lines(tokens)
+        .into_iter()
+        .map(|(tokens, spans)| LineDerivation {
+            line: spans[0].line,
+            tree: grammar::parse(&tokens),
+            tokens,
+        })
+        .collect()
+}
+
+/// The sentential forms of the leftmost derivation of a tree, tokens
+/// written as their source text.
+pub fn leftmost_derivation(tree: &Node, tokens: &[Token]) -> Vec<String> {
+    let mut form: Vec<&Node> = vec![tree];
+    let mut forms = vec![form_text(&form, tokens)];
+
+    while let Some(index) = form
+        .iter()
+        .position(|node| matches!(node, Node::Branch { .. }))
+    {
+        let Node::Branch { children, .. } = form[index] else {
+            unreachable!("the position of a branch was found")
+        };
+        form.splice(index..=index, children.iter());
+        forms.push(form_text(&form, tokens));
+    }
+
+    forms
+}
+
+/// The tree with one node per line, nonterminals as `<name>` and tokens as
+/// their terminal with the source text, e.g. `INT 10`.
+pub fn tree_text(tree: &Node, tokens: &[Token]) -> String {
+    let mut text = String::new();
+    let mut work = vec![(tree, 0)];
+    while let Some((node, depth)) = work.pop() {
+        text.push_str(&format!("{}{}\n", "  ".repeat(depth), label(node, tokens)));
+        if let Node::Branch { children, .. } = node {
+            work.extend(children.iter().rev().map(|child| (child, depth + 1)));
+        }
+    }
+    text
+}
+
+/// The trees of the lines as one Graphviz DOT graph with a cluster per
+/// line. Lines that don't parse are left out.
+pub fn trees_to_dot(lines: &[LineDerivation]) -> String {
+    let mut dot =
+        String::from("digraph \"parse trees\" {\n    node [shape=plaintext];\n    ordering=out;\n");
+    for line in lines {
+        let Ok(tree) = &line.tree else {
+            continue;
+        };
+        dot.push_str(&format!(
+            "    subgraph cluster_{} {{\n        label=\"line {}\";\n",
+            line.line, line.line
+        ));
+
+        // Nodes are numbered in preorder within the line
+        let mut next = 0;
+        let mut work = vec![(tree, None)];
+        while let Some((node, parent)) = work.pop() {
+            let id = format!("l{}n{}", line.line, next);
+            next += 1;
+            let shape = match node {
+                Node::Token(_) => ", shape=box",
+                Node::Branch { .. } => "",
+            };
+            dot.push_str(&format!(
+                "        {} [label=\"{}\"{}];\n",
+                id,
+                escape(&label(node, &line.tokens)),
+                shape
+            ));
+            if let Some(parent) = parent {
+                dot.push_str(&format!("        {} -> {};\n", parent, id));
+            }
+            if let Node::Branch { children, .. } = node {
+                work.extend(children.iter().rev().map(|child| (child, Some(id.clone()))));
+            }
+        }
+        dot.push_str("    }\n");
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn form_text(form: &[&Node], tokens: &[Token]) -> String {
+    let symbols: Vec<String> = form
+        .iter()
+        .map(|node| match node {
+            Node::Token(index) => tokens[*index].to_string(),
+            Node::Branch { nonterminal, .. } => nonterminal.to_string(),
+        })
+        .collect();
+    symbols.join(" ")
+}
+
+fn label(node: &Node, tokens: &[Token]) -> String {
+    match node {
+        Node::Branch { nonterminal, .. } => nonterminal.to_string(),
+        Node::Token(index) => {
+            let token = &tokens[*index];
+            let terminal = grammar::terminal(token).map_or("?", |terminal| TERMINALS[terminal]);
+            // Quoted terminals already are their text
+            if terminal.starts_with('"') {
+                terminal.to_string()
+            } else {
+                format!("{} {}", terminal, token)
+            }
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,3 @@
+// The parser tables are generated from hiwkhao.grammar by build.rs on every build.
+// Run `cargo run -p preprocessor parser` to print the generated code.
+include!(concat!(env!("OUT_DIR"), "/grammar.rs"));
@@ -1,24 +1,56 @@
+use grammar::{Node, NonTerminal, SyntaxError};
 use logos::Lexer;
 use scanner::grammar::Token;
-use std::collections::HashMap;
+use scanner::{LexError, Span, SpannedToken};
+use sema::Analyzer;
+use std::fmt;
 
+pub use operator::{Associativity, BinOp, CmpOp, UnOp};
+
+// Tokens that must be read after a skipped token before another syntax error
+// in the line is reported
+const RECOVERY_TOKENS: usize = 2;
+
+pub mod derivation;
+pub mod grammar;
+pub mod operator;
+pub mod sema;
 pub mod symbol_table;
+pub mod trace;
 
 // Core data structures
+
+/// An expression and where in the source it was parsed from.
+#[derive(Debug, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    /// From the first to the last token of the expression, parentheses
+    /// around it excluded. Expressions built by hand have the default span.
+    pub span: Span,
+}
+
 #[derive(Debug, Clone)]
-pub enum Expr {
+pub enum ExprKind {
     Int(i64),
     Float(f64),
+    Str(String),
     Variable(String),
-    BinaryOp(Box<Expr>, String, Box<Expr>),
+    BinaryOp(Box<Expr>, BinOp, Box<Expr>),
     Assignment(String, Box<Expr>),
-    Boolean(Box<Expr>, String, Box<Expr>),
-    // List can be a list of numbers (real/int)
-    List(Vec<f64>),
+    /// `name[index] = value`, assigning an element of a list.
+    ElementAssignment(String, Box<Expr>, Box<Expr>),
+    Boolean(Box<Expr>, CmpOp, Box<Expr>),
+    /// `list[length]`, a new list of `length` zeros.
+    List(usize),
     ListAccess(String, Box<Expr>),
-    UnaryOp(String, Box<Expr>),
+    UnaryOp(UnOp, Box<Expr>),
 }
 
+/// The outcome of one source line: the span from its first token to its last,
+/// whose `line` is the line number, and the expression of its statement or
+/// every error found in the line.
+pub type LineResult = (Span, Result<Expr, Vec<ParseError>>);
+
 #[derive(Debug, Clone)]
 pub struct Position {
     pub line: usize,
@@ -39,36 +71,99 @@ pub enum ParseError {
     IndexOutOfRange(Position, usize),
     DivisionByZero(Position),
     MissingIndex(Position),
-    TokenizeError,
+    /// A keyword used as a variable name.
+    ReservedWord(String, Position),
+    /// A value of the wrong type, e.g. a string in arithmetic, with what was
+    /// expected and found.
+    TypeError(String, Span),
+    TokenizeError(LexError),
 }
 
-// Improved expression string representation
 impl Expr {
-    fn to_string(&self) -> String {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Expr { kind, span }
+    }
+}
+
+impl From<ExprKind> for Expr {
+    fn from(kind: ExprKind) -> Self {
+        Expr::new(kind, Span::default())
+    }
+}
+
+// Improved expression string representation
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ExprKind::Int(n) => write!(f, "{}", n),
+            ExprKind::Float(n) => write!(f, "{:.1}", n),
+            ExprKind::Str(s) => write!(f, "{:?}", s),
+            ExprKind::Variable(name) => write!(f, "{}", name),
+            ExprKind::BinaryOp(left, op, right) => write!(f, "({}{}{})", left, op, right),
+            ExprKind::UnaryOp(op, expr) => write!(f, "({}{})", op, expr),
+            ExprKind::Assignment(var, expr) => write!(f, "({}={})", var, expr),
+            ExprKind::ElementAssignment(var, index, expr) => {
+                write!(f, "({}[({})]={})", var, index, expr)
+            }
+            ExprKind::Boolean(left, op, right) => write!(f, "({}{}{})", left, op, right),
+            ExprKind::List(length) => write!(f, "(list[({})])", length),
+            ExprKind::ListAccess(var, idx) => write!(f, "({}[({})])", var, idx),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Expr::Int(n) => n.to_string(),
-            Expr::Float(n) => format!("{:.1}", n),
-            Expr::Variable(name) => name.clone(),
-            Expr::BinaryOp(left, op, right) => {
-                format!("({}{}{}", left.to_string(), op, right.to_string() + ")")
+            ParseError::UndefinedVariable(var, pos) => {
+                write!(
+                    f,
+                    "Undefined variable {} at line {}, pos {}",
+                    var, pos.line, pos.column
+                )
             }
-            Expr::UnaryOp(op, expr) => format!("({}{}", op, expr.to_string() + ")"),
-            Expr::Assignment(var, expr) => {
-                if var.contains('[') {
-                    // Extract the variable name and index
-                    let parts: Vec<&str> = var.split('[').collect();
-                    let var_name = parts[0];
-                    let index = parts[1].trim_end_matches(']');
-                    format!("({}[({})]={}", var_name, index, expr.to_string() + ")")
-                } else {
-                    format!("({}={})", var, expr.to_string())
-                }
+            ParseError::SyntaxError(pos) => {
+                write!(f, "SyntaxError at line {}, pos {}", pos.line, pos.column)
             }
-            Expr::Boolean(left, op, right) => {
-                format!("({}{}{}", left.to_string(), op, right.to_string() + ")")
+            ParseError::InvalidAtom(pos) => {
+                write!(f, "Invalid atom at line {}, pos {}", pos.line, pos.column)
+            }
+            ParseError::IndexOutOfRange(pos, index) => {
+                write!(
+                    f,
+                    "IndexOutOfRange at line {}, pos {}, index {}",
+                    pos.line, pos.column, index
+                )
             }
-            Expr::List(lst) => format!("(list[({})])", lst.len()),
-            Expr::ListAccess(var, idx) => format!("({}[({})])", var, idx.to_string()),
+            ParseError::DivisionByZero(pos) => {
+                write!(
+                    f,
+                    "Division by zero at line {}, pos {}",
+                    pos.line, pos.column
+                )
+            }
+            ParseError::MissingIndex(pos) => {
+                write!(
+                    f,
+                    "Missing index expression at line {}, pos {}",
+                    pos.line, pos.column
+                )
+            }
+            ParseError::ReservedWord(word, pos) => {
+                write!(
+                    f,
+                    "Reserved word {} can't be a variable at line {}, pos {}",
+                    word, pos.line, pos.column
+                )
+            }
+            ParseError::TypeError(message, span) => {
+                write!(
+                    f,
+                    "TypeError at line {}, pos {}, {}",
+                    span.line, span.column, message
+                )
+            }
+            ParseError::TokenizeError(error) => write!(f, "{}", error),
         }
     }
 }
@@ -77,860 +172,351 @@ impl Expr {
 pub struct TokenInfo;
 
 impl TokenInfo {
-    pub fn token_length(token: &Token) -> usize {
-        match token {
-            Token::VAR(name) => name.len(),
-            Token::INT(n) | Token::REAL(n) => n.len(),
-            Token::EQ | Token::NE | Token::LE | Token::GE | Token::INTDIV => 2,
-            Token::LIST => 4,
-            Token::ADD
-            | Token::SUB
-            | Token::MUL
-            | Token::DIV
-            | Token::POW
-            | Token::LPAREN
-            | Token::RPAREN
-            | Token::LBRACKET
-            | Token::RBRACKET
-            | Token::ASSIGN
-            | Token::GT
-            | Token::LT
-            | Token::NEWLINE
-            | Token::WHITESPACE
-            | Token::ERR => 1,
-        }
+    pub fn token_length(token: &SpannedToken) -> usize {
+        token.token_length()
     }
 }
 
 // Parser implementation
 pub struct Parser {
     tokens: Vec<Token>,
-    pos: usize,
-    variables: HashMap<String, Expr>,
     current_line: usize,
-    current_column: usize,
-    token_positions: Vec<usize>,
+    token_spans: Vec<Span>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
         Self {
             tokens,
-            pos: 0,
-            variables: HashMap::new(),
             current_line: 1,
-            current_column: 1,
-            token_positions: Vec::new(),
+            token_spans: Vec::new(),
         }
     }
 
-    // Token navigation methods
-    fn peek(&self) -> Option<&Token> {
-        self.tokens.get(self.pos)
-    }
-
-    fn consume(&mut self) -> Option<Token> {
-        if self.pos < self.tokens.len() {
-            let token = self.tokens[self.pos].clone();
-            self.pos += 1;
-            self.current_column += TokenInfo::token_length(&token);
-
-            if self.pos < self.tokens.len() {
-                self.current_column += 1;
-            }
-
-            Some(token)
-        } else {
-            None
-        }
-    }
-
-    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
-        match self.peek() {
-            Some(token) if token == &expected => {
-                self.consume();
-                Ok(())
-            }
-            _ => Err(ParseError::SyntaxError(self.get_current_position())),
-        }
-    }
-
-    fn get_current_position(&self) -> Position {
-        Position {
-            line: self.current_line,
-            column: if self.pos > 0 && self.pos <= self.token_positions.len() {
-                self.token_positions[self.pos - 1]
-            } else if !self.token_positions.is_empty() {
-                self.token_positions[0]
-            } else {
-                self.current_column
+    // Position of the token at `index`, or of the first token of the line
+    fn position(&self, index: usize) -> Position {
+        match self.token_spans.get(index).or(self.token_spans.first()) {
+            Some(span) => Position {
+                line: span.line,
+                column: span.column,
+            },
+            None => Position {
+                line: self.current_line,
+                column: 1,
             },
         }
     }
 
-    // Parsing methods
-    pub fn parse(&mut self) -> Result<Expr, ParseError> {
-        self.parse_calculation()
-    }
-
-    fn parse_calculation(&mut self) -> Result<Expr, ParseError> {
-        println!("DEBUG [Parser]: Starting parse_calculation");
-        match self.peek() {
-            Some(Token::VAR(_)) => {
-                // Look ahead to see if this is a list element assignment
-                let var_name = match self.peek() {
-                    Some(Token::VAR(name)) => name.clone(),
-                    _ => unreachable!(),
-                };
-                
-                // Save current position in case we need to backtrack
-                let current_pos = self.pos;
-                
-                // Try to parse as list element assignment
-                self.consume(); // Consume VAR
-                if let Some(Token::LBRACKET) = self.peek() {
-                    self.consume(); // Consume LBRACKET
-                    if let Some(Token::INT(_)) = self.peek() {
-                        let index_expr = self.parse_expression()?;
-                        self.expect(Token::RBRACKET)?;
-                        if let Some(Token::ASSIGN) = self.peek() {
-                            self.consume(); // Consume ASSIGN
-                            let value = self.parse_expression()?;
-                            return Ok(Expr::Assignment(
-                                format!("{}[{}]", var_name, match index_expr {
-                                    Expr::Int(i) => i.to_string(),
-                                    _ => return Err(ParseError::SyntaxError(self.get_current_position())),
-                                }),
-                                Box::new(value),
-                            ));
-                        }
-                    }
-                }
-                
-                // If not a list element assignment, reset position and try normal assignment
-                self.pos = current_pos;
-                if self.tokens.get(self.pos + 1) == Some(&Token::ASSIGN) {
-                    println!("DEBUG [Parser]: Found assignment expression");
-                    self.parse_assignment()
-                } else {
-                    let expr = self.parse_boolean()?;
-                    if self.pos < self.tokens.len() {
-                        match self.tokens[self.pos] {
-                            Token::EQ | Token::NE | Token::GT | Token::LT | Token::GE | Token::LE => {
-                                println!("DEBUG [Parser]: Found comparison operator");
-                                let op = match self.consume().unwrap() {
-                                    Token::EQ => "==",
-                                    Token::NE => "!=",
-                                    Token::GT => ">",
-                                    Token::LT => "<",
-                                    Token::GE => ">=",
-                                    Token::LE => "<=",
-                                    _ => unreachable!(),
-                                };
-                                println!("DEBUG [Parser]: Comparison operator: {}", op);
-                                let right = self.parse_expression()?;
-                                Ok(Expr::Boolean(
-                                    Box::new(expr),
-                                    op.to_string(),
-                                    Box::new(right),
-                                ))
-                            }
-                            _ => Ok(expr),
-                        }
-                    } else {
-                        Ok(expr)
-                    }
-                }
-            }
-            _ => {
-                let expr = self.parse_boolean()?;
-                if self.pos < self.tokens.len() {
-                    match self.tokens[self.pos] {
-                        Token::EQ | Token::NE | Token::GT | Token::LT | Token::GE | Token::LE => {
-                            println!("DEBUG [Parser]: Found comparison operator");
-                            let op = match self.consume().unwrap() {
-                                Token::EQ => "==",
-                                Token::NE => "!=",
-                                Token::GT => ">",
-                                Token::LT => "<",
-                                Token::GE => ">=",
-                                Token::LE => "<=",
-                                _ => unreachable!(),
-                            };
-                            println!("DEBUG [Parser]: Comparison operator: {}", op);
-                            let right = self.parse_expression()?;
-                            Ok(Expr::Boolean(
-                                Box::new(expr),
-                                op.to_string(),
-                                Box::new(right),
-                            ))
-                        }
-                        _ => Ok(expr),
-                    }
-                } else {
-                    Ok(expr)
-                }
-            }
-        }
-    }
-
-    fn parse_assignment(&mut self) -> Result<Expr, ParseError> {
-        println!("DEBUG [Parser]: Starting parse_assignment");
-        let name = match self.consume() {
-            Some(Token::VAR(name)) => name,
-            _ => {
-                println!("DEBUG [Parser]: Error - Expected variable name in assignment");
-                return Err(ParseError::SyntaxError(self.get_current_position()));
-            }
-        };
-
-        self.expect(Token::ASSIGN)?;
-
-        let expr = self.parse_expression()?;
-        println!("DEBUG [Parser]: Parsed assignment expression: {} = {:?}", name, expr);
-
-        // Validate and store the assignment
-        match &expr {
-            Expr::List(lst) if lst.is_empty() => {
-                println!("DEBUG [Parser]: Error - Empty list in assignment");
-                return Err(ParseError::SyntaxError(self.get_current_position()));
-            }
-            _ => {
-                println!("DEBUG [Parser]: Storing variable in symbol table: {}", name);
-                self.variables.insert(name.clone(), expr.clone());
-            }
+    // The expression of a node, spanning from its first to its last token
+    fn expr(&self, kind: ExprKind, node: &Node) -> Expr {
+        let tokens = node.tokens();
+        let span = match (
+            self.token_spans.get(tokens.start),
+            self.token_spans.get(tokens.end.saturating_sub(1)),
+        ) {
+            (Some(first), Some(last)) => Span {
+                start: first.start,
+                end: last.end,
+                line: first.line,
+                column: first.column,
+                end_line: last.end_line,
+                end_column: last.end_column,
+            },
+            _ => Span::default(),
         };
-
-        Ok(Expr::Assignment(name, Box::new(expr)))
+        Expr::new(kind, span)
     }
 
-    fn parse_boolean(&mut self) -> Result<Expr, ParseError> {
-        println!("DEBUG [Parser]: Starting parse_boolean");
-        let is_negative = if let Some(Token::SUB) = self.peek() {
-            println!("DEBUG [Parser]: Found negative expression");
-            self.consume();
-            true
-        } else {
-            false
-        };
-
-        let mut left = self.parse_expression()?;
-        println!("DEBUG [Parser]: Parsed left side of boolean: {:?}", left);
-
-        if is_negative {
-            println!("DEBUG [Parser]: Applying negative to expression");
-            left = match left {
-                Expr::Variable(name) => {
-                    Expr::UnaryOp("-".to_string(), Box::new(Expr::Variable(name)))
-                }
-                Expr::Boolean(l, op, r) => match *l {
-                    Expr::Variable(name) => Expr::Boolean(
-                        Box::new(Expr::UnaryOp(
-                            "-".to_string(),
-                            Box::new(Expr::Variable(name)),
-                        )),
-                        op,
-                        r,
-                    ),
-                    _ => Expr::Boolean(l, op, r),
-                },
-                _ => Expr::UnaryOp("-".to_string(), Box::new(left)),
+    /// Parses the tokens of the current line with the parser generated from
+    /// `hiwkhao.grammar` and builds the expression of its statement.
+    ///
+    /// A token the grammar doesn't allow is reported and skipped, and the line
+    /// is parsed again without it, so every syntax error of the line is found
+    /// in one pass. The expression is only syntax: names, types and values are
+    /// checked by [`sema::Analyzer`].
+    pub fn parse(&mut self) -> Result<Expr, Vec<ParseError>> {
+        let mut errors = Vec::new();
+        // Indices of the tokens that haven't been skipped
+        let mut kept: Vec<usize> = (0..self.tokens.len()).collect();
+        let mut last_skipped: Option<usize> = None;
+
+        let tree = loop {
+            let tokens: Vec<Token> = kept
+                .iter()
+                .map(|&index| self.tokens[index].clone())
+                .collect();
+            let SyntaxError { position, .. } = match grammar::parse(&tokens) {
+                Ok(tree) => break tree,
+                Err(error) => error,
             };
-        }
-
-        Ok(left)
-    }
+            let previous = position.checked_sub(1).map(|index| kept[index]);
+            let found = kept.get(position).copied();
 
-    fn parse_expression(&mut self) -> Result<Expr, ParseError> {
-        let mut result = self.parse_term()?;
-
-        while let Some(token) = self.peek() {
-            match token {
-                Token::ADD | Token::SUB => {
-                    let op = if matches!(self.consume().unwrap(), Token::ADD) {
-                        "+"
-                    } else {
-                        "-"
-                    };
-                    let right = self.parse_term()?;
-                    // Allow list access in binary operations
-                    match (&result, &right) {
-                        (Expr::List(_), _) | (_, Expr::List(_)) => {
-                            return Err(ParseError::SyntaxError(self.get_current_position()))
-                        }
-                        _ => {}
-                    }
-                    result = Expr::BinaryOp(Box::new(result), op.to_string(), Box::new(right));
-                }
-                Token::EQ | Token::NE | Token::GT | Token::LT | Token::GE | Token::LE => {
-                    let op = match self.consume().unwrap() {
-                        Token::EQ => "==",
-                        Token::NE => "!=",
-                        Token::GT => ">",
-                        Token::LT => "<",
-                        Token::GE => ">=",
-                        Token::LE => "<=",
-                        _ => unreachable!(),
-                    };
-                    let right = self.parse_term()?;
-                    result = Expr::Boolean(Box::new(result), op.to_string(), Box::new(right));
-                }
-                _ => break,
+            // An error soon after a skipped token is usually the same mistake
+            let reached = found.unwrap_or(self.tokens.len());
+            if last_skipped.is_none_or(|skipped| reached > skipped + RECOVERY_TOKENS) {
+                errors.push(self.syntax_error(previous, found));
             }
-        }
-        Ok(result)
-    }
-
-    fn parse_term(&mut self) -> Result<Expr, ParseError> {
-        let mut left = self.parse_factor()?;
-
-        while let Some(token) = self.peek() {
-            match token {
-                Token::MUL | Token::DIV => {
-                    let is_mul = matches!(self.consume().unwrap(), Token::MUL);
-
-                    // Check if the next token is LIST
-                    if let Some(Token::LIST) = self.peek() {
-                        return Err(ParseError::SyntaxError(self.get_current_position()));
-                    }
-
-                    let right = self.parse_factor()?;
-
-                    // Check if we're operating directly on a list (not list access)
-                    match (&left, &right) {
-                        (Expr::List(_), _) | (_, Expr::List(_)) => {
-                            return Err(ParseError::SyntaxError(self.get_current_position()))
-                        }
-                        _ => {}
-                    }
-                    // Check if we're operating on a list
-                    match (&left, &right) {
-                        (Expr::List(_), _) | (_, Expr::List(_)) => {
-                            return Err(ParseError::SyntaxError(self.get_current_position()))
-                        }
-                        _ => {}
-                    }
-
-                    if !is_mul {
-                        // Check for division by zero
-                        match &right {
-                            Expr::Float(n) if *n == 0.0 => {
-                                return Err(ParseError::DivisionByZero(self.get_current_position()))
-                            }
-                            Expr::Int(n) if *n == 0 => {
-                                return Err(ParseError::DivisionByZero(self.get_current_position()))
-                            }
-                            _ => {}
-                        }
-                    }
-
-                    left = Expr::BinaryOp(
-                        Box::new(left),
-                        if is_mul { "*" } else { "/" }.to_string(),
-                        Box::new(right),
-                    );
+            match found {
+                Some(index) => {
+                    kept.remove(position);
+                    last_skipped = Some(index);
                 }
-                _ => break,
+                // Nothing is left to skip at the end of the line
+                None => return Err(errors),
             }
-        }
-        Ok(left)
-    }
-
-    fn parse_factor(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.parse_atom()?;
+        };
 
-        while let Some(Token::POW) = self.peek() {
-            self.consume();
-            let right = self.parse_factor()?;
-            expr = Expr::BinaryOp(Box::new(expr), "^".to_string(), Box::new(right));
+        if !errors.is_empty() {
+            return Err(errors);
         }
-
-        Ok(expr)
+        self.build(&tree)
     }
 
-    fn parse_atom(&mut self) -> Result<Expr, ParseError> {
-        match self.peek().cloned() {
-            Some(Token::LPAREN) => {
-                self.consume();
-                let expr = self.parse_expression()?;
-                self.expect(Token::RPAREN)?;
-                Ok(expr)
+    // The error reported for the token at `found` (`None` at the end of the
+    // line), positioned at the token read before it
+    fn syntax_error(&self, previous: Option<usize>, found: Option<usize>) -> ParseError {
+        let position = self.position(previous.unwrap_or(0));
+        match (
+            previous.map(|index| &self.tokens[index]),
+            found.map(|index| &self.tokens[index]),
+        ) {
+            (Some(Token::LBRACKET), Some(Token::RBRACKET)) => ParseError::MissingIndex(position),
+            (Some(keyword), Some(Token::ASSIGN)) if keyword.is_keyword() => {
+                ParseError::ReservedWord(keyword.to_string(), position)
             }
-            Some(Token::INT(n)) => self.parse_number(n, true),
-            Some(Token::REAL(n)) => self.parse_number(n, false),
-            Some(Token::VAR(name)) => self.parse_variable(name),
-            Some(Token::LIST) => self.parse_list(),
-            Some(Token::ERR) => Err(ParseError::SyntaxError(self.get_current_position())),
-            _ => Err(ParseError::InvalidAtom(self.get_current_position())),
+            _ => ParseError::SyntaxError(position),
         }
     }
 
-    fn parse_number(&mut self, n: String, is_int: bool) -> Result<Expr, ParseError> {
-        let previous_token = self.consume();
-        let current_token = self.peek().cloned();
-
-        // Check if currnet token is negative
-        let is_current_negative =
-            if let Some(Token::INT(n)) | Some(Token::REAL(n)) = current_token.clone() {
-                let number = n.parse::<f64>().unwrap();
-                number < 0.0
-            } else {
-                false
-            };
-
-        // If the previous and current tokens are numbers (either int/real), and last token contains a negative sign, we assume this to be a binary operation and the last number is positive
-        if matches!(previous_token, Some(Token::INT(_)) | Some(Token::REAL(_)))
-            && matches!(current_token, Some(Token::INT(_)) | Some(Token::REAL(_)))
-            && is_current_negative
-        {
-            let prev = match previous_token.unwrap() {
-                Token::INT(n) => self.parse_number(n.to_string(), true)?,
-                Token::REAL(n) => self.parse_number(n.to_string(), false)?,
-                _ => return Err(ParseError::SyntaxError(self.get_current_position())),
-            };
-            let curr = match current_token.unwrap() {
-                Token::INT(n) => {
-                    self.parse_number(n.parse::<i64>().unwrap().abs().to_string(), true)?
-                }
-                Token::REAL(n) => {
-                    self.parse_number(n.parse::<f64>().unwrap().abs().to_string(), false)?
-                }
-                _ => return Err(ParseError::SyntaxError(self.get_current_position())),
-            };
-            let expr = Expr::BinaryOp(Box::new(prev), Token::SUB.to_string(), Box::new(curr));
+    // Builds the expression a node of the parse tree stands for. Whether its
+    // names, types and values make sense is left to `sema`. The build script
+    // checks that every production of the grammar has a case here.
+    fn build(&mut self, node: &Node) -> Result<Expr, Vec<ParseError>> {
+        let Node::Branch {
+            nonterminal,
+            children,
+            ..
+        } = node
+        else {
+            return self.build_token(node);
+        };
 
-            Ok(expr)
-        } else {
-            if let Some(token) = current_token {
-                if !matches!(
-                    token,
-                    Token::ADD
-                        | Token::SUB
-                        | Token::MUL
-                        | Token::DIV
-                        | Token::INTDIV
-                        | Token::POW
-                        | Token::EQ
-                        | Token::NE
-                        | Token::GT
-                        | Token::LT
-                        | Token::GE
-                        | Token::LE
-                        | Token::LPAREN
-                        | Token::RPAREN
-                        | Token::RBRACKET
-                ) {
-                    return Err(ParseError::SyntaxError(self.get_current_position()));
-                }
+        match (nonterminal, children.as_slice()) {
+            (_, [child]) => self.build(child),
+            (NonTerminal::Atom, [_, inner, _]) => self.build(inner),
+            (NonTerminal::Atom, [_, operand]) => {
+                let operand = self.build(operand)?;
+                Ok(self.expr(ExprKind::UnaryOp(UnOp::Neg, Box::new(operand)), node))
             }
-
-            let number = n
-                .parse::<f64>()
-                .map_err(|_| ParseError::SyntaxError(self.get_current_position()))?;
-
-            if number < 0.0 {
-                Ok(Expr::UnaryOp(
-                    "-".to_string(),
-                    Box::new(if is_int {
-                        Expr::Int(number.abs() as i64)
-                    } else {
-                        Expr::Float(number.abs())
-                    }),
-                ))
-            } else if is_int {
-                Ok(Expr::Int(number as i64))
-            } else {
-                Ok(Expr::Float(number))
+            (NonTerminal::Assignment, [_, _, _]) => self.build_assignment(node),
+            (NonTerminal::IndexAccess, [name, _, index, _]) => {
+                let name = self.name(name);
+                let index = self.expr(ExprKind::Int(self.int(index)), &children[2]);
+                Ok(self.expr(ExprKind::ListAccess(name, Box::new(index)), node))
             }
-        }
-    }
-
-    fn parse_variable(&mut self, name: String) -> Result<Expr, ParseError> {
-        self.consume();
-
-        // Check if we're dealing with a list index access
-        if let Some(Token::LBRACKET) = self.peek() {
-            self.consume();
-            let index_expr = self.parse_expression()?;
-            self.expect(Token::RBRACKET)?;
-
-            if !self.variables.contains_key(&name) {
-                return Err(ParseError::UndefinedVariable(
-                    name,
-                    self.get_current_position(),
-                ));
+            (NonTerminal::ListAccess, [_, _, size, _]) => {
+                let length = usize::try_from(self.int(size)).unwrap_or_default();
+                Ok(self.expr(ExprKind::List(length), node))
             }
-
-            // Validate that we're accessing a list
-            match &self.variables[&name] {
-                Expr::List(lst) => {
-                    let index = match index_expr {
-                        Expr::Int(n) => n as usize,
-                        _ => return Err(ParseError::SyntaxError(self.get_current_position())),
-                    };
-
-                    if index >= lst.len() {
-                        return Err(ParseError::IndexOutOfRange(
-                            self.get_current_position(),
-                            index,
-                        ));
-                    }
-                }
-                _ => return Err(ParseError::SyntaxError(self.get_current_position())),
+            (NonTerminal::BooleanExpr, [left, op, right]) => {
+                let left = self.build(left)?;
+                let right = self.build(right)?;
+                let op = CmpOp::from_token(self.token(op)).expect("a comparison operator");
+                let kind = ExprKind::Boolean(Box::new(left), op, Box::new(right));
+                Ok(self.expr(kind, node))
             }
-
-            // Check if this is an assignment to a list index
-            if let Some(Token::ASSIGN) = self.peek() {
-                self.consume(); // Consume the ASSIGN token
-
-                // Parse the value being assigned
-                let value = self.parse_expression()?;
-
-                match value.clone() {
-                    // Case 1: Assigning a number directly
-                    Expr::Int(n) => {
-                        if let Expr::List(ref mut lst) = self.variables.get_mut(&name).unwrap() {
-                            let idx = match index_expr {
-                                Expr::Int(i) => i as usize,
-                                _ => {
-                                    return Err(ParseError::SyntaxError(
-                                        self.get_current_position(),
-                                    ))
-                                }
-                            };
-                            if idx < lst.len() {
-                                lst[idx] = n as f64;
-                            }
-                        }
-                    }
-                    Expr::Float(n) => {
-                        if let Expr::List(ref mut lst) = self.variables.get_mut(&name).unwrap() {
-                            let idx = match index_expr {
-                                Expr::Int(i) => i as usize,
-                                _ => {
-                                    return Err(ParseError::SyntaxError(
-                                        self.get_current_position(),
-                                    ))
-                                }
-                            };
-                            if idx < lst.len() {
-                                lst[idx] = n;
-                            }
-                        }
-                    }
-                    // Case 2: Assigning from another list's index
-                    Expr::ListAccess(other_name, other_index) => {
-                        // Get the source value first
-                        let source_value =
-                            if let Some(Expr::List(lst)) = self.variables.get(&other_name) {
-                                lst.clone()
-                            } else {
-                                return Err(ParseError::SyntaxError(self.get_current_position()));
-                            };
-
-                        if let Some(Expr::List(ref mut target_lst)) = self.variables.get_mut(&name)
-                        {
-                            let target_idx = match index_expr {
-                                Expr::Int(i) => i as usize,
-                                _ => {
-                                    return Err(ParseError::SyntaxError(
-                                        self.get_current_position(),
-                                    ))
-                                }
-                            };
-                            let source_idx = match *other_index {
-                                Expr::Int(i) => i as usize,
-                                _ => {
-                                    return Err(ParseError::SyntaxError(
-                                        self.get_current_position(),
-                                    ))
-                                }
-                            };
-                            if target_idx < target_lst.len() && source_idx < source_value.len() {
-                                target_lst[target_idx] = source_value[source_idx];
-                            }
-                        }
-                    }
-                    _ => return Err(ParseError::SyntaxError(self.get_current_position())),
-                }
-
-                // Return the assignment expression
-                Ok(Expr::Assignment(
-                    format!("{}[({})]", name, match index_expr {
-                        Expr::Int(i) => i.to_string(),
-                        _ => return Err(ParseError::SyntaxError(self.get_current_position())),
-                    }),
-                    Box::new(value),
+            (_, [left, op, right]) => {
+                let left = self.build(left)?;
+                let right = self.build(right)?;
+                let op = BinOp::from_token(self.token(op)).expect("an arithmetic operator");
+                Ok(self.expr(
+                    ExprKind::BinaryOp(Box::new(left), op, Box::new(right)),
+                    node,
                 ))
-            } else {
-                Ok(Expr::ListAccess(name, Box::new(index_expr)))
-            }
-        } else {
-            if !self.variables.contains_key(&name) {
-                return Err(ParseError::UndefinedVariable(
-                    name,
-                    self.get_current_position(),
-                ));
             }
-
-            Ok(Expr::Variable(name))
+            _ => unreachable!(
+                "no production of {} has {} symbols",
+                nonterminal,
+                children.len()
+            ),
         }
     }
 
-    fn parse_list(&mut self) -> Result<Expr, ParseError> {
-        self.consume(); // Consume LIST token
-        self.expect(Token::LBRACKET)?;
-
-        if let Some(Token::RBRACKET) = self.peek() {
-            return Err(ParseError::MissingIndex(self.get_current_position()));
-        }
+    fn build_token(&self, node: &Node) -> Result<Expr, Vec<ParseError>> {
+        let kind = match self.token(node) {
+            Token::INT(n) => ExprKind::Int(*n),
+            Token::REAL(n) => ExprKind::Float(*n),
+            Token::STRING(text) => ExprKind::Str(text.clone()),
+            Token::VAR(name) => ExprKind::Variable(name.clone()),
+            token => unreachable!("{} is not an atom", token),
+        };
+        Ok(self.expr(kind, node))
+    }
 
-        let size = match self.consume() {
-            Some(Token::INT(n)) => n
-                .parse::<usize>()
-                .map_err(|_| ParseError::SyntaxError(self.get_current_position()))?,
-            _ => return Err(ParseError::SyntaxError(self.get_current_position())),
+    // Builds `VAR = value` or `VAR[INT] = value`
+    fn build_assignment(&mut self, node: &Node) -> Result<Expr, Vec<ParseError>> {
+        let Node::Branch { children, .. } = node else {
+            unreachable!("an assignment is a branch")
+        };
+        let [target, _, value] = children.as_slice() else {
+            unreachable!("an assignment is a target, `=` and a value")
         };
 
-        if size == 0 {
-            return Err(ParseError::SyntaxError(self.get_current_position()));
+        if let Node::Branch { .. } = target {
+            let ExprKind::ListAccess(name, index) = self.build(target)?.kind else {
+                unreachable!("only list elements are assigned through an index")
+            };
+            let value = self.build(value)?;
+            let kind = ExprKind::ElementAssignment(name, index, Box::new(value));
+            return Ok(self.expr(kind, node));
         }
 
-        self.expect(Token::RBRACKET)?;
-
-        let mut list = vec![0.0; size];
-
-        if let Some(Token::ASSIGN) = self.peek() {
-            self.consume(); // Consume ASSIGN token
-
-            // Parse the value after assignment
-            match self.peek() {
-                // Case 1: Assigning a number
-                Some(Token::INT(..)) | Some(Token::REAL(..)) => {
-                    let value = match self.parse_expression()? {
-                        Expr::Int(n) => n as f64,
-                        Expr::Float(n) => n,
-                        Expr::UnaryOp(op, expr) => {
-                            let val = match *expr {
-                                Expr::Int(n) => n as f64,
-                                Expr::Float(n) => n,
-                                _ => {
-                                    return Err(ParseError::SyntaxError(
-                                        self.get_current_position(),
-                                    ))
-                                }
-                            };
-                            if op == "-" {
-                                -val
-                            } else {
-                                val
-                            }
-                        }
-                        _ => return Err(ParseError::SyntaxError(self.get_current_position())),
-                    };
-
-                    // Fill all elements with the same value
-                    list = vec![value; size];
-                }
+        let name = self.name(target);
+        let value = self.build(value)?;
+        Ok(self.expr(ExprKind::Assignment(name, Box::new(value)), node))
+    }
 
-                // Case 2: Assigning another list
-                Some(Token::VAR(..)) => {
-                    let var_expr = self.parse_expression()?;
-                    match var_expr {
-                        Expr::Variable(name) => {
-                            if let Some(Expr::List(source_list)) =
-                                self.variables.get(&name).cloned()
-                            {
-                                if source_list.len() != size {
-                                    return Err(ParseError::SyntaxError(
-                                        self.get_current_position(),
-                                    ));
-                                }
-                                list = source_list;
-                            } else {
-                                return Err(ParseError::SyntaxError(self.get_current_position()));
-                            }
-                        }
-                        _ => return Err(ParseError::SyntaxError(self.get_current_position())),
-                    }
-                }
+    // The first token a node derives, e.g. its operator
+    fn token(&self, node: &Node) -> &Token {
+        &self.tokens[node.tokens().start]
+    }
 
-                // Case 3: Assigning another list literal
-                Some(Token::LIST) => {
-                    let other_list = match self.parse_expression()? {
-                        Expr::List(l) => l,
-                        _ => return Err(ParseError::SyntaxError(self.get_current_position())),
-                    };
-                    if other_list.len() != size {
-                        return Err(ParseError::SyntaxError(self.get_current_position()));
-                    }
-                    list = other_list;
-                }
+    fn name(&self, node: &Node) -> String {
+        match &self.tokens[node.tokens().start] {
+            Token::VAR(name) => name.clone(),
+            token => unreachable!("{} is not a variable", token),
+        }
+    }
 
-                _ => return Err(ParseError::SyntaxError(self.get_current_position())),
-            }
+    fn int(&self, node: &Node) -> i64 {
+        match self.tokens[node.tokens().start] {
+            Token::INT(n) => n,
+            ref token => unreachable!("{} is not an integer", token),
         }
+    }
 
-        Ok(Expr::List(list))
+    pub fn parse_tokens(&mut self, tokens: logos::Lexer<'_, Token>) -> Vec<LineResult> {
+        self.parse_spanned_tokens(scanner::spanned(tokens))
     }
 
-    pub fn parse_tokens(&mut self, tokens: logos::Lexer<'_, Token>) -> Vec<Result<Expr, ParseError>> {
+    /// Parses the tokens line by line, with one result for every line that
+    /// has tokens. A line with errors doesn't stop the lines after it.
+    pub fn parse_spanned_tokens(&mut self, tokens: Vec<SpannedToken>) -> Vec<LineResult> {
         println!("DEBUG [Parser]: Starting to parse tokens");
-        let tokens_vec = tokens.collect::<Result<Vec<_>, _>>().unwrap_or_default();
-        println!("DEBUG [Parser]: Collected tokens: {:?}", tokens_vec);
-        
-        let mut results = Vec::new();
-        let mut current_line_tokens = Vec::new();
-        let mut current_line = 1;
-
-        for token in tokens_vec.iter() {
-            match token {
-                Token::NEWLINE => {
-                    if !current_line_tokens.is_empty() {
-                        println!("DEBUG [Parser]: Processing line {}: {:?}", current_line, current_line_tokens);
-                        self.tokens = current_line_tokens.clone();
-                        self.pos = 0;
-                        let result = self.parse();
-                        println!("DEBUG [Parser]: Line {} parse result: {:?}", current_line, result);
-                        
-                        // Only add one error per line
-                        if result.is_err() && !results.last().map_or(false, |last: &Result<Expr, ParseError>| last.is_err()) {
-                            results.push(result);
-                        } else if result.is_ok() {
-                            results.push(result);
-                        }
-                    }
-                    current_line_tokens.clear();
-                    current_line += 1;
-                }
-                _ => current_line_tokens.push(token.clone()),
-            }
-        }
 
-        if !current_line_tokens.is_empty() {
-            println!("DEBUG [Parser]: Processing final line {}: {:?}", current_line, current_line_tokens);
-            self.tokens = current_line_tokens;
-            self.pos = 0;
-            let result = self.parse();
-            println!("DEBUG [Parser]: Final line {} parse result: {:?}", current_line, result);
-            
-            // Only add one error per line
-            if result.is_err() && !results.last().map_or(false, |last: &Result<Expr, ParseError>| last.is_err()) {
-                results.push(result);
-            } else if result.is_ok() {
-                results.push(result);
-            }
+        let mut results: Vec<LineResult> = Vec::new();
+        let mut analyzer = Analyzer::new();
+
+        for (line_tokens, spans) in Self::split_into_lines(tokens) {
+            let (first_span, last_span) = (spans[0], spans[spans.len() - 1]);
+            let line_span = Span {
+                end: last_span.end,
+                end_line: last_span.end_line,
+                end_column: last_span.end_column,
+                ..first_span
+            };
+            println!(
+                "DEBUG [Parser]: Processing line {}: {:?}",
+                first_span.line, line_tokens
+            );
+            self.setup_line_parsing(line_tokens, spans, first_span.line);
+            let result = self.parse_line(&mut analyzer);
+            println!(
+                "DEBUG [Parser]: Line {} parse result: {:?}",
+                first_span.line, result
+            );
+            results.push((line_span, result));
         }
 
-        println!("DEBUG [Parser]: All parsing complete, results: {:?}", results);
+        println!(
+            "DEBUG [Parser]: All parsing complete, results: {:?}",
+            results
+        );
         results
     }
 
     pub fn parse_tokens_fancy(&mut self, input: Lexer<'_, Token>) -> Vec<String> {
-        let tokens = input.collect::<Vec<_>>();
-        let lines = self.split_into_lines(tokens);
+        self.parse_spanned_tokens_fancy(scanner::spanned(input))
+    }
+
+    /// Like [`Parser::parse_tokens_fancy`], for tokens that were already
+    /// scanned, e.g. read back from a JSON Lines token file.
+    pub fn parse_spanned_tokens_fancy(&mut self, tokens: Vec<SpannedToken>) -> Vec<String> {
+        let lines = Self::split_into_lines(tokens);
         let mut output = Vec::new();
-        let mut current_line = 1;
+        let mut analyzer = Analyzer::new();
 
-        for (line_tokens, positions) in lines {
-            self.setup_line_parsing(line_tokens, positions, current_line);
+        for (line_tokens, spans) in lines {
+            let line_number = spans[0].line;
+            self.setup_line_parsing(line_tokens, spans, line_number);
 
             // Print tokens for debugging
-            //println!("Tokens for line {}: {:?}", current_line, self.tokens);
+            //println!("Tokens for line {}: {:?}", line_number, self.tokens);
 
-            match self.parse() {
+            match self.parse_line(&mut analyzer) {
                 Ok(expr) => output.push(expr.to_string()),
-                Err(err) => output.push(self.format_error(err)),
+                // The bracket output keeps one message per line, the first
+                Err(errors) => output.push(errors[0].to_string()),
             }
-
-            current_line += 1;
         }
 
         output
     }
 
-    fn split_into_lines(&self, tokens: Vec<Result<Token, ()>>) -> Vec<(Vec<Token>, Vec<usize>)> {
-        let mut lines = Vec::new();
-        let mut current_line_tokens = Vec::new();
-        let mut current_line_positions = Vec::new();
-        let mut column = 1;
-
-        for token in tokens {
-            match token {
-                Ok(Token::NEWLINE) => {
-                    if !current_line_tokens.is_empty() {
-                        lines.push((current_line_tokens, current_line_positions));
-                        current_line_tokens = Vec::new();
-                        current_line_positions = Vec::new();
-                    }
-                    column = 1;
-                }
-                Ok(Token::WHITESPACE) => {
-                    column += 1;
-                }
-                Ok(tok) => {
-                    current_line_positions.push(column);
-                    current_line_tokens.push(tok);
-                    column += TokenInfo::token_length(&current_line_tokens.last().unwrap());
-                }
-                Err(_) => {
-                    current_line_positions.push(column);
-                    current_line_tokens.push(Token::ERR);
-                    column += TokenInfo::token_length(&Token::ERR);
-                }
+    // Groups the tokens by the source line they start on, dropping newlines and comments
+    pub(crate) fn split_into_lines(tokens: Vec<SpannedToken>) -> Vec<(Vec<Token>, Vec<Span>)> {
+        let mut lines: Vec<(Vec<Token>, Vec<Span>)> = Vec::new();
+        let mut current_line = 0;
+
+        for SpannedToken { token, span, .. } in tokens {
+            if token == Token::NEWLINE || token.is_trivia() {
+                continue;
+            }
+
+            if span.line != current_line {
+                lines.push((Vec::new(), Vec::new()));
+                current_line = span.line;
             }
-        }
 
-        if !current_line_tokens.is_empty() {
-            lines.push((current_line_tokens, current_line_positions));
+            if let Some((line_tokens, line_spans)) = lines.last_mut() {
+                line_tokens.push(token);
+                line_spans.push(span);
+            }
         }
 
         lines
     }
 
+    // Parses and analyses the current line, reporting its lexical errors
+    // instead if it has any
+    fn parse_line(&mut self, analyzer: &mut Analyzer) -> Result<Expr, Vec<ParseError>> {
+        let lex_errors: Vec<ParseError> = self
+            .tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::ERR(error) => Some(ParseError::TokenizeError(error.clone())),
+                _ => None,
+            })
+            .collect();
+
+        if lex_errors.is_empty() {
+            let expr = self.parse()?;
+            analyzer.check(&expr)?;
+            Ok(expr)
+        } else {
+            Err(lex_errors)
+        }
+    }
+
     fn setup_line_parsing(
         &mut self,
         line_tokens: Vec<Token>,
-        positions: Vec<usize>,
+        spans: Vec<Span>,
         line_number: usize,
     ) {
         self.tokens = line_tokens;
-        self.token_positions = positions;
-        self.pos = 0;
+        self.token_spans = spans;
         self.current_line = line_number;
-        self.current_column = 1;
-    }
-
-    fn format_error(&self, err: ParseError) -> String {
-        match err {
-            ParseError::UndefinedVariable(var, pos) => {
-                format!(
-                    "Undefined variable {} at line {}, pos {}",
-                    var, pos.line, pos.column
-                )
-            }
-            ParseError::SyntaxError(pos) => {
-                format!("SyntaxError at line {}, pos {}", pos.line, pos.column)
-            }
-            ParseError::InvalidAtom(pos) => {
-                format!("Invalid atom at line {}, pos {}", pos.line, pos.column)
-            }
-            ParseError::IndexOutOfRange(pos, index) => {
-                format!(
-                    "IndexOutOfRange at line {}, pos {}, index {}",
-                    pos.line, pos.column, index
-                )
-            }
-            ParseError::DivisionByZero(pos) => {
-                format!("Division by zero at line {}, pos {}", pos.line, pos.column)
-            }
-            ParseError::MissingIndex(pos) => {
-                format!(
-                    "Missing index expression at line {}, pos {}",
-                    pos.line, pos.column
-                )
-            }
-            ParseError::TokenizeError => "TokenizeError".to_string(),
-        }
     }
 }
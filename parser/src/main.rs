@@ -4,34 +4,101 @@ use parser::Parser;
 const SCANNER_DEFAULT_OUTPUT_FILE: &str = "hiwkhao.tok";
 const SYMBOL_TABLE_DEFAULT_OUTPUT_FILE: &str = "hiwkhao.csv";
 const PARSER_DEFAULT_OUTPUT_FILE: &str = "hiwkhao.bracket";
+const TREE_TEXT_OUTPUT_FILE: &str = "hiwkhao.tree.txt";
+const TREE_DOT_OUTPUT_FILE: &str = "hiwkhao.tree.dot";
 
-fn scanner(input: &String) {
-    let result = scanner::run_scanner(&input);
+fn scanner(input: &str, output_file: Option<&String>) {
+    let result = scanner::run_scanner(input);
     //println!("{}", result.join("\n"));
 
-    let output_file = std::env::args()
-        .nth(2)
+    let output_file = output_file
+        .cloned()
         .unwrap_or(SCANNER_DEFAULT_OUTPUT_FILE.to_string());
 
     std::fs::write(output_file, result.join("\n")).unwrap();
 }
 
+// Removes `--name value` from the arguments and returns the value
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let index = args.iter().position(|arg| arg == name)?;
+    args.remove(index);
+    if index >= args.len() {
+        eprintln!("{} needs a value", name);
+        std::process::exit(1);
+    }
+    Some(args.remove(index))
+}
+
 fn main() {
-    let input = if let Some(file_path) = std::env::args().nth(1) {
-        std::fs::read_to_string(file_path).unwrap()
-    } else {
+    // `--trace LINE` prints the parser's moves on one line of the input and
+    // `--derive` the leftmost derivation and parse tree of every line,
+    // instead of parsing the whole file
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let derive = match args.iter().position(|arg| arg == "--derive") {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    };
+    let trace_line = take_option(&mut args, "--trace").map(|line| {
+        line.parse::<usize>().unwrap_or_else(|_| {
+            eprintln!("--trace needs a line number, found `{}`", line);
+            std::process::exit(1);
+        })
+    });
+
+    let Some(file_path) = args.first().cloned() else {
         eprintln!("No input file provided.");
         std::process::exit(1);
     };
 
-    scanner(&input);
+    // A `.jsonl` token file from `scanner --format jsonl` is parsed as is;
+    // anything else is source text that is scanned first
+    let tokens = if file_path.ends_with(".jsonl") {
+        scanner::json::read_jsonl(&file_path).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        })
+    } else {
+        let input = std::fs::read_to_string(file_path).unwrap();
+        if trace_line.is_none() && !derive {
+            scanner(&input, args.get(1));
+        }
+        scanner::tokenize_spanned(&input)
+    };
+
+    if let Some(line) = trace_line {
+        match parser::trace::trace_line(tokens, line) {
+            Some(trace) => print!("{}", trace),
+            None => {
+                eprintln!("Line {} has no tokens", line);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if derive {
+        derive_lines(tokens);
+        return;
+    }
 
-    let tokens = scanner::tokenize(&input);
     let mut parser = Parser::new(vec![]);
 
-    let parsed_data = parser.parse_tokens(tokens.clone());
+    let parsed_data = parser.parse_spanned_tokens(tokens.clone());
+
+    // Every error of the file at once; the bracket output shows the first
+    // error of each line
+    for (_, result) in &parsed_data {
+        if let Err(errors) = result {
+            for error in errors {
+                eprintln!("{}", error);
+            }
+        }
+    }
 
-    let result = parser.parse_tokens_fancy(tokens);
+    let result = parser.parse_spanned_tokens_fancy(tokens);
 
     let mut table = SymbolTable::new();
     table.process_parsed_expressions(parsed_data);
@@ -42,9 +109,31 @@ fn main() {
         .write_to_csv(SYMBOL_TABLE_DEFAULT_OUTPUT_FILE)
         .unwrap();
 
-    let output_file = std::env::args()
-        .nth(2)
+    let output_file = args
+        .get(1)
+        .cloned()
         .unwrap_or(PARSER_DEFAULT_OUTPUT_FILE.to_string());
 
     std::fs::write(output_file, result.join("\n")).unwrap();
 }
+
+// Prints the leftmost derivation and parse tree of each line, and writes the
+// trees as indented text and Graphviz DOT
+fn derive_lines(tokens: Vec<scanner::SpannedToken>) {
+    let lines = parser::derivation::derive_lines(tokens);
+    let mut trees = String::new();
+    for line in &lines {
+        println!("Line {}\n\n{}", line.line, line.derivation_text());
+        if line.tree.is_ok() {
+            println!("{}", line.tree_text());
+        }
+        trees.push_str(&format!("Line {}\n{}\n", line.line, line.tree_text()));
+    }
+
+    std::fs::write(TREE_TEXT_OUTPUT_FILE, trees).unwrap();
+    std::fs::write(
+        TREE_DOT_OUTPUT_FILE,
+        parser::derivation::trees_to_dot(&lines),
+    )
+    .unwrap();
+}
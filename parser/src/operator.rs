@@ -0,0 +1,157 @@
+//! The operators of expressions, with the precedence and associativity
+//! `hiwkhao.grammar` gives them.
+
+use scanner::grammar::Token;
+use std::fmt;
+
+/// Which side an operator groups from when it is repeated, e.g. `1 - 2 - 3`
+/// is `((1-2)-3)` and `2 ^ 3 ^ 2` is `(2^(3^2))`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+    /// The operator can't be repeated without parentheses.
+    NonAssociative,
+}
+
+/// Arithmetic operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    /// `//`, division rounded down.
+    IntDiv,
+    Pow,
+}
+
+/// Comparison operators of a boolean expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+/// Prefix operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnOp {
+    Neg,
+}
+
+impl BinOp {
+    pub fn from_token(token: &Token) -> Option<Self> {
+        match token {
+            Token::ADD => Some(BinOp::Add),
+            Token::SUB => Some(BinOp::Sub),
+            Token::MUL => Some(BinOp::Mul),
+            Token::DIV => Some(BinOp::Div),
+            Token::INTDIV => Some(BinOp::IntDiv),
+            Token::POW => Some(BinOp::Pow),
+            _ => None,
+        }
+    }
+
+    /// How tightly the operator binds; the operator with the higher
+    /// precedence is applied first.
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinOp::Add | BinOp::Sub => 2,
+            BinOp::Mul | BinOp::Div | BinOp::IntDiv => 3,
+            BinOp::Pow => 4,
+        }
+    }
+
+    pub fn associativity(self) -> Associativity {
+        match self {
+            BinOp::Pow => Associativity::Right,
+            _ => Associativity::Left,
+        }
+    }
+
+    /// `/` and `//`, which can't take a zero on the right.
+    pub fn is_division(self) -> bool {
+        matches!(self, BinOp::Div | BinOp::IntDiv)
+    }
+}
+
+impl CmpOp {
+    pub fn from_token(token: &Token) -> Option<Self> {
+        match token {
+            Token::EQ => Some(CmpOp::Eq),
+            Token::NE => Some(CmpOp::Ne),
+            Token::LT => Some(CmpOp::Lt),
+            Token::GT => Some(CmpOp::Gt),
+            Token::LE => Some(CmpOp::Le),
+            Token::GE => Some(CmpOp::Ge),
+            _ => None,
+        }
+    }
+
+    /// Comparisons bind looser than any arithmetic operator.
+    pub fn precedence(self) -> u8 {
+        1
+    }
+
+    pub fn associativity(self) -> Associativity {
+        Associativity::NonAssociative
+    }
+}
+
+impl UnOp {
+    pub fn from_token(token: &Token) -> Option<Self> {
+        match token {
+            Token::SUB => Some(UnOp::Neg),
+            _ => None,
+        }
+    }
+
+    /// A minus binds tighter than `^`, so `-2 ^ 2` is `((-2)^2)`.
+    pub fn precedence(self) -> u8 {
+        5
+    }
+
+    pub fn associativity(self) -> Associativity {
+        Associativity::Right
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::IntDiv => "//",
+            BinOp::Pow => "^",
+        };
+        f.write_str(text)
+    }
+}
+
+impl fmt::Display for CmpOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            CmpOp::Eq => "==",
+            CmpOp::Ne => "!=",
+            CmpOp::Lt => "<",
+            CmpOp::Gt => ">",
+            CmpOp::Le => "<=",
+            CmpOp::Ge => ">=",
+        };
+        f.write_str(text)
+    }
+}
+
+impl fmt::Display for UnOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnOp::Neg => f.write_str("-"),
+        }
+    }
+}
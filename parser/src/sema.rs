@@ -0,0 +1,228 @@
+//! Semantic analysis of the expressions the parser builds: variables must be
+//! assigned before they are read, strings can't be negated, take part in
+//! arithmetic or be compared with numbers, only numbers are stored in list
+//! elements, constant list indices must be in range and nothing is divided by
+//! a literal zero.
+//!
+//! The parser only knows the syntax of one line. The [`Analyzer`] carries what
+//! earlier lines assigned, so lines are checked in source order.
+
+use crate::{Expr, ExprKind, ParseError, Position};
+use scanner::Span;
+use std::collections::HashMap;
+use std::fmt;
+
+/// What a variable holds, as far as the checks need to know.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Number,
+    Str,
+    /// A list and its length.
+    List(usize),
+}
+
+/// The variables assigned so far and their types.
+#[derive(Debug, Default)]
+pub struct Analyzer {
+    variables: HashMap<String, Type>,
+}
+
+impl Analyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks the statement of one line and returns every error in it. A
+    /// statement without errors that assigns a variable defines it for the
+    /// lines after it.
+    pub fn check(&mut self, expr: &Expr) -> Result<(), Vec<ParseError>> {
+        let mut errors = Vec::new();
+        let value_type = self.analyze(expr, &mut errors);
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        if let (ExprKind::Assignment(name, _), Some(value_type)) = (&expr.kind, value_type) {
+            self.variables.insert(name.clone(), value_type);
+        }
+        Ok(())
+    }
+
+    // The type of an expression, or `None` if it has errors, which are added
+    // to `errors`. An operator is only checked once its operands are fine.
+    fn analyze(&self, expr: &Expr, errors: &mut Vec<ParseError>) -> Option<Type> {
+        match &expr.kind {
+            ExprKind::Int(_) | ExprKind::Float(_) => Some(Type::Number),
+            ExprKind::Str(_) => Some(Type::Str),
+            ExprKind::Variable(name) => match self.variables.get(name) {
+                Some(variable_type) => Some(*variable_type),
+                None => {
+                    errors.push(ParseError::UndefinedVariable(name.clone(), start(expr)));
+                    None
+                }
+            },
+            ExprKind::List(0) => {
+                errors.push(ParseError::SyntaxError(last_token(expr)));
+                None
+            }
+            ExprKind::List(length) => Some(Type::List(*length)),
+            ExprKind::ListAccess(name, index) => {
+                match self.element_error(name, constant_index(index), last_token(expr), expr) {
+                    Some(error) => {
+                        errors.push(error);
+                        None
+                    }
+                    None => Some(Type::Number),
+                }
+            }
+            ExprKind::UnaryOp(_, operand) => match self.analyze(operand, errors)? {
+                Type::Number => Some(Type::Number),
+                found => {
+                    errors.push(type_error(Type::Number, found, operand));
+                    None
+                }
+            },
+            ExprKind::Boolean(left, _, right) => {
+                let (left_type, right_type) =
+                    (self.analyze(left, errors), self.analyze(right, errors));
+                let (left_type, right_type) = (left_type?, right_type?);
+                // A string is only compared with another string
+                if left_type != right_type && (left_type == Type::Str || right_type == Type::Str) {
+                    errors.push(type_error(left_type, right_type, right));
+                    return None;
+                }
+                Some(Type::Number)
+            }
+            ExprKind::BinaryOp(left, op, right) => {
+                let (left_type, right_type) =
+                    (self.analyze(left, errors), self.analyze(right, errors));
+                let (left_type, right_type) = (left_type?, right_type?);
+                // Arithmetic works on numbers and list elements, not strings
+                let strings: Vec<ParseError> = [(left, left_type), (right, right_type)]
+                    .into_iter()
+                    .filter(|&(_, operand_type)| operand_type == Type::Str)
+                    .map(|(operand, operand_type)| type_error(Type::Number, operand_type, operand))
+                    .collect();
+                if !strings.is_empty() {
+                    errors.extend(strings);
+                    return None;
+                }
+                if op.is_division() && is_zero(right) {
+                    errors.push(ParseError::DivisionByZero(last_token(expr)));
+                    return None;
+                }
+                Some(Type::Number)
+            }
+            ExprKind::Assignment(_, value) => self.analyze(value, errors),
+            ExprKind::ElementAssignment(name, index, value) => {
+                // The target `name[index]` is positioned at its name
+                let target_error =
+                    self.element_error(name, constant_index(index), start(expr), expr);
+                errors.extend(target_error);
+                match self.analyze(value, errors)? {
+                    Type::Number => Some(Type::Number),
+                    found => {
+                        errors.push(type_error(Type::Number, found, value));
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    // The error of reading or writing element `index` of the list `name`,
+    // where `expr` starts with that name
+    fn element_error(
+        &self,
+        name: &str,
+        index: usize,
+        position: Position,
+        expr: &Expr,
+    ) -> Option<ParseError> {
+        match self.variables.get(name) {
+            None => Some(ParseError::UndefinedVariable(name.to_string(), position)),
+            Some(Type::List(length)) if index >= *length => {
+                Some(ParseError::IndexOutOfRange(position, index))
+            }
+            Some(Type::List(_)) => None,
+            Some(&found) => {
+                let message = format!("expected a list, found {}", found);
+                Some(ParseError::TypeError(message, name_span(expr, name)))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Number => f.write_str("a number"),
+            Type::Str => f.write_str("a string"),
+            Type::List(_) => f.write_str("a list"),
+        }
+    }
+}
+
+fn type_error(expected: Type, found: Type, expr: &Expr) -> ParseError {
+    ParseError::TypeError(format!("expected {}, found {}", expected, found), expr.span)
+}
+
+// Whether `expr` is a literal zero, negated or not
+fn is_zero(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Int(n) => *n == 0,
+        ExprKind::Float(n) => *n == 0.0,
+        ExprKind::UnaryOp(_, operand) => is_zero(operand),
+        _ => false,
+    }
+}
+
+// A constant index; anything else is treated as out of range
+fn constant_index(index: &Expr) -> usize {
+    match index.kind {
+        ExprKind::Int(index) => usize::try_from(index).unwrap_or(usize::MAX),
+        _ => usize::MAX,
+    }
+}
+
+// The span of the name `expr` starts with
+fn name_span(expr: &Expr, name: &str) -> Span {
+    Span {
+        end: expr.span.start + name.len(),
+        end_line: expr.span.line,
+        end_column: expr.span.column + name.chars().count(),
+        ..expr.span
+    }
+}
+
+fn start(expr: &Expr) -> Position {
+    Position {
+        line: expr.span.line,
+        column: expr.span.column,
+    }
+}
+
+// Where the last token of an expression starts, which is where the parser
+// finished reading it. An expression that ends in `)` or `]` ends one column
+// after that token.
+fn last_token(expr: &Expr) -> Position {
+    let closing = Position {
+        line: expr.span.end_line,
+        column: expr.span.end_column.saturating_sub(1),
+    };
+    let last = match &expr.kind {
+        ExprKind::Int(_) | ExprKind::Float(_) | ExprKind::Str(_) | ExprKind::Variable(_) => {
+            return start(expr)
+        }
+        ExprKind::List(_) | ExprKind::ListAccess(..) => return closing,
+        ExprKind::BinaryOp(_, _, last)
+        | ExprKind::Boolean(_, _, last)
+        | ExprKind::UnaryOp(_, last)
+        | ExprKind::Assignment(_, last)
+        | ExprKind::ElementAssignment(_, _, last) => last,
+    };
+    if last.span.end == expr.span.end {
+        last_token(last)
+    } else {
+        closing
+    }
+}
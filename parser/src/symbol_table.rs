@@ -1,29 +1,32 @@
 use csv::Writer;
 use scanner::grammar::Token;
+use scanner::Span;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs::File;
 
-use crate::{Expr, ParseError};
+use crate::{Expr, ExprKind, LineResult};
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone)]
 pub enum VariableType {
     INT,
     REAL,
+    STRING,
     LIST(Box<VariableType>),
 }
 
 #[derive(Debug)]
 pub struct SymbolTableEntry {
     lexeme: String,
-    line_number: usize,
-    start_pos: usize,
+    /// The assignment that defined the symbol.
+    span: Span,
     length: usize,
     value_type: Token,
     value: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct SymbolTable {
     entries: Vec<SymbolTableEntry>,
     //tokens: Vec<Token>,
@@ -50,16 +53,14 @@ impl SymbolTable {
     pub fn insert(
         &mut self,
         lexeme: String,
-        line_number: usize,
-        start_pos: usize,
+        span: Span,
         length: usize,
         value_type: Token,
         value: String,
     ) {
         let entry = SymbolTableEntry {
             lexeme,
-            line_number,
-            start_pos,
+            span,
             length,
             value_type,
             value,
@@ -67,88 +68,86 @@ impl SymbolTable {
         self.entries.push(entry);
     }
 
-    pub fn process_parsed_expressions(&mut self, parsed_results: Vec<Result<Expr, ParseError>>) {
-        let mut current_pos = 0;
-
-        for (line_number, result) in parsed_results.iter().enumerate() {
-            if let Ok(expr) = result {
-                match expr {
-                    Expr::Assignment(var_name, value_expr) => {
-                        let (value_type, value, length) = match &**value_expr {
-                            Expr::Int(n) => {
-                                self.variables.insert(var_name.clone(), VariableType::INT);
-                                (
-                                    Token::INT(n.to_string()),
-                                    n.to_string(),
-                                    n.to_string().len(),
-                                )
-                            }
-                            Expr::Float(n) => {
-                                self.variables.insert(var_name.clone(), VariableType::REAL);
-                                (
-                                    Token::REAL(n.to_string()),
-                                    n.to_string(),
-                                    n.to_string().len(),
-                                )
-                            }
-                            Expr::List(elements) => {
-                                // Determine list element type dynamically (e.g., INT or REAL) by inspecting the elements
-                                // Even all element are f64 but if non of them has a decimal point, we can assume it's an INT
-                                let element_type = if elements.iter().all(|e| e.fract() == 0.0) {
-                                    VariableType::INT
-                                } else {
-                                    VariableType::REAL
-                                };
-                                self.variables.insert(
-                                    var_name.clone(),
-                                    VariableType::LIST(Box::new(element_type)),
-                                );
-
-                                (Token::LIST, "Array".to_string(), "Array".len())
-                            }
-
-                            Expr::ListAccess(list_name, index) => {
-                                let list_type = self.variables.get(list_name);
-                                let element_type = match list_type {
-                                    Some(VariableType::LIST(inner_type)) => inner_type.as_ref(),
-                                    _ => &VariableType::INT, // Default to INT if unknown
-                                };
-
-                                let index_value = if let Expr::Int(n) = **index {
-                                    n.to_string()
-                                } else {
-                                    "unknown".to_string()
-                                };
-
-                                let token_type = match element_type {
-                                    VariableType::INT => Token::INT(index_value.clone()),
-                                    VariableType::REAL => Token::REAL(index_value.clone()),
-                                    _ => Token::LIST,
-                                };
-
-                                (
-                                    token_type,
-                                    format!("{}[{}]", list_name, index_value),
-                                    list_name.len() + index_value.len() + 2,
-                                )
-                            }
-                            _ => continue,
-                        };
-
-                        self.insert(
-                            var_name.clone(),
-                            line_number + 1,
-                            current_pos,
-                            length,
-                            value_type,
-                            value,
-                        );
-
-                        current_pos += length + 1;
-                    }
-                    _ => (),
+    /// Records every assignment, positioned at the span of the assignment.
+    pub fn process_parsed_expressions(&mut self, parsed_results: Vec<LineResult>) {
+        for (_, result) in parsed_results.iter() {
+            let Ok(Expr { kind, span }) = result else {
+                continue;
+            };
+            // An element is recorded under its name and index, e.g. `x[1]`
+            let (var_name, value_expr) = match kind {
+                ExprKind::Assignment(name, value) => (name.clone(), value),
+                ExprKind::ElementAssignment(name, index, value) => {
+                    (format!("{}[{}]", name, index), value)
+                }
+                _ => continue,
+            };
+
+            let (value_type, value, length) = match &value_expr.kind {
+                ExprKind::Int(n) => {
+                    self.variables.insert(var_name.clone(), VariableType::INT);
+                    (
+                        Token::INT(*n),
+                        n.to_string(),
+                        n.to_string().len(),
+                    )
+                }
+                ExprKind::Float(n) => {
+                    self.variables.insert(var_name.clone(), VariableType::REAL);
+                    (
+                        Token::REAL(*n),
+                        n.to_string(),
+                        n.to_string().len(),
+                    )
+                }
+                ExprKind::Str(text) => {
+                    self.variables.insert(var_name.clone(), VariableType::STRING);
+                    let token = Token::STRING(text.clone());
+                    let value = token.to_string();
+                    (token, value.clone(), value.chars().count())
+                }
+                ExprKind::List(_) => {
+                    // A new list holds only zeros, so its elements start out as INT
+                    self.variables.insert(
+                        var_name.clone(),
+                        VariableType::LIST(Box::new(VariableType::INT)),
+                    );
+
+                    (Token::LIST, "Array".to_string(), "Array".len())
+                }
+
+                ExprKind::ListAccess(list_name, index) => {
+                    let list_type = self.variables.get(list_name);
+                    let element_type = match list_type {
+                        Some(VariableType::LIST(inner_type)) => inner_type.as_ref(),
+                        _ => &VariableType::INT, // Default to INT if unknown
+                    };
+
+                    let index = if let ExprKind::Int(n) = index.kind { Some(n) } else { None };
+                    let index_value = index.map_or("unknown".to_string(), |n| n.to_string());
+
+                    let token_type = match element_type {
+                        VariableType::INT => Token::INT(index.unwrap_or_default()),
+                        VariableType::REAL => Token::REAL(index.unwrap_or_default() as f64),
+                        _ => Token::LIST,
+                    };
+
+                    (
+                        token_type,
+                        format!("{}[{}]", list_name, index_value),
+                        list_name.chars().count() + index_value.len() + 2,
+                    )
                 }
-            }
+                _ => continue,
+            };
+
+            self.insert(
+                var_name.clone(),
+                *span,
+                length,
+                value_type,
+                value,
+            );
         }
     }
 
@@ -159,8 +158,8 @@ impl SymbolTable {
             symbol_table_output.push(format!(
                 "{},{},{},{},{:?},{}",
                 entry.lexeme,
-                entry.line_number,
-                entry.start_pos,
+                entry.span.line,
+                entry.span.column,
                 entry.length,
                 entry.value_type,
                 entry.value
@@ -174,26 +173,34 @@ impl SymbolTable {
         let file = File::create(filename)?;
         let mut wtr = Writer::from_writer(file);
 
-        wtr.write_record(&[
+        wtr.write_record([
             "Lexeme",
             "Line Number",
             "Start Position",
             "Length",
             "Type",
             "Value",
+            "End Line",
+            "End Position",
+            "Start Byte",
+            "End Byte",
         ])?;
 
         for entry in &self.entries {
             let type_name = format!("{:?}", entry.value_type);
             let trimmed_type = type_name.split('(').next().unwrap_or(&type_name);
 
-            wtr.write_record(&[
+            wtr.write_record([
                 &entry.lexeme,
-                &entry.line_number.to_string(),
-                &entry.start_pos.to_string(),
+                &entry.span.line.to_string(),
+                &entry.span.column.to_string(),
                 &entry.length.to_string(),
                 trimmed_type,
                 &entry.value,
+                &entry.span.end_line.to_string(),
+                &entry.span.end_column.to_string(),
+                &entry.span.start.to_string(),
+                &entry.span.end.to_string(),
             ])?;
         }
 
@@ -0,0 +1,71 @@
+//! The moves of the generated LR parser on one line, as the stack, input and
+//! action table of a textbook trace.
+
+use crate::grammar::{self, Move, PRODUCTION_TEXT, TERMINALS};
+use crate::Parser;
+use scanner::grammar::Token;
+use scanner::SpannedToken;
+
+/// The trace of the source line numbered `line`, or `None` if the line has
+/// no tokens.
+pub fn trace_line(tokens: Vec<SpannedToken>, line: usize) -> Option<String> {
+    Parser::split_into_lines(tokens)
+        .into_iter()
+        .find(|(_, spans)| spans[0].line == line)
+        .map(|(tokens, _)| trace(&tokens))
+}
+
+/// One row per move: the stack of states and symbols, the tokens left with
+/// `$` at the end, and what the parser does.
+pub fn trace(tokens: &[Token]) -> String {
+    let (steps, _) = grammar::trace(tokens);
+    let input: Vec<&str> = tokens
+        .iter()
+        .map(|token| grammar::terminal(token).map_or("?", |terminal| TERMINALS[terminal]))
+        .chain(std::iter::once("$"))
+        .collect();
+
+    let rows: Vec<[String; 3]> = steps
+        .iter()
+        .map(|step| {
+            let mut stack = step.states[0].to_string();
+            for (symbol, state) in step.symbols.iter().zip(&step.states[1..]) {
+                stack.push_str(&format!(" {} {}", symbol, state));
+            }
+            let action = match step.action {
+                Move::Shift(state) => format!("shift {}", state),
+                Move::Reduce(production) => format!("reduce {}", PRODUCTION_TEXT[production]),
+                Move::Accept => "accept".to_string(),
+                Move::Error => "error".to_string(),
+            };
+            [stack, input[step.position..].join(" "), action]
+        })
+        .collect();
+
+    let header = ["Stack", "Input", "Action"].map(String::from);
+    let mut widths = header.clone().map(|cell| cell.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut text = String::new();
+    let separator = widths.map(|width| "-".repeat(width));
+    for row in std::iter::once(&header)
+        .chain(std::iter::once(&separator))
+        .chain(&rows)
+    {
+        let line = format!(
+            "{:stack$}  {:>input$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            stack = widths[0],
+            input = widths[1]
+        );
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
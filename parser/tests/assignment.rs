@@ -32,3 +32,17 @@ fn invalid_assignment() {
     let output = parser.parse_tokens_fancy(tokens);
     assert_eq!(output, expected_output);
 }
+
+#[test]
+fn variable_declearations_with_comments() {
+    let input = r"# setup
+x = 3 # three
+/* y = 5
+   is disabled */ y = 4
+";
+    let expected_output = vec!["(x=3)", "(y=4)"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
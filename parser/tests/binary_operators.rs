@@ -123,8 +123,9 @@ fn negative_subtraction_no_space_2() {
 
 #[test]
 fn negative_subtraction_no_space_3() {
+    // `"-" <atom>` binds tighter than subtraction, spaced or not
     let input = "- 23 - 8";
-    let expected_output = vec![r"(-(23-8))"];
+    let expected_output = vec![r"((-23)-8)"];
     let tokens = scanner::tokenize(input);
     let mut parser = parser::Parser::new(vec![]);
     let output = parser.parse_tokens_fancy(tokens);
@@ -164,7 +165,7 @@ fn negative_division_integers() {
 #[test]
 fn zero_division() {
     let input = "23 / 0";
-    let expected_output = vec![r"Division by zero at line 1, pos 4"];
+    let expected_output = vec![r"Division by zero at line 1, pos 6"];
     let tokens = scanner::tokenize(input);
     let mut parser = parser::Parser::new(vec![]);
     let output = parser.parse_tokens_fancy(tokens);
@@ -190,3 +191,111 @@ fn negative_exponent_float() {
     let output = parser.parse_tokens_fancy(tokens);
     assert_eq!(output, expected_output);
 }
+
+#[test]
+fn zero_division_with_spaces() {
+    let input = "23  /\t\t0";
+    let expected_output = vec![r"Division by zero at line 1, pos 8"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn large_integer_is_exact() {
+    let input = "9007199254740993 + 0x10";
+    let expected_output = vec![r"(9007199254740993+16)"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn variable_minus_number_without_space() {
+    let input = "x = 5\nx -1\nx-1";
+    let expected_output = vec!["(x=5)", "(x-1)", "(x-1)"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn subtract_negative_number() {
+    let input = "23 - -8";
+    let expected_output = vec![r"(23-(-8))"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn negative_exponent_operand() {
+    let input = "2 ^ -3 * 4";
+    let expected_output = vec![r"((2^(-3))*4)"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn integer_division() {
+    let input = "23 // 8 * 2\n7 // 0";
+    let expected_output = vec![r"((23//8)*2)", r"Division by zero at line 2, pos 6"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn power_is_right_associative() {
+    let input = "2 ^ 3 ^ 2";
+    let expected_output = vec![r"(2^(3^2))"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn operators_are_typed() {
+    use parser::{Associativity, BinOp, CmpOp, ExprKind, UnOp};
+
+    let tokens = scanner::tokenize("-1 + 2 // 3 >= 4");
+    let mut parser = parser::Parser::new(vec![]);
+    let results = parser.parse_tokens(tokens);
+    let ExprKind::Boolean(left, CmpOp::Ge, _) = &results[0].1.as_ref().unwrap().kind else {
+        panic!("expected a comparison, got {:?}", results[0].1);
+    };
+    let ExprKind::BinaryOp(negated, BinOp::Add, sum) = &left.kind else {
+        panic!("expected a sum, got {:?}", left);
+    };
+    assert!(matches!(negated.kind, ExprKind::UnaryOp(UnOp::Neg, _)));
+    assert!(matches!(sum.kind, ExprKind::BinaryOp(_, BinOp::IntDiv, _)));
+
+    assert!(UnOp::Neg.precedence() > BinOp::Pow.precedence());
+    assert!(BinOp::Mul.precedence() > BinOp::Sub.precedence());
+    assert!(BinOp::Add.precedence() > CmpOp::Eq.precedence());
+    assert_eq!(BinOp::Pow.associativity(), Associativity::Right);
+    assert_eq!(BinOp::Div.associativity(), Associativity::Left);
+    assert_eq!(BinOp::IntDiv.to_string(), "//");
+}
+
+#[test]
+fn negation_ignores_spacing() {
+    let spaced = "- 23 - 8\nx = - 2 ^ 2\n1 * - 3\n- (4 + 5) < 6";
+    let tight = "-23-8\nx=-2^2\n1*-3\n-(4+5)<6";
+    let mut parser = parser::Parser::new(vec![]);
+    let spaced_output = parser.parse_tokens_fancy(scanner::tokenize(spaced));
+    let tight_output = parser.parse_tokens_fancy(scanner::tokenize(tight));
+    assert_eq!(spaced_output, tight_output);
+    assert_eq!(
+        spaced_output,
+        vec!["((-23)-8)", "(x=((-2)^2))", "(1*(-3))", "((-(4+5))<6)"]
+    );
+}
@@ -0,0 +1,110 @@
+use parser::derivation;
+use parser::grammar::{self, Move, Node, NonTerminal};
+use scanner::grammar::Token;
+
+fn tokens(input: &str) -> Vec<Token> {
+    scanner::tokenize_spanned(input)
+        .into_iter()
+        .map(|token| token.token)
+        .collect()
+}
+
+fn nonterminal(node: &Node) -> Option<NonTerminal> {
+    match node {
+        Node::Token(_) => None,
+        Node::Branch { nonterminal, .. } => Some(*nonterminal),
+    }
+}
+
+#[test]
+fn tree_covers_the_tokens() {
+    let tokens = tokens("x = 1 + 2");
+    let tree = grammar::parse(&tokens).unwrap();
+    assert_eq!(nonterminal(&tree), Some(NonTerminal::Program));
+    assert_eq!(tree.tokens(), 0..5);
+
+    let Node::Branch { children, .. } = &tree else {
+        panic!("the start symbol is a branch");
+    };
+    assert_eq!(nonterminal(&children[0]), Some(NonTerminal::Statement));
+}
+
+#[test]
+fn syntax_error_position_and_expected() {
+    let tokens = tokens("list[1+2]");
+    let error = grammar::parse(&tokens).unwrap_err();
+    assert_eq!(error.position, 3);
+    assert_eq!(error.expected, vec!["\"]\""]);
+
+    let error = grammar::parse(&self::tokens("1 +")).unwrap_err();
+    assert_eq!(error.position, 2);
+    assert!(error.expected.contains(&"INT"));
+}
+
+#[test]
+fn tokens_outside_the_grammar_are_errors() {
+    let error = grammar::parse(&[Token::INT(1), Token::LINE_COMMENT]).unwrap_err();
+    assert_eq!(error.position, 1);
+}
+
+#[test]
+fn nonterminal_names() {
+    assert_eq!(NonTerminal::BooleanExpr.to_string(), "<boolean_expr>");
+    assert_eq!(grammar::TERMINALS.last(), Some(&"$"));
+}
+
+#[test]
+fn trace_shows_stack_input_and_action() {
+    let (steps, result) = grammar::trace(&tokens("x = 1"));
+    assert!(result.is_ok());
+    assert_eq!(steps[0].states, vec![0]);
+    assert!(matches!(steps[0].action, Move::Shift(_)));
+    assert_eq!(steps.last().unwrap().action, Move::Accept);
+    assert_eq!(steps.last().unwrap().symbols, vec!["<program>"]);
+
+    let table = parser::trace::trace(&tokens("x = 1"));
+    let lines: Vec<&str> = table.lines().collect();
+    assert!(lines[0].starts_with("Stack"));
+    assert!(lines[2].ends_with("VAR \"=\" INT $  shift 1"));
+    assert!(table.contains("reduce <atom> ::= INT\n"));
+    assert!(lines.last().unwrap().ends_with("accept"));
+
+    let table = parser::trace::trace(&tokens("1 +"));
+    assert!(table.ends_with("$  error\n"));
+}
+
+#[test]
+fn leftmost_derivation_and_tree() {
+    let tokens = tokens("x = 10 * 5");
+    let tree = grammar::parse(&tokens).unwrap();
+    let forms = derivation::leftmost_derivation(&tree, &tokens);
+    assert_eq!(forms[0], "<program>");
+    assert_eq!(forms[3], "x = <expression>");
+    assert_eq!(forms[5], "x = <term> * <factor>");
+    assert_eq!(forms.last().unwrap(), "x = 10 * 5");
+
+    let text = derivation::tree_text(&tree, &tokens);
+    assert!(
+        text.starts_with("<program>\n  <statement>\n    <assignment>\n      VAR x\n      \"=\"\n")
+    );
+    assert!(text.ends_with("            <atom>\n              INT 5\n"));
+}
+
+#[test]
+fn derivations_of_each_line() {
+    let lines = derivation::derive_lines(scanner::tokenize_spanned("1 + 2\n\n1 +\n"));
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1].line, 3);
+    assert!(lines[0]
+        .derivation_text()
+        .starts_with("   <program>\n=> <statement>\n"));
+    assert!(lines[1]
+        .derivation_text()
+        .starts_with("no derivation: syntax error at the end of the line"));
+
+    let dot = derivation::trees_to_dot(&lines);
+    assert!(dot.contains("subgraph cluster_1 {"));
+    assert!(!dot.contains("cluster_3"));
+    assert!(dot.contains("l1n0 [label=\"<program>\"];"));
+    assert!(dot.contains("[label=\"\\\"+\\\"\", shape=box];"));
+}
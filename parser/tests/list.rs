@@ -34,7 +34,7 @@ fn invalid_list_index() {
 #[test]
 fn list_with_arithmetic() {
     let input = "x = list[2] + 5";
-    let expected_output = vec![r"SyntaxError at line 1, pos 11"];
+    let expected_output = vec![r"(x=((list[(2)])+5))"];
     let tokens = scanner::tokenize(input);
     let mut parser = parser::Parser::new(vec![]);
     let output = parser.parse_tokens_fancy(tokens);
@@ -44,7 +44,7 @@ fn list_with_arithmetic() {
 #[test]
 fn list_with_arithmetic2() {
     let input = "x = 5 + list[2]";
-    let expected_output = vec![r"SyntaxError at line 1, pos 11"];
+    let expected_output = vec![r"(x=(5+(list[(2)])))"];
     let tokens = scanner::tokenize(input);
     let mut parser = parser::Parser::new(vec![]);
     let output = parser.parse_tokens_fancy(tokens);
@@ -54,7 +54,7 @@ fn list_with_arithmetic2() {
 #[test]
 fn list_with_negative_index() {
     let input = "list[-1]";
-    let expected_output = vec![r"SyntaxError at line 1, pos 6"];
+    let expected_output = vec![r"SyntaxError at line 1, pos 5"];
     let tokens = scanner::tokenize(input);
     let mut parser = parser::Parser::new(vec![]);
     let output = parser.parse_tokens_fancy(tokens);
@@ -105,3 +105,27 @@ x[0] = y[0]
     let output = parser.parse_tokens_fancy(tokens);
     assert_eq!(output, expected_output);
 }
+
+#[test]
+fn list_is_reserved() {
+    let input = "list = 5";
+    let expected_output = vec![r"Reserved word list can't be a variable at line 1, pos 1"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn list_of_largest_length() {
+    let input = "x = list[9223372036854775807]\nx[9223372036854775806] = 1\nx[9223372036854775807]";
+    let expected_output = vec![
+        r"(x=(list[(9223372036854775807)]))",
+        r"(x[(9223372036854775806)]=1)",
+        r"IndexOutOfRange at line 3, pos 22, index 9223372036854775807",
+    ];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
@@ -0,0 +1,60 @@
+use parser::{ParseError, Parser};
+
+fn messages(input: &str) -> Vec<(usize, Vec<String>)> {
+    let mut parser = Parser::new(vec![]);
+    parser
+        .parse_spanned_tokens(scanner::tokenize_spanned(input))
+        .into_iter()
+        .map(|(span, result)| {
+            let errors = result.err().unwrap_or_default();
+            (
+                span.line,
+                errors.iter().map(ParseError::to_string).collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn every_line_has_a_result() {
+    let input = "x = = 1\n2 = 3\n\ny = 2\ny +";
+    let lines: Vec<usize> = messages(input).iter().map(|(line, _)| *line).collect();
+    assert_eq!(lines, vec![1, 2, 4, 5]);
+    assert_eq!(
+        messages(input),
+        vec![
+            (1, vec!["SyntaxError at line 1, pos 3".to_string()]),
+            (2, vec!["SyntaxError at line 2, pos 1".to_string()]),
+            (4, vec![]),
+            (5, vec!["SyntaxError at line 5, pos 3".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn syntax_errors_after_a_skipped_token_are_reported() {
+    assert_eq!(
+        messages("x = = 3 + * 4")[0].1,
+        vec![
+            "SyntaxError at line 1, pos 3",
+            "SyntaxError at line 1, pos 9"
+        ]
+    );
+}
+
+#[test]
+fn every_undefined_variable_is_reported() {
+    assert_eq!(
+        messages("a * (b - 1) < c")[0].1,
+        vec![
+            "Undefined variable a at line 1, pos 1",
+            "Undefined variable b at line 1, pos 6",
+            "Undefined variable c at line 1, pos 15",
+        ]
+    );
+}
+
+#[test]
+fn every_lexical_error_is_reported() {
+    assert_eq!(messages("x = 1 $ 2 ?")[0].1.len(), 2);
+}
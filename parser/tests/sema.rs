@@ -0,0 +1,120 @@
+use parser::sema::Analyzer;
+use parser::{ExprKind, ParseError, Parser};
+
+fn messages(input: &str) -> Vec<String> {
+    let mut parser = Parser::new(vec![]);
+    parser.parse_tokens_fancy(scanner::tokenize(input))
+}
+
+#[test]
+fn parsing_alone_does_not_resolve_names() {
+    let tokens: Vec<_> = scanner::tokenize("y = x[3] / 0").flatten().collect();
+    let expr = Parser::new(tokens).parse().unwrap();
+    assert_eq!(expr.to_string(), "(y=((x[(3)])/0))");
+
+    let errors = Analyzer::new().check(&expr).unwrap_err();
+    assert!(matches!(
+        errors.as_slice(),
+        [ParseError::UndefinedVariable(name, _)] if name == "x"
+    ));
+}
+
+#[test]
+fn analyzer_remembers_assignments() {
+    let input = "x = list[2]\nx[1] = 5\nx[2] = 5\ns = \"a\"\ns * 2";
+    assert_eq!(
+        messages(input),
+        vec![
+            "(x=(list[(2)]))",
+            "(x[(1)]=5)",
+            "IndexOutOfRange at line 3, pos 1, index 2",
+            "(s=\"a\")",
+            "TypeError at line 5, pos 1, expected a number, found a string",
+        ]
+    );
+}
+
+#[test]
+fn element_assignments_store_numbers() {
+    let input = "x = list[2]
+s = \"a\"
+x[0] = s
+x[1] = 2 * 3
+s[0] = 1";
+    assert_eq!(
+        messages(input),
+        vec![
+            "(x=(list[(2)]))",
+            "(s=\"a\")",
+            "TypeError at line 3, pos 8, expected a number, found a string",
+            "(x[(1)]=(2*3))",
+            "TypeError at line 5, pos 1, expected a list, found a string",
+        ]
+    );
+}
+
+#[test]
+fn element_targets_are_structured() {
+    let tokens: Vec<_> = scanner::tokenize("x[1] = \"a\"").flatten().collect();
+    let expr = Parser::new(tokens).parse().unwrap();
+    let ExprKind::ElementAssignment(name, index, _) = &expr.kind else {
+        panic!("{:?} is not an element assignment", expr);
+    };
+    assert_eq!(name, "x");
+    assert!(matches!(index.kind, ExprKind::Int(1)));
+
+    let mut analyzer = Analyzer::new();
+    let list = Parser::new(scanner::tokenize("x = list[2]").flatten().collect()).parse();
+    analyzer.check(&list.unwrap()).unwrap();
+    let errors = analyzer.check(&expr).unwrap_err();
+    assert!(matches!(
+        errors.as_slice(),
+        [ParseError::TypeError(message, _)] if message == "expected a number, found a string"
+    ));
+}
+
+#[test]
+fn errors_point_at_the_last_token_read() {
+    assert_eq!(
+        messages("10 / (0)\nlist[0]"),
+        vec![
+            "Division by zero at line 1, pos 8",
+            "SyntaxError at line 2, pos 7",
+        ]
+    );
+}
+
+#[test]
+fn each_pass_starts_without_variables() {
+    let input = "x\nx = 1";
+    let mut parser = Parser::new(vec![]);
+    let first = parser.parse_tokens_fancy(scanner::tokenize(input));
+    let second = parser.parse_tokens_fancy(scanner::tokenize(input));
+    assert_eq!(first, second);
+    assert_eq!(first[0], "Undefined variable x at line 1, pos 1");
+}
+
+#[test]
+fn negation_and_comparison_check_types() {
+    let input = "s = \"ab\"
+y = -s
+z = -\"x\"
+\"a\" < 3
+s == \"ab\"
+x = 1
+x / -0
+x / -(0.0)";
+    assert_eq!(
+        messages(input),
+        vec![
+            "(s=\"ab\")",
+            "TypeError at line 2, pos 6, expected a number, found a string",
+            "TypeError at line 3, pos 6, expected a number, found a string",
+            "TypeError at line 4, pos 7, expected a string, found a number",
+            "(s==\"ab\")",
+            "(x=1)",
+            "Division by zero at line 7, pos 6",
+            "Division by zero at line 8, pos 10",
+        ]
+    );
+}
@@ -0,0 +1,44 @@
+use parser::{ExprKind, Parser};
+use scanner::Span;
+
+#[test]
+fn expressions_span_their_tokens() {
+    let input = "y = 1\nx = (2 + 34)";
+    let mut parser = Parser::new(vec![]);
+    let results = parser.parse_spanned_tokens(scanner::tokenize_spanned(input));
+    let expr = results[1].1.as_ref().unwrap();
+    assert_eq!(
+        expr.span,
+        Span {
+            start: 6,
+            end: 18,
+            line: 2,
+            column: 1,
+            end_line: 2,
+            end_column: 13,
+        }
+    );
+
+    // The parentheses belong to the assignment, not to the sum
+    let ExprKind::Assignment(_, value) = &expr.kind else {
+        panic!("expected an assignment, got {:?}", expr);
+    };
+    assert_eq!((value.span.start, value.span.end), (11, 17));
+    assert_eq!((value.span.column, value.span.end_column), (6, 12));
+    let ExprKind::BinaryOp(left, _, right) = &value.kind else {
+        panic!("expected a sum, got {:?}", value);
+    };
+    assert_eq!((left.span.column, right.span.column), (6, 10));
+}
+
+#[test]
+fn symbol_table_rows_use_the_assignment_span() {
+    let input = "x = 1\n\n  y = 2.5";
+    let mut parser = Parser::new(vec![]);
+    let results = parser.parse_spanned_tokens(scanner::tokenize_spanned(input));
+    let mut table = parser::symbol_table::SymbolTable::new();
+    table.process_parsed_expressions(results);
+    let rows = table.output();
+    assert!(rows[0].starts_with("x,1,1,"), "{:?}", rows);
+    assert!(rows[1].starts_with("y,3,3,"), "{:?}", rows);
+}
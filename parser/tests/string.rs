@@ -0,0 +1,40 @@
+#[test]
+fn string_literal() {
+    let input = r#""hello""#;
+    let expected_output = vec![r#""hello""#];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn string_assignment() {
+    let input = r#"label = "sum:\t"
+label"#;
+    let expected_output = vec![r#"(label="sum:\t")"#, "label"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn string_arithmetic() {
+    let input = r#""a" + 1"#;
+    let expected_output = vec!["TypeError at line 1, pos 1, expected a number, found a string"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn string_in_parentheses() {
+    let input = r#"("hi")"#;
+    let expected_output = vec![r#""hi""#];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
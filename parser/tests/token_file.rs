@@ -0,0 +1,29 @@
+use parser::Parser;
+use scanner::json::{from_jsonl, run_scanner_jsonl};
+use scanner::Backend;
+
+#[test]
+fn parses_tokens_read_back() {
+    let input = include_str!("../../sample.txt");
+    let tokens = from_jsonl(&run_scanner_jsonl(input, Backend::Logos).join("\n")).unwrap();
+
+    let from_file = Parser::new(vec![]).parse_spanned_tokens_fancy(tokens);
+    let from_source = Parser::new(vec![]).parse_tokens_fancy(scanner::tokenize(input));
+    assert_eq!(from_file, from_source);
+}
+
+#[test]
+fn hand_written_tokens() {
+    // Tokens don't have to come from the scanner
+    let text = [
+        r#"{"kind":"INT","value":2,"lexeme":"2","span":{"start":0,"end":1,"line":1,"column":1,"end_line":1,"end_column":2}}"#,
+        r#"{"kind":"POW","lexeme":"^","span":{"start":1,"end":2,"line":1,"column":2,"end_line":1,"end_column":3}}"#,
+        r#"{"kind":"REAL","value":0.5,"lexeme":"0.5","span":{"start":2,"end":5,"line":1,"column":3,"end_line":1,"end_column":6}}"#,
+    ]
+    .join("\n");
+    let tokens = from_jsonl(&text).unwrap();
+    assert_eq!(
+        Parser::new(vec![]).parse_spanned_tokens_fancy(tokens),
+        vec!["(2^0.5)"]
+    );
+}
@@ -21,3 +21,57 @@ fn variable_not_declared_2() {
     let output = parser.parse_tokens_fancy(tokens);
     assert_eq!(output, expected_output);
 }
+
+#[test]
+fn variable_not_declared_after_blank_lines() {
+    let input = "\n\n   x";
+    let expected_output = vec![r"Undefined variable x at line 3, pos 4"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn thai_variable() {
+    let input = "ผลรวม = 10\nผลรวม * 2";
+    let expected_output = vec!["(ผลรวม=10)", "(ผลรวม*2)"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn thai_variable_not_declared() {
+    let input = "ผลรวม = 10\nผลรวม + ค่า";
+    let expected_output = vec!["(ผลรวม=10)", "Undefined variable ค่า at line 2, pos 9"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn lexical_error_only_affects_its_line() {
+    let input = "x = 1\ny = x $ 2\nx + 1";
+    let expected_output = vec![
+        "(x=1)",
+        "Unexpected character at line 2, pos 7",
+        "(x+1)",
+    ];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn unterminated_string_error() {
+    let input = "x = \"abc";
+    let expected_output = vec!["Unterminated string at line 1, pos 5"];
+    let tokens = scanner::tokenize(input);
+    let mut parser = parser::Parser::new(vec![]);
+    let output = parser.parse_tokens_fancy(tokens);
+    assert_eq!(output, expected_output);
+}
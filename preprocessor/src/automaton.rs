@@ -0,0 +1,487 @@
+//! Compiles the `.lex` rules into finite automata.
+//!
+//! Every rule's regex becomes a Thompson NFA fragment, the fragments are
+//! joined under one start state, subset construction turns that NFA into a
+//! DFA and Moore's partition refinement minimizes it. Accepting states
+//! remember which rule they accept; when several rules accept the same text
+//! the one with the highest priority wins, computed the way logos does so
+//! both backends agree.
+
+use crate::spec::{Rule, SpecError};
+use regex_syntax::hir::{Class, Hir, HirKind};
+use regex_syntax::ParserBuilder;
+use std::collections::{BTreeSet, HashMap};
+
+/// An inclusive range of Unicode scalar values, kept as `u32` so ranges can
+/// be split at any point, including the surrogate gap.
+pub type CharRange = (u32, u32);
+
+#[derive(Debug, Default, Clone)]
+pub struct NfaState {
+    /// Targets reachable without consuming input.
+    pub epsilon: Vec<usize>,
+    /// Transitions taken on any character in the ranges.
+    pub transitions: Vec<(Vec<CharRange>, usize)>,
+    /// Index of the rule this state accepts.
+    pub accept: Option<usize>,
+}
+
+/// A Thompson NFA for all rules, with one start state branching into each rule.
+#[derive(Debug, Clone)]
+pub struct Nfa {
+    pub states: Vec<NfaState>,
+    pub start: usize,
+}
+
+/// A deterministic automaton over alphabet classes. State 0 is the start.
+#[derive(Debug, Clone)]
+pub struct Dfa {
+    /// Sorted, disjoint ranges that every transition either fully contains
+    /// or does not touch.
+    pub classes: Vec<CharRange>,
+    /// `table[state][class]` is the state reached on a character of `class`.
+    pub table: Vec<Vec<Option<usize>>>,
+    /// Rule accepted in each state.
+    pub accept: Vec<Option<usize>>,
+    /// NFA states each DFA state was built from, or the DFA states each
+    /// minimized state merges.
+    pub sources: Vec<Vec<usize>>,
+}
+
+/// All automata built from a `.lex` specification.
+#[derive(Debug, Clone)]
+pub struct Automata {
+    pub nfa: Nfa,
+    pub dfa: Dfa,
+    pub min_dfa: Dfa,
+    /// Priority of each rule used to settle ties between rules.
+    pub priorities: Vec<usize>,
+    /// The keyword table as `(identifier rule, word, keyword rule)`.
+    pub keywords: Vec<(usize, String, usize)>,
+}
+
+impl Automata {
+    pub fn build(rules: &[Rule]) -> Result<Self, SpecError> {
+        let mut nfa = Nfa {
+            states: vec![NfaState::default()],
+            start: 0,
+        };
+        let mut priorities = Vec::new();
+        let mut keywords = Vec::new();
+
+        for (index, rule) in rules.iter().enumerate() {
+            // Keywords are read by their identifier rule, not by an automaton
+            if let Some(name) = &rule.keyword {
+                if let (Some(identifier), Some(word)) = (
+                    rules.iter().position(|other| &other.name == name),
+                    rule.literal(),
+                ) {
+                    keywords.push((identifier, word, index));
+                }
+                priorities.push(0);
+                continue;
+            }
+
+            let hir = parse_regex(rule)?;
+            priorities.push(rule.priority.map_or_else(|| priority(&hir), |p| p as usize));
+
+            let (start, end) = nfa.compile(&hir).map_err(|message| SpecError {
+                line: rule.line,
+                message: format!("rule `{}`: {}", rule.name, message),
+            })?;
+            nfa.states[end].accept = Some(index);
+            nfa.states[0].epsilon.push(start);
+        }
+
+        let dfa = Dfa::from_nfa(&nfa, &priorities);
+        let min_dfa = dfa.minimize();
+        Ok(Automata {
+            nfa,
+            dfa,
+            min_dfa,
+            priorities,
+            keywords,
+        })
+    }
+
+    /// Rule and byte length of the token read from the start of `input` by
+    /// the minimized DFA, with identifiers looked up in the keyword table.
+    pub fn longest_match(&self, input: &str) -> Option<(usize, usize)> {
+        let (rule, len) = self.min_dfa.longest_match(input)?;
+        let keyword = self
+            .keywords
+            .iter()
+            .find(|(identifier, word, _)| *identifier == rule && word == &input[..len]);
+        Some((keyword.map_or(rule, |&(_, _, keyword)| keyword), len))
+    }
+}
+
+/// Parses a rule's regex the way logos does: Unicode aware, `.` not matching `\n`.
+pub fn parse_regex(rule: &Rule) -> Result<Hir, SpecError> {
+    ParserBuilder::new()
+        .build()
+        .parse(&rule.regex)
+        .map_err(|err| SpecError {
+            line: rule.line,
+            message: format!("invalid regex for `{}`: {}", rule.name, err),
+        })
+}
+
+/// Priority of a regex as logos computes it: two per literal character or
+/// class, nothing for optional or repeated parts, and the cheapest branch of
+/// an alternation.
+pub fn priority(hir: &Hir) -> usize {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => 0,
+        HirKind::Literal(literal) => match std::str::from_utf8(&literal.0) {
+            Ok(text) => 2 * text.chars().count(),
+            Err(_) => 2 * literal.0.len(),
+        },
+        HirKind::Class(_) => 2,
+        HirKind::Repetition(repetition) => repetition.min as usize * priority(&repetition.sub),
+        HirKind::Capture(capture) => priority(&capture.sub),
+        HirKind::Concat(parts) => parts.iter().map(priority).sum(),
+        HirKind::Alternation(branches) => branches.iter().map(priority).min().unwrap_or(0),
+    }
+}
+
+impl Nfa {
+    fn add_state(&mut self) -> usize {
+        self.states.push(NfaState::default());
+        self.states.len() - 1
+    }
+
+    /// Adds the fragment for `hir` and returns its start and end states.
+    fn compile(&mut self, hir: &Hir) -> Result<(usize, usize), String> {
+        match hir.kind() {
+            HirKind::Empty => {
+                let state = self.add_state();
+                Ok((state, state))
+            }
+            HirKind::Literal(literal) => {
+                let text = std::str::from_utf8(&literal.0)
+                    .map_err(|_| "byte literals are not supported".to_string())?;
+                let start = self.add_state();
+                let mut end = start;
+                for c in text.chars() {
+                    let next = self.add_state();
+                    self.states[end]
+                        .transitions
+                        .push((vec![(c as u32, c as u32)], next));
+                    end = next;
+                }
+                Ok((start, end))
+            }
+            HirKind::Class(class) => {
+                let ranges = match class {
+                    Class::Unicode(class) => class
+                        .ranges()
+                        .iter()
+                        .map(|range| (range.start() as u32, range.end() as u32))
+                        .collect(),
+                    Class::Bytes(class) => class
+                        .ranges()
+                        .iter()
+                        .map(|range| (range.start() as u32, range.end() as u32))
+                        .collect(),
+                };
+                let start = self.add_state();
+                let end = self.add_state();
+                self.states[start].transitions.push((ranges, end));
+                Ok((start, end))
+            }
+            HirKind::Look(_) => Err("look-around assertions are not supported".to_string()),
+            HirKind::Capture(capture) => self.compile(&capture.sub),
+            HirKind::Concat(parts) => {
+                let start = self.add_state();
+                let mut end = start;
+                for part in parts {
+                    let (part_start, part_end) = self.compile(part)?;
+                    self.states[end].epsilon.push(part_start);
+                    end = part_end;
+                }
+                Ok((start, end))
+            }
+            HirKind::Alternation(branches) => {
+                let start = self.add_state();
+                let end = self.add_state();
+                for branch in branches {
+                    let (branch_start, branch_end) = self.compile(branch)?;
+                    self.states[start].epsilon.push(branch_start);
+                    self.states[branch_end].epsilon.push(end);
+                }
+                Ok((start, end))
+            }
+            HirKind::Repetition(repetition) => {
+                if !repetition.greedy {
+                    return Err("non-greedy repetition is not supported".to_string());
+                }
+
+                // The required copies first, then either a loop or optional copies
+                let start = self.add_state();
+                let mut end = start;
+                for _ in 0..repetition.min {
+                    let (sub_start, sub_end) = self.compile(&repetition.sub)?;
+                    self.states[end].epsilon.push(sub_start);
+                    end = sub_end;
+                }
+
+                match repetition.max {
+                    None => {
+                        let (sub_start, sub_end) = self.compile(&repetition.sub)?;
+                        let exit = self.add_state();
+                        self.states[end].epsilon.extend([sub_start, exit]);
+                        self.states[sub_end].epsilon.extend([sub_start, exit]);
+                        end = exit;
+                    }
+                    Some(max) => {
+                        let exit = self.add_state();
+                        for _ in repetition.min..max {
+                            let (sub_start, sub_end) = self.compile(&repetition.sub)?;
+                            self.states[end].epsilon.extend([sub_start, exit]);
+                            end = sub_end;
+                        }
+                        self.states[end].epsilon.push(exit);
+                        end = exit;
+                    }
+                }
+                Ok((start, end))
+            }
+        }
+    }
+
+    /// Rules whose regex matches all of `text`, in rule order.
+    pub fn matching_rules(&self, text: &str) -> Vec<usize> {
+        let mut states = self.epsilon_closure([self.start]);
+
+        for c in text.chars() {
+            let c = c as u32;
+            let targets: Vec<usize> = states
+                .iter()
+                .flat_map(|&state| &self.states[state].transitions)
+                .filter(|(ranges, _)| ranges.iter().any(|&(start, end)| start <= c && c <= end))
+                .map(|&(_, target)| target)
+                .collect();
+            states = self.epsilon_closure(targets);
+        }
+
+        let rules: BTreeSet<usize> = states
+            .iter()
+            .filter_map(|&state| self.states[state].accept)
+            .collect();
+        rules.into_iter().collect()
+    }
+
+    /// All states reachable from `states` through epsilon transitions.
+    pub fn epsilon_closure(&self, states: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        let mut closure = BTreeSet::new();
+        let mut stack: Vec<usize> = states.into_iter().collect();
+
+        while let Some(state) = stack.pop() {
+            if closure.insert(state) {
+                stack.extend(&self.states[state].epsilon);
+            }
+        }
+
+        closure.into_iter().collect()
+    }
+
+    /// Splits the characters used by the transitions into disjoint classes.
+    pub fn alphabet(&self) -> Vec<CharRange> {
+        let mut bounds = BTreeSet::new();
+        for state in &self.states {
+            for (ranges, _) in &state.transitions {
+                for &(start, end) in ranges {
+                    bounds.insert(start);
+                    bounds.insert(end + 1);
+                }
+            }
+        }
+
+        let bounds: Vec<u32> = bounds.into_iter().collect();
+        bounds
+            .windows(2)
+            .map(|pair| (pair[0], pair[1] - 1))
+            .collect()
+    }
+}
+
+impl Dfa {
+    /// Subset construction.
+    fn from_nfa(nfa: &Nfa, priorities: &[usize]) -> Self {
+        let classes = nfa.alphabet();
+        let class_of = |c: u32| classes.partition_point(|&(_, end)| end < c);
+
+        let mut dfa = Dfa {
+            classes: classes.clone(),
+            table: Vec::new(),
+            accept: Vec::new(),
+            sources: Vec::new(),
+        };
+        let mut ids: HashMap<Vec<usize>, usize> = HashMap::new();
+        let start = nfa.epsilon_closure([nfa.start]);
+        ids.insert(start.clone(), 0);
+        dfa.push_state(nfa, start, priorities);
+
+        let mut next = 0;
+        while next < dfa.sources.len() {
+            let mut targets: Vec<Vec<usize>> = vec![Vec::new(); classes.len()];
+            for &state in &dfa.sources[next] {
+                for (ranges, target) in &nfa.states[state].transitions {
+                    for &(start, end) in ranges {
+                        for class_targets in &mut targets[class_of(start)..=class_of(end)] {
+                            class_targets.push(*target);
+                        }
+                    }
+                }
+            }
+
+            let mut row = vec![None; classes.len()];
+            for (class, class_targets) in targets.into_iter().enumerate() {
+                if class_targets.is_empty() {
+                    continue;
+                }
+                let closure = nfa.epsilon_closure(class_targets);
+                let id = match ids.get(&closure) {
+                    Some(&id) => id,
+                    None => {
+                        let id = dfa.sources.len();
+                        ids.insert(closure.clone(), id);
+                        dfa.push_state(nfa, closure, priorities);
+                        id
+                    }
+                };
+                row[class] = Some(id);
+            }
+            dfa.table[next] = row;
+            next += 1;
+        }
+
+        dfa
+    }
+
+    fn push_state(&mut self, nfa: &Nfa, states: Vec<usize>, priorities: &[usize]) {
+        // The highest priority wins, then the rule written first
+        let accept = states
+            .iter()
+            .filter_map(|&state| nfa.states[state].accept)
+            .min_by_key(|&rule| (std::cmp::Reverse(priorities[rule]), rule));
+        self.table.push(Vec::new());
+        self.accept.push(accept);
+        self.sources.push(states);
+    }
+
+    /// Moore's algorithm: split states until every block agrees on its
+    /// accepted rule and on the block each class leads to.
+    pub fn minimize(&self) -> Dfa {
+        let mut block: Vec<usize> = Vec::new();
+        let mut ids: HashMap<Option<usize>, usize> = HashMap::new();
+        for accept in &self.accept {
+            let next_id = ids.len();
+            block.push(*ids.entry(*accept).or_insert(next_id));
+        }
+        let mut count = ids.len();
+
+        loop {
+            let mut ids: HashMap<(usize, Vec<Option<usize>>), usize> = HashMap::new();
+            let refined: Vec<usize> = (0..self.len())
+                .map(|state| {
+                    let signature = (
+                        block[state],
+                        self.table[state]
+                            .iter()
+                            .map(|target| target.map(|target| block[target]))
+                            .collect(),
+                    );
+                    let next_id = ids.len();
+                    *ids.entry(signature).or_insert(next_id)
+                })
+                .collect();
+
+            let done = ids.len() == count;
+            count = ids.len();
+            block = refined;
+            if done {
+                break;
+            }
+        }
+
+        // Blocks are numbered in order of first appearance, so the start state stays 0
+        let mut min = Dfa {
+            classes: self.classes.clone(),
+            table: vec![Vec::new(); count],
+            accept: vec![None; count],
+            sources: vec![Vec::new(); count],
+        };
+        for state in 0..self.len() {
+            let id = block[state];
+            if min.sources[id].is_empty() {
+                min.table[id] = self.table[state]
+                    .iter()
+                    .map(|target| target.map(|target| block[target]))
+                    .collect();
+                min.accept[id] = self.accept[state];
+            }
+            min.sources[id].push(state);
+        }
+        min
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Transitions of `state` as sorted `(first, last, target)` character
+    /// ranges, merging neighbouring classes with the same target.
+    pub fn transitions(&self, state: usize) -> Vec<(char, char, usize)> {
+        let mut ranges: Vec<(u32, u32, usize)> = Vec::new();
+        for (class, target) in self.table[state].iter().enumerate() {
+            let Some(target) = *target else { continue };
+            let (start, end) = self.classes[class];
+            match ranges.last_mut() {
+                Some(last) if last.2 == target && last.1 + 1 == start => last.1 = end,
+                _ => ranges.push((start, end, target)),
+            }
+        }
+
+        // Characters can't be surrogates, so trim the gap out of each range
+        ranges
+            .into_iter()
+            .filter_map(|(start, end, target)| {
+                let start = char::from_u32(start).or_else(|| char::from_u32(0xE000))?;
+                let end = char::from_u32(end).unwrap_or('\u{D7FF}');
+                (start <= end).then_some((start, end, target))
+            })
+            .collect()
+    }
+
+    /// Runs the automaton over `input`, returning the rule and byte length of
+    /// the longest match at its start.
+    pub fn longest_match(&self, input: &str) -> Option<(usize, usize)> {
+        let mut state = 0;
+        let mut last = None;
+
+        for (offset, c) in input.char_indices() {
+            let class = self.classes.partition_point(|&(_, end)| end < c as u32);
+            let covers = self
+                .classes
+                .get(class)
+                .is_some_and(|&(start, _)| start <= c as u32);
+            match self.table[state].get(class).copied().flatten() {
+                Some(next) if covers => {
+                    state = next;
+                    if let Some(rule) = self.accept[state] {
+                        last = Some((rule, offset + c.len_utf8()));
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        last
+    }
+}
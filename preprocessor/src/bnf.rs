@@ -0,0 +1,408 @@
+//! Reads the BNF of a `.grammar` file.
+//!
+//! Each rule is `<name> ::= alternative | alternative ...` and may continue
+//! on following lines that start with `|`. An alternative is a sequence of
+//! `<nonterminals>`, token names such as `INT` and quoted token texts such as
+//! `"+"`; `ε` stands for the empty sequence. The first rule defines the start
+//! symbol, and lines starting with `#` are comments.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A symbol on the right-hand side of a production, by index into
+/// [`Grammar::terminals`] or [`Grammar::nonterminals`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Symbol {
+    Terminal(usize),
+    NonTerminal(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Production {
+    pub lhs: usize,
+    pub rhs: Vec<Symbol>,
+    /// Line of the `.grammar` file the alternative was read from.
+    pub line: usize,
+}
+
+/// The rules of a `.grammar` file.
+#[derive(Debug, Clone, Default)]
+pub struct Grammar {
+    /// Nonterminal names without their angle brackets. The first one is the
+    /// start symbol.
+    pub nonterminals: Vec<String>,
+    /// Terminals as written in the grammar: a token name (`INT`) or a quoted
+    /// token text (`"+"`).
+    pub terminals: Vec<String>,
+    pub productions: Vec<Production>,
+}
+
+/// Nullable nonterminals and the FIRST set of each nonterminal, as terminal
+/// indices.
+#[derive(Debug, Clone)]
+pub struct FirstSets {
+    pub nullable: Vec<bool>,
+    pub first: Vec<BTreeSet<usize>>,
+}
+
+impl FirstSets {
+    /// FIRST set of a sequence of symbols and whether it derives the empty
+    /// string.
+    pub fn of(&self, symbols: &[Symbol]) -> (BTreeSet<usize>, bool) {
+        let mut first = BTreeSet::new();
+        for symbol in symbols {
+            match *symbol {
+                Symbol::Terminal(terminal) => {
+                    first.insert(terminal);
+                    return (first, false);
+                }
+                Symbol::NonTerminal(nonterminal) => {
+                    first.extend(&self.first[nonterminal]);
+                    if !self.nullable[nonterminal] {
+                        return (first, false);
+                    }
+                }
+            }
+        }
+        (first, true)
+    }
+}
+
+impl Grammar {
+    /// Index of the end-of-input terminal `$`, one past the grammar's own.
+    pub fn end(&self) -> usize {
+        self.terminals.len()
+    }
+
+    /// Name of a terminal, `$` for the end of input.
+    pub fn terminal_name(&self, terminal: usize) -> &str {
+        self.terminals.get(terminal).map_or("$", String::as_str)
+    }
+
+    /// The symbol as written in the grammar, e.g. `<term>` or `"*"`.
+    pub fn symbol_name(&self, symbol: Symbol) -> String {
+        match symbol {
+            Symbol::Terminal(terminal) => self.terminal_name(terminal).to_string(),
+            Symbol::NonTerminal(nonterminal) => format!("<{}>", self.nonterminals[nonterminal]),
+        }
+    }
+
+    /// A production as written in the grammar, e.g. `<factor> ::= <atom>`.
+    pub fn production_text(&self, production: usize) -> String {
+        let production = &self.productions[production];
+        let rhs: Vec<String> = production
+            .rhs
+            .iter()
+            .map(|&symbol| self.symbol_name(symbol))
+            .collect();
+        let rhs = if rhs.is_empty() {
+            "ε".to_string()
+        } else {
+            rhs.join(" ")
+        };
+        format!("<{}> ::= {}", self.nonterminals[production.lhs], rhs)
+    }
+
+    /// Line of the first production that uses `terminal`.
+    pub fn terminal_line(&self, terminal: usize) -> usize {
+        self.productions
+            .iter()
+            .find(|production| production.rhs.contains(&Symbol::Terminal(terminal)))
+            .map_or(0, |production| production.line)
+    }
+
+    /// The grammar with a new start symbol `<start'> ::= <start>` as its last
+    /// production, which LR parsers accept on.
+    pub fn augmented(&self) -> Grammar {
+        let mut grammar = self.clone();
+        let start = grammar.nonterminals.len();
+        grammar
+            .nonterminals
+            .push(format!("{}'", self.nonterminals[0]));
+        grammar.productions.push(Production {
+            lhs: start,
+            rhs: vec![Symbol::NonTerminal(0)],
+            line: 0,
+        });
+        grammar
+    }
+
+    /// Computes the nullable nonterminals and FIRST sets by iterating to a
+    /// fixed point.
+    pub fn first_sets(&self) -> FirstSets {
+        let mut sets = FirstSets {
+            nullable: vec![false; self.nonterminals.len()],
+            first: vec![BTreeSet::new(); self.nonterminals.len()],
+        };
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                let (first, nullable) = sets.of(&production.rhs);
+                let lhs = production.lhs;
+                if nullable && !sets.nullable[lhs] {
+                    sets.nullable[lhs] = true;
+                    changed = true;
+                }
+                let before = sets.first[lhs].len();
+                sets.first[lhs].extend(first);
+                changed |= sets.first[lhs].len() != before;
+            }
+        }
+
+        sets
+    }
+
+    /// Computes the FOLLOW set of each nonterminal: the terminals that can
+    /// come right after it, with `$` after the start symbol.
+    pub fn follow_sets(&self, first: &FirstSets) -> Vec<BTreeSet<usize>> {
+        let mut follow = vec![BTreeSet::new(); self.nonterminals.len()];
+        follow[0].insert(self.end());
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                for (index, symbol) in production.rhs.iter().enumerate() {
+                    let Symbol::NonTerminal(nonterminal) = *symbol else {
+                        continue;
+                    };
+                    let (mut after, nullable) = first.of(&production.rhs[index + 1..]);
+                    if nullable {
+                        after.extend(&follow[production.lhs]);
+                    }
+                    let before = follow[nonterminal].len();
+                    follow[nonterminal].extend(after);
+                    changed |= follow[nonterminal].len() != before;
+                }
+            }
+        }
+
+        follow
+    }
+}
+
+/// Writes the grammar back as a `.grammar` file, one rule per line.
+impl fmt::Display for Grammar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (nonterminal, name) in self.nonterminals.iter().enumerate() {
+            let alternatives: Vec<String> = self
+                .productions
+                .iter()
+                .filter(|production| production.lhs == nonterminal)
+                .map(|production| {
+                    if production.rhs.is_empty() {
+                        return "ε".to_string();
+                    }
+                    let symbols: Vec<String> = production
+                        .rhs
+                        .iter()
+                        .map(|&symbol| self.symbol_name(symbol))
+                        .collect();
+                    symbols.join(" ")
+                })
+                .collect();
+            if !alternatives.is_empty() {
+                writeln!(f, "<{}> ::= {}", name, alternatives.join(" | "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A problem found while reading the `.grammar` file.
+#[derive(Debug, Clone)]
+pub struct GrammarError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for GrammarError {}
+
+/// Reads every rule of a `.grammar` file.
+pub fn parse_grammar(source: &str) -> Result<Grammar, GrammarError> {
+    let mut grammar = Grammar::default();
+    // Nonterminals used so far, with the line they were first used on
+    let mut used: Vec<(usize, usize)> = Vec::new();
+    let mut defined: Vec<bool> = Vec::new();
+    let mut current: Option<usize> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let error = |message: String| GrammarError {
+            line: line_number,
+            message,
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let alternatives = match line.strip_prefix('|') {
+            Some(rest) => {
+                current.ok_or_else(|| error("`|` before any rule".to_string()))?;
+                rest
+            }
+            None => {
+                let (name, rest) = line
+                    .split_once("::=")
+                    .ok_or_else(|| error(format!("expected `<name> ::=`, found `{}`", line)))?;
+                let name = name
+                    .trim()
+                    .strip_prefix('<')
+                    .and_then(|name| name.strip_suffix('>'))
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| error(format!("expected `<name>`, found `{}`", name.trim())))?;
+                let lhs = nonterminal(&mut grammar, &mut defined, name);
+                if defined[lhs] {
+                    return Err(error(format!("rule `<{}>` is defined twice", name)));
+                }
+                defined[lhs] = true;
+                current = Some(lhs);
+                rest
+            }
+        };
+        let lhs = current.expect("a rule is open");
+
+        for alternative in split_alternatives(alternatives).map_err(error)? {
+            let mut rhs = Vec::new();
+            for symbol in alternative {
+                match symbol {
+                    Word::NonTerminal(name) => {
+                        let index = nonterminal(&mut grammar, &mut defined, &name);
+                        if !used.iter().any(|&(used, _)| used == index) {
+                            used.push((index, line_number));
+                        }
+                        rhs.push(Symbol::NonTerminal(index));
+                    }
+                    Word::Terminal(text) => {
+                        let index = match grammar.terminals.iter().position(|other| other == &text)
+                        {
+                            Some(index) => index,
+                            None => {
+                                grammar.terminals.push(text);
+                                grammar.terminals.len() - 1
+                            }
+                        };
+                        rhs.push(Symbol::Terminal(index));
+                    }
+                    Word::Empty => {}
+                }
+            }
+            grammar.productions.push(Production {
+                lhs,
+                rhs,
+                line: line_number,
+            });
+        }
+    }
+
+    if grammar.productions.is_empty() {
+        return Err(GrammarError {
+            line: 1,
+            message: "the grammar has no rules".to_string(),
+        });
+    }
+    if let Some(&(index, line)) = used.iter().find(|&&(index, _)| !defined[index]) {
+        return Err(GrammarError {
+            line,
+            message: format!(
+                "rule `<{}>` is used but never defined",
+                grammar.nonterminals[index]
+            ),
+        });
+    }
+
+    Ok(grammar)
+}
+
+// Index of a nonterminal, adding it the first time it is seen
+fn nonterminal(grammar: &mut Grammar, defined: &mut Vec<bool>, name: &str) -> usize {
+    match grammar.nonterminals.iter().position(|other| other == name) {
+        Some(index) => index,
+        None => {
+            grammar.nonterminals.push(name.to_string());
+            defined.push(false);
+            grammar.nonterminals.len() - 1
+        }
+    }
+}
+
+enum Word {
+    NonTerminal(String),
+    Terminal(String),
+    Empty,
+}
+
+// Splits the right-hand side of a rule into alternatives of symbols
+fn split_alternatives(text: &str) -> Result<Vec<Vec<Word>>, String> {
+    let mut alternatives = vec![Vec::new()];
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            _ if c.is_whitespace() => {}
+            '|' => alternatives.push(Vec::new()),
+            'ε' => alternatives.last_mut().unwrap().push(Word::Empty),
+            '<' => {
+                let end = text[start..]
+                    .find('>')
+                    .ok_or_else(|| format!("unterminated `{}`", &text[start..]))?;
+                let name = &text[start + 1..start + end];
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    return Err(format!("invalid nonterminal `<{}>`", name));
+                }
+                alternatives
+                    .last_mut()
+                    .unwrap()
+                    .push(Word::NonTerminal(name.to_string()));
+                while chars.next_if(|&(index, _)| index <= start + end).is_some() {}
+            }
+            '"' => {
+                let mut end = None;
+                let mut escaped = false;
+                for (index, c) in chars.by_ref() {
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => {
+                            end = Some(index);
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                let end = end.ok_or_else(|| format!("unterminated `{}`", &text[start..]))?;
+                if end == start + 1 {
+                    return Err("empty token text `\"\"`, write `ε` for nothing".to_string());
+                }
+                alternatives
+                    .last_mut()
+                    .unwrap()
+                    .push(Word::Terminal(text[start..=end].to_string()));
+            }
+            _ if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((index, c)) =
+                    chars.next_if(|&(_, c)| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    end = index + c.len_utf8();
+                }
+                alternatives
+                    .last_mut()
+                    .unwrap()
+                    .push(Word::Terminal(text[start..end].to_string()));
+            }
+            _ => return Err(format!("unexpected `{}`", c)),
+        }
+    }
+
+    Ok(alternatives)
+}
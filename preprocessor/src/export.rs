@@ -0,0 +1,269 @@
+//! Graphviz DOT and CSV transition tables of the automata, so the NFA, DFA
+//! and minimized DFA behind the lexer can be looked at.
+//!
+//! Accepting states are labelled with the `Token` variant of their rule, and
+//! an identifier rule's states also list the keywords looked up from it.
+//! Unicode classes such as `\p{XID_Start}` span hundreds of ranges, so long
+//! range lists are shortened in labels.
+
+use crate::automaton::{Automata, CharRange, Dfa, Nfa};
+use crate::spec::{Rule, SpecError};
+use std::collections::BTreeMap;
+
+/// Ranges shown in a label before the rest are summarized.
+const SHOWN_RANGES: usize = 3;
+
+/// The NFA, DFA and minimized DFA of `rules` as DOT and CSV files, named
+/// `{name}.nfa.dot`, `{name}.dfa.csv`, `{name}.min.dot` and so on. The
+/// keyword table is written to `{name}.keywords.csv` when `rules` has
+/// keywords.
+pub fn export(name: &str, rules: &[Rule]) -> Result<Vec<(String, String)>, SpecError> {
+    let automata = Automata::build(rules)?;
+    let mut files = vec![
+        ("nfa.dot", nfa_to_dot(name, &automata.nfa, rules)),
+        ("nfa.csv", nfa_to_csv(&automata.nfa, rules)),
+        ("dfa.dot", dfa_to_dot(name, &automata.dfa, rules)),
+        ("dfa.csv", dfa_to_csv(&automata.dfa, rules)),
+        ("min.dot", dfa_to_dot(name, &automata.min_dfa, rules)),
+        ("min.csv", dfa_to_csv(&automata.min_dfa, rules)),
+    ];
+    if !automata.keywords.is_empty() {
+        files.push(("keywords.csv", keywords_to_csv(&automata, rules)));
+    }
+
+    Ok(files
+        .into_iter()
+        .map(|(suffix, contents)| (format!("{}.{}", name, suffix), contents))
+        .collect())
+}
+
+pub fn nfa_to_dot(name: &str, nfa: &Nfa, rules: &[Rule]) -> String {
+    let mut dot = header(&format!("{} NFA", name), nfa.start);
+    for (state, nfa_state) in nfa.states.iter().enumerate() {
+        dot.push_str(&node(state, nfa_state.accept.map(|rule| &rules[rule]), rules));
+        for target in &nfa_state.epsilon {
+            dot.push_str(&format!(
+                "    {} -> {} [label=\"ε\", style=dashed];\n",
+                state, target
+            ));
+        }
+        for (ranges, target) in &nfa_state.transitions {
+            dot.push_str(&edge(state, *target, ranges));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+pub fn dfa_to_dot(name: &str, dfa: &Dfa, rules: &[Rule]) -> String {
+    let mut dot = header(&format!("{} DFA", name), 0);
+    for state in 0..dfa.len() {
+        dot.push_str(&node(state, dfa.accept[state].map(|rule| &rules[rule]), rules));
+
+        // One edge per target, labelled with every range leading there
+        let mut targets: BTreeMap<usize, Vec<CharRange>> = BTreeMap::new();
+        for (first, last, target) in dfa.transitions(state) {
+            targets
+                .entry(target)
+                .or_default()
+                .push((first as u32, last as u32));
+        }
+        for (target, ranges) in targets {
+            dot.push_str(&edge(state, target, &ranges));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// One row per state: its accepted token, epsilon targets and the targets of
+/// each group of characters.
+pub fn nfa_to_csv(nfa: &Nfa, rules: &[Rule]) -> String {
+    let classes = nfa.alphabet();
+    let cells: Vec<Vec<String>> = nfa
+        .states
+        .iter()
+        .map(|nfa_state| {
+            classes
+                .iter()
+                .map(|&(start, _)| {
+                    let targets: Vec<String> = nfa_state
+                        .transitions
+                        .iter()
+                        .filter(|(ranges, _)| ranges.iter().any(|&(s, e)| s <= start && start <= e))
+                        .map(|(_, target)| target.to_string())
+                        .collect();
+                    targets.join(" ")
+                })
+                .collect()
+        })
+        .collect();
+
+    let rows = nfa.states.iter().enumerate().map(|(state, nfa_state)| {
+        let epsilon: Vec<String> = nfa_state.epsilon.iter().map(usize::to_string).collect();
+        vec![
+            state.to_string(),
+            accept_label(nfa_state.accept.map(|rule| &rules[rule]), rules),
+            epsilon.join(" "),
+        ]
+    });
+    table(&["state", "accept", "ε"], rows, &classes, cells)
+}
+
+/// One row per state: its accepted token and the target of each group of
+/// characters. State 0 is the start.
+pub fn dfa_to_csv(dfa: &Dfa, rules: &[Rule]) -> String {
+    let cells: Vec<Vec<String>> = dfa
+        .table
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|target| target.map_or(String::new(), |target| target.to_string()))
+                .collect()
+        })
+        .collect();
+
+    let rows = (0..dfa.len()).map(|state| {
+        vec![
+            state.to_string(),
+            accept_label(dfa.accept[state].map(|rule| &rules[rule]), rules),
+        ]
+    });
+    table(&["state", "accept"], rows, &dfa.classes, cells)
+}
+
+/// One row per keyword: the word, the rule that reads it and the token it
+/// becomes.
+pub fn keywords_to_csv(automata: &Automata, rules: &[Rule]) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(["word", "rule", "token"])
+        .expect("writing to memory");
+    for (identifier, word, keyword) in &automata.keywords {
+        writer
+            .write_record([word, &rules[*identifier].name, &rules[*keyword].name])
+            .expect("writing to memory");
+    }
+    String::from_utf8(writer.into_inner().expect("writing to memory")).unwrap()
+}
+
+/// Writes a CSV with the `fixed` columns followed by one column per group of
+/// classes that behave the same in every state. Classes no state moves on are
+/// left out.
+fn table(
+    fixed: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+    classes: &[CharRange],
+    cells: Vec<Vec<String>>,
+) -> String {
+    let mut groups: Vec<(Vec<CharRange>, Vec<String>)> = Vec::new();
+    for (class, &range) in classes.iter().enumerate() {
+        let column: Vec<String> = cells.iter().map(|row| row[class].clone()).collect();
+        if column.iter().all(String::is_empty) {
+            continue;
+        }
+        match groups.iter_mut().find(|(_, existing)| *existing == column) {
+            Some((ranges, _)) => ranges.push(range),
+            None => groups.push((vec![range], column)),
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let header = fixed
+        .iter()
+        .map(|name| name.to_string())
+        .chain(groups.iter().map(|(ranges, _)| ranges_label(ranges)));
+    writer.write_record(header).expect("writing to memory");
+    for (state, row) in rows.enumerate() {
+        let targets = groups.iter().map(|(_, column)| column[state].clone());
+        writer
+            .write_record(row.into_iter().chain(targets))
+            .expect("writing to memory");
+    }
+
+    String::from_utf8(writer.into_inner().expect("writing to memory")).unwrap()
+}
+
+fn header(title: &str, start: usize) -> String {
+    format!(
+        "digraph \"{}\" {{\n    rankdir=LR;\n    node [shape=circle];\n    start [shape=point];\n    start -> {};\n",
+        escape(title),
+        start
+    )
+}
+
+fn node(state: usize, accept: Option<&Rule>, rules: &[Rule]) -> String {
+    match accept {
+        Some(rule) => format!(
+            "    {} [shape=doublecircle, label=\"{}\\n{}\"];\n",
+            state,
+            state,
+            escape(&accept_label(Some(rule), rules))
+        ),
+        None => format!("    {};\n", state),
+    }
+}
+
+fn edge(from: usize, to: usize, ranges: &[CharRange]) -> String {
+    format!(
+        "    {} -> {} [label=\"{}\"];\n",
+        from,
+        to,
+        escape(&ranges_label(ranges))
+    )
+}
+
+/// The `Token` variant a rule produces; skipped rules produce none. An
+/// identifier rule also names the keywords it reads, e.g. `VAR (list: LIST)`.
+fn accept_label(rule: Option<&Rule>, rules: &[Rule]) -> String {
+    let Some(rule) = rule else {
+        return String::new();
+    };
+    if rule.skip {
+        return format!("{} (skip)", rule.name);
+    }
+    let keywords: Vec<String> = rules
+        .iter()
+        .filter(|keyword| keyword.keyword.as_ref() == Some(&rule.name))
+        .filter_map(|keyword| Some(format!("{}: {}", keyword.literal()?, keyword.name)))
+        .collect();
+    if keywords.is_empty() {
+        rule.name.clone()
+    } else {
+        format!("{} ({})", rule.name, keywords.join(", "))
+    }
+}
+
+/// Ranges written as `a-z 0-9 _`, shortened when there are many.
+pub fn ranges_label(ranges: &[CharRange]) -> String {
+    let mut parts: Vec<String> = ranges
+        .iter()
+        .take(SHOWN_RANGES)
+        .map(|&(start, end)| {
+            if start == end {
+                char_label(start)
+            } else {
+                format!("{}-{}", char_label(start), char_label(end))
+            }
+        })
+        .collect();
+    if ranges.len() > SHOWN_RANGES {
+        parts.push(format!("… +{} ranges", ranges.len() - SHOWN_RANGES));
+    }
+    parts.join(" ")
+}
+
+/// Printable ASCII as itself, everything else as an escape or code point.
+fn char_label(code: u32) -> String {
+    match char::from_u32(code) {
+        Some('\n') => "\\n".to_string(),
+        Some('\r') => "\\r".to_string(),
+        Some('\t') => "\\t".to_string(),
+        Some(c) if c.is_ascii_graphic() => c.to_string(),
+        _ => format!("U+{:04X}", code),
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,294 @@
+use crate::automaton::Automata;
+use crate::native::generate_native;
+use crate::spec::{Rule, Spec, SpecError};
+use std::collections::BTreeSet;
+
+/// Generates the logos `Token` enum, its helper impls and the `native`
+/// table-driven lexer. Fails if a rule's regex can't be compiled.
+pub fn generate(spec: &Spec) -> Result<String, SpecError> {
+    let rules = &spec.rules;
+    let automata = Automata::build(rules)?;
+    let mut rust_code = String::from(
+        r#"// This file is auto-generated by the preprocessor
+// The preprocessor reads the .lex file and generates the Rust code for logos
+// and for the table-driven native lexer at the end of the file
+// It is regenerated by the scanner's build script whenever hiwkhao.lex changes
+
+use logos::Logos;
+use std::fmt;
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+"#,
+    );
+    let derives: Vec<&str> = ["Logos", "Clone", "Debug", "PartialEq"]
+        .into_iter()
+        .chain(spec.derives.iter().map(String::as_str))
+        .collect();
+    rust_code.push_str(&format!("#[derive({})]\n", derives.join(", ")));
+    for attribute in &spec.attributes {
+        rust_code.push_str(&format!("#[{}]\n", attribute));
+    }
+    if let Some(error) = &spec.error {
+        rust_code.push_str(&format!("#[logos(error = {})]\n", error));
+    }
+    rust_code.push_str("pub enum Token {\n");
+
+    for rule in rules {
+        // An identifier rule with keywords is read through its first keyword:
+        // the callback of a variant without a payload may return any `Token`
+        let reader = match &rule.keyword {
+            Some(name) => spec
+                .keywords_of(name)
+                .next()
+                .filter(|first| first.name == rule.name)
+                .and_then(|_| rules.iter().find(|other| &other.name == name)),
+            None if spec.keywords_of(&rule.name).next().is_some() => None,
+            None => Some(rule),
+        };
+
+        if let Some(reader) = reader {
+            let mut arguments = vec![raw_string(&reader.regex)];
+            if reader.name != rule.name {
+                arguments.push(format!("|lex| {}", read_identifier(reader, "lex")));
+            } else if rule.skip {
+                arguments.push("logos::skip".to_string());
+            } else if let Some(callback) = &rule.callback {
+                arguments.push(callback.clone());
+            } else if rule.payload.is_some() {
+                arguments
+                    .push("|lex| lex.slice().parse().map_err(|_| Default::default())".to_string());
+            }
+            if let Some(priority) = reader.priority {
+                arguments.push(format!("priority = {}", priority));
+            }
+            rust_code.push_str(&format!("    #[regex({})]\n", arguments.join(", ")));
+        }
+        match &rule.payload {
+            Some(payload) => rust_code.push_str(&format!("    {}({}),\n", rule.name, payload)),
+            None => rust_code.push_str(&format!("    {},\n", rule.name)),
+        }
+    }
+
+    // Input no rule matches becomes ERR, carrying the lexer error if there is one
+    match &spec.error {
+        Some(error) => {
+            rust_code.push_str(&format!("    #[allow(dead_code)]\n    ERR({}),\n", error))
+        }
+        None => rust_code.push_str("    #[allow(dead_code)]\n    ERR,\n"),
+    }
+    rust_code.push_str("}\n");
+
+    let err_pattern = if spec.error.is_some() {
+        "ERR(_)"
+    } else {
+        "ERR"
+    };
+    rust_code.push_str(
+        r#"
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+"#,
+    );
+    for rule in rules {
+        match (&rule.payload, &rule.display) {
+            (Some(_), None) => rust_code.push_str(&format!(
+                "            Self::{}(value) => write!(f, {:?}, value),\n",
+                rule.name,
+                rule.format.as_deref().unwrap_or("{}")
+            )),
+            (Some(_), Some(display)) => rust_code.push_str(&format!(
+                "            Self::{}(_) => f.write_str({:?}),\n",
+                rule.name, display
+            )),
+            (None, _) => rust_code.push_str(&format!(
+                "            Self::{} => f.write_str({:?}),\n",
+                rule.name,
+                rule.display_text()
+            )),
+        }
+    }
+    rust_code.push_str(&format!(
+        r#"            Self::{} => f.write_str("<error>"),
+        }}
+    }}
+}}
+"#,
+        err_pattern
+    ));
+
+    rust_code.push_str(
+        r#"
+impl Token {
+    /// Name of the token in `.tok` output.
+    pub fn label(&self) -> &'static str {
+        match self {
+"#,
+    );
+    for rule in rules {
+        let pattern = match rule.payload {
+            Some(_) => format!("Self::{}(_)", rule.name),
+            None => format!("Self::{}", rule.name),
+        };
+        rust_code.push_str(&format!(
+            "            {} => {:?},\n",
+            pattern,
+            rule.label_text()
+        ));
+    }
+    rust_code.push_str(&format!(
+        r#"            Self::{} => "ERR",
+        }}
+    }}
+"#,
+        err_pattern
+    ));
+
+    let trivia: Vec<String> = rules
+        .iter()
+        .filter(|rule| rule.trivia)
+        .map(|rule| match rule.payload {
+            Some(_) => format!("Self::{}(_)", rule.name),
+            None => format!("Self::{}", rule.name),
+        })
+        .collect();
+    let trivia = if trivia.is_empty() {
+        "false".to_string()
+    } else {
+        format!("matches!(self, {})", trivia.join(" | "))
+    };
+    rust_code.push_str(&format!(
+        r#"
+    /// Whether the token is trivia (e.g. a comment) that the parser ignores.
+    pub fn is_trivia(&self) -> bool {{
+        {}
+    }}
+"#,
+        trivia
+    ));
+
+    // Identifier rules with keywords turn into the keyword their word spells
+    rust_code.push_str(
+        r#"
+    /// The keyword an identifier token spells, or the token itself.
+    pub fn resolve_keyword(self) -> Self {
+        match &self {
+"#,
+    );
+    let mut keywords = Vec::new();
+    for rule in rules {
+        let mut table = spec.keywords_of(&rule.name).peekable();
+        if table.peek().is_none() {
+            continue;
+        }
+        rust_code.push_str(&format!(
+            "            Self::{}(word) => match AsRef::<str>::as_ref(word) {{\n",
+            rule.name
+        ));
+        for keyword in table {
+            let word = keyword.literal().expect("keywords are fixed words");
+            rust_code.push_str(&format!(
+                "                {:?} => Self::{},\n",
+                word, keyword.name
+            ));
+            keywords.push(format!("Self::{}", keyword.name));
+        }
+        rust_code.push_str("                _ => self,\n            },\n");
+    }
+    let is_keyword = if keywords.is_empty() {
+        "false".to_string()
+    } else {
+        format!("matches!(self, {})", keywords.join(" | "))
+    };
+    rust_code.push_str(&format!(
+        r#"            _ => self,
+        }}
+    }}
+
+    /// Whether the token is a reserved word from the keyword table.
+    pub fn is_keyword(&self) -> bool {{
+        {}
+    }}
+}}
+"#,
+        is_keyword
+    ));
+
+    rust_code.push_str(&outcome_trait(spec));
+
+    rust_code.push_str(&generate_native(spec, &automata.min_dfa));
+    Ok(rust_code)
+}
+
+/// Wraps a regex in a raw string literal with enough `#`s to hold any quotes.
+fn raw_string(regex: &str) -> String {
+    let mut hashes = String::new();
+    while regex.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    format!("r{hashes}\"{regex}\"{hashes}")
+}
+
+/// Code reading an identifier with `rule`'s callback, or by parsing the slice
+/// of `lexer`, and looking it up in the keyword table. It evaluates to a
+/// `Result<Token, _>`.
+pub(crate) fn read_identifier(rule: &Rule, lexer: &str) -> String {
+    let payload = rule.payload.as_deref().unwrap_or("()");
+    let word = match &rule.callback {
+        Some(callback) => format!(
+            "Outcome::<{}>::into_result({}({}))",
+            payload, callback, lexer
+        ),
+        None => format!(
+            "{}.slice().parse::<{}>().map_err(|_| Default::default())",
+            lexer, payload
+        ),
+    };
+    format!(
+        "{}.map(|word| Token::{}(word).resolve_keyword())",
+        word, rule.name
+    )
+}
+
+/// Callbacks may return their payload or a `Result`, like they can for logos.
+/// `Outcome` turns either into a `Result`.
+fn outcome_trait(spec: &Spec) -> String {
+    let outcomes: BTreeSet<&str> = spec
+        .rules
+        .iter()
+        .filter(|rule| rule.callback.is_some() && !rule.skip)
+        .map(|rule| rule.payload.as_deref().unwrap_or("()"))
+        .collect();
+    if outcomes.is_empty() {
+        return String::new();
+    }
+
+    let error = spec.error.as_deref().unwrap_or("()");
+    let mut rust_code = format!(
+        r#"
+trait Outcome<T> {{
+    fn into_result(self) -> Result<T, {0}>;
+}}
+
+impl<T> Outcome<T> for Result<T, {0}> {{
+    fn into_result(self) -> Result<T, {0}> {{
+        self
+    }}
+}}
+"#,
+        error
+    );
+    for outcome in outcomes {
+        rust_code.push_str(&format!(
+            r#"
+impl Outcome<{0}> for {0} {{
+    fn into_result(self) -> Result<{0}, {1}> {{
+        Ok(self)
+    }}
+}}
+"#,
+            outcome, error
+        ));
+    }
+    rust_code
+}
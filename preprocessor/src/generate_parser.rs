@@ -0,0 +1,389 @@
+use crate::bnf::{Grammar, GrammarError};
+use crate::lalr::{Action, Automaton};
+use crate::spec::Spec;
+
+/// Generates the LALR(1) parser of a grammar whose terminals are tokens of
+/// `spec`: its tables, a `NonTerminal` enum and `parse`, which builds the
+/// concrete parse tree of a token slice. Fails if a terminal isn't a token
+/// or the grammar isn't LALR(1).
+pub fn generate_parser(grammar: &Grammar, spec: &Spec) -> Result<String, GrammarError> {
+    let patterns = token_patterns(grammar, spec)?;
+    let automaton = Automaton::build(grammar);
+    let table = automaton.lalr_table();
+
+    if let Some(conflict) = table.conflicts.first() {
+        let describe = |action: &Action| match *action {
+            Action::Shift(state) => format!("shift to state {}", state),
+            Action::Reduce(production) => {
+                format!("reduce {}", grammar.production_text(production))
+            }
+            Action::Accept => "accept".to_string(),
+        };
+        let actions: Vec<String> = conflict.actions.iter().map(describe).collect();
+        let line = conflict
+            .actions
+            .iter()
+            .find_map(|action| match *action {
+                Action::Reduce(production) => Some(grammar.productions[production].line),
+                _ => None,
+            })
+            .unwrap_or_default();
+        return Err(GrammarError {
+            line,
+            message: format!(
+                "the grammar is not LALR(1): in state {} on {} the parser could {}",
+                conflict.state,
+                grammar.terminal_name(conflict.terminal),
+                actions.join(" or ")
+            ),
+        });
+    }
+
+    let variants: Vec<String> = grammar
+        .nonterminals
+        .iter()
+        .map(|name| variant_name(name))
+        .collect();
+    let mut rust_code = String::from(
+        r#"// This file is auto-generated by the preprocessor
+// The preprocessor reads the .grammar file, builds its LALR(1) tables and
+// generates the parser that runs them
+// It is regenerated by the parser's build script whenever the grammar changes
+
+use super::Token;
+use std::fmt;
+use std::ops::Range;
+
+/// Nonterminals of the grammar, named after its `<rules>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NonTerminal {
+"#,
+    );
+    for variant in &variants {
+        rust_code.push_str(&format!("    {},\n", variant));
+    }
+    rust_code.push_str(
+        r#"}
+
+impl NonTerminal {
+    /// Name of the nonterminal in the grammar, e.g. `<program>`.
+    pub fn name(self) -> &'static str {
+        match self {
+"#,
+    );
+    for (variant, name) in variants.iter().zip(&grammar.nonterminals) {
+        rust_code.push_str(&format!(
+            "            Self::{} => {:?},\n",
+            variant,
+            format!("<{}>", name)
+        ));
+    }
+    rust_code.push_str(
+        r#"        }
+    }
+}
+
+impl fmt::Display for NonTerminal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+"#,
+    );
+
+    let terminals: Vec<String> = (0..=grammar.end())
+        .map(|terminal| format!("{:?}", grammar.terminal_name(terminal)))
+        .collect();
+    rust_code.push_str(&format!(
+        "\n/// Terminals as written in the grammar; the last one is the end of input.\npub static TERMINALS: [&str; {}] = [{}];\n",
+        terminals.len(),
+        terminals.join(", ")
+    ));
+
+    rust_code.push_str(&format!(
+        "\n/// Left-hand side and length of the right-hand side of each production.\npub static PRODUCTIONS: [(NonTerminal, usize); {}] = [\n",
+        grammar.productions.len()
+    ));
+    for (index, production) in grammar.productions.iter().enumerate() {
+        rust_code.push_str(&format!(
+            "    (NonTerminal::{}, {}), // {}\n",
+            variants[production.lhs],
+            production.rhs.len(),
+            grammar.production_text(index)
+        ));
+    }
+    rust_code.push_str("];\n");
+
+    rust_code.push_str(&format!(
+        "\n/// Each production as written in the grammar.\npub static PRODUCTION_TEXT: [&str; {}] = [\n",
+        grammar.productions.len()
+    ));
+    for index in 0..grammar.productions.len() {
+        rust_code.push_str(&format!("    {:?},\n", grammar.production_text(index)));
+    }
+    rust_code.push_str("];\n");
+
+    rust_code.push_str(
+        r#"
+/// The terminal a token is, or `None` for tokens the grammar doesn't use.
+pub fn terminal(token: &Token) -> Option<usize> {
+    match token {
+"#,
+    );
+    for (index, pattern) in patterns.iter().enumerate() {
+        rust_code.push_str(&format!("        {} => Some({}),\n", pattern, index));
+    }
+    rust_code.push_str("        _ => None,\n    }\n}\n");
+
+    rust_code.push_str(&format!(
+        r#"
+#[derive(Clone, Copy)]
+enum Action {{
+    Shift(u16),
+    Reduce(u16),
+    Accept,
+}}
+
+/// Actions of each state as `(terminal, action)` pairs sorted by terminal.
+static ACTION: [&[(u16, Action)]; {}] = [
+"#,
+        automaton.states.len()
+    ));
+    for actions in &table.action {
+        let cells: Vec<String> = actions
+            .iter()
+            .map(|(terminal, action)| {
+                let action = match *action {
+                    Action::Shift(state) => format!("Action::Shift({})", state),
+                    Action::Reduce(production) => format!("Action::Reduce({})", production),
+                    Action::Accept => "Action::Accept".to_string(),
+                };
+                format!("({}, {})", terminal, action)
+            })
+            .collect();
+        rust_code.push_str(&format!("    &[{}],\n", cells.join(", ")));
+    }
+    rust_code.push_str("];\n");
+
+    rust_code.push_str(&format!(
+        "\n/// Successor of each state on a nonterminal as sorted `(nonterminal, state)` pairs.\nstatic GOTO: [&[(u16, u16)]; {}] = [\n",
+        automaton.states.len()
+    ));
+    for gotos in &table.goto {
+        let cells: Vec<String> = gotos
+            .iter()
+            .map(|(nonterminal, state)| format!("({}, {})", nonterminal, state))
+            .collect();
+        rust_code.push_str(&format!("    &[{}],\n", cells.join(", ")));
+    }
+    rust_code.push_str("];\n");
+
+    rust_code.push_str(&format!(
+        r#"
+const END: usize = {};
+
+/// A node of the concrete parse tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {{
+    /// A token, by its index in the parsed tokens.
+    Token(usize),
+    /// A nonterminal with the nodes of the production it was derived by.
+    Branch {{
+        nonterminal: NonTerminal,
+        production: usize,
+        children: Vec<Node>,
+        /// Indices of the tokens the nonterminal derives.
+        tokens: Range<usize>,
+    }},
+}}
+
+impl Node {{
+    /// Indices of the tokens the node covers.
+    pub fn tokens(&self) -> Range<usize> {{
+        match self {{
+            Node::Token(index) => *index..*index + 1,
+            Node::Branch {{ tokens, .. }} => tokens.clone(),
+        }}
+    }}
+}}
+
+/// A token the grammar doesn't allow where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {{
+    /// Index of the token, the number of tokens at the end of input.
+    pub position: usize,
+    /// The terminals that could have come instead.
+    pub expected: Vec<&'static str>,
+}}
+
+fn action(state: usize, terminal: usize) -> Option<Action> {{
+    let actions = ACTION[state];
+    actions
+        .binary_search_by_key(&terminal, |&(terminal, _)| terminal as usize)
+        .ok()
+        .map(|index| actions[index].1)
+}}
+
+fn goto(state: usize, nonterminal: NonTerminal) -> usize {{
+    let gotos = GOTO[state];
+    let index = gotos
+        .binary_search_by_key(&(nonterminal as u16), |&(nonterminal, _)| nonterminal)
+        .expect("the tables have a goto for every reduction");
+    gotos[index].1 as usize
+}}
+
+/// What the parser does in one step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {{
+    /// Shifts the next token and goes to a state.
+    Shift(usize),
+    /// Reduces by a production, an index into [`PRODUCTIONS`].
+    Reduce(usize),
+    Accept,
+    /// The next token isn't allowed here.
+    Error,
+}}
+
+/// The parser's stack and input before one move, as [`trace`] records it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {{
+    /// States on the stack, bottom first.
+    pub states: Vec<usize>,
+    /// The grammar symbol of each state but the first.
+    pub symbols: Vec<&'static str>,
+    /// Index of the next token.
+    pub position: usize,
+    pub action: Move,
+}}
+
+/// Parses the tokens from the start symbol of the grammar.
+pub fn parse(tokens: &[Token]) -> Result<Node, SyntaxError> {{
+    run(tokens, |_, _, _, _| {{}})
+}}
+
+/// Parses the tokens like [`parse`] and records every step.
+pub fn trace(tokens: &[Token]) -> (Vec<Step>, Result<Node, SyntaxError>) {{
+    let mut steps = Vec::new();
+    let result = run(tokens, |states, nodes, position, action| {{
+        let symbols = nodes
+            .iter()
+            .map(|node| match node {{
+                Node::Token(index) => terminal(&tokens[*index]).map_or("?", |t| TERMINALS[t]),
+                Node::Branch {{ nonterminal, .. }} => nonterminal.name(),
+            }})
+            .collect();
+        steps.push(Step {{
+            states: states.to_vec(),
+            symbols,
+            position,
+            action,
+        }});
+    }});
+    (steps, result)
+}}
+
+// The LR driver, telling `observe` about the stack and input before each move
+fn run(
+    tokens: &[Token],
+    mut observe: impl FnMut(&[usize], &[Node], usize, Move),
+) -> Result<Node, SyntaxError> {{
+    let mut states: Vec<usize> = vec![0];
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut position = 0;
+
+    loop {{
+        let state = *states.last().expect("the start state is never popped");
+        let lookahead = match tokens.get(position) {{
+            Some(token) => terminal(token),
+            None => Some(END),
+        }};
+
+        match lookahead.and_then(|terminal| action(state, terminal)) {{
+            Some(Action::Shift(target)) => {{
+                observe(&states, &nodes, position, Move::Shift(target as usize));
+                nodes.push(Node::Token(position));
+                states.push(target as usize);
+                position += 1;
+            }}
+            Some(Action::Reduce(production)) => {{
+                observe(&states, &nodes, position, Move::Reduce(production as usize));
+                let (nonterminal, len) = PRODUCTIONS[production as usize];
+                let children = nodes.split_off(nodes.len() - len);
+                states.truncate(states.len() - len);
+                let tokens = match (children.first(), children.last()) {{
+                    (Some(first), Some(last)) => first.tokens().start..last.tokens().end,
+                    _ => position..position,
+                }};
+                nodes.push(Node::Branch {{
+                    nonterminal,
+                    production: production as usize,
+                    children,
+                    tokens,
+                }});
+                let state = *states.last().expect("the start state is never popped");
+                states.push(goto(state, nonterminal));
+            }}
+            Some(Action::Accept) => {{
+                observe(&states, &nodes, position, Move::Accept);
+                return Ok(nodes.pop().expect("the start symbol was reduced"));
+            }}
+            None => {{
+                observe(&states, &nodes, position, Move::Error);
+                return Err(SyntaxError {{
+                    position,
+                    expected: ACTION[state]
+                        .iter()
+                        .map(|&(terminal, _)| TERMINALS[terminal as usize])
+                        .collect(),
+                }});
+            }}
+        }}
+    }}
+}}
+"#,
+        grammar.end()
+    ));
+
+    Ok(rust_code)
+}
+
+// The `Token` pattern of each terminal: a token name is the rule of that name
+// and a quoted text is the rule whose regex matches just that text
+fn token_patterns(grammar: &Grammar, spec: &Spec) -> Result<Vec<String>, GrammarError> {
+    grammar
+        .terminals
+        .iter()
+        .enumerate()
+        .map(|(index, terminal)| {
+            let rule = match terminal.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+                Some(text) => spec
+                    .rules
+                    .iter()
+                    .find(|rule| !rule.skip && rule.literal().as_deref() == Some(text)),
+                None => spec.rules.iter().find(|rule| &rule.name == terminal),
+            };
+            let rule = rule.ok_or_else(|| GrammarError {
+                line: grammar.terminal_line(index),
+                message: format!("terminal {} is not a token of the lexer", terminal),
+            })?;
+            Ok(match rule.payload {
+                Some(_) => format!("Token::{}(_)", rule.name),
+                None => format!("Token::{}", rule.name),
+            })
+        })
+        .collect()
+}
+
+// `list_access` becomes `ListAccess`
+fn variant_name(name: &str) -> String {
+    name.split(['_', '-'])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
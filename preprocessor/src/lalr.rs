@@ -0,0 +1,573 @@
+//! LR(0) item sets and the SLR and LALR(1) parse tables of a grammar.
+//!
+//! The canonical collection of LR(0) item sets is built for the augmented
+//! grammar. SLR reduces a complete item on the FOLLOW set of its rule.
+//! LALR(1) lookaheads are found by the propagation method: each kernel item
+//! is closed with a dummy lookahead, lookaheads generated inside the closure
+//! are spontaneous and the dummy ones propagate along `goto`.
+//!
+//! [`export`] writes the item sets, both tables and their conflicts as text
+//! and CSV, and the automaton as Graphviz DOT.
+
+use crate::bnf::{FirstSets, Grammar, Production, Symbol};
+use crate::export::escape;
+use crate::ll::text_table;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A production with a dot before the `dot`-th symbol of its right-hand side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Item {
+    pub production: usize,
+    pub dot: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct State {
+    /// The items the state was reached with (the start item for state 0).
+    pub kernel: Vec<Item>,
+    /// The kernel and the items its closure adds.
+    pub items: Vec<Item>,
+    /// The state reached on each symbol.
+    pub transitions: BTreeMap<Symbol, usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Shift(usize),
+    Reduce(usize),
+    Accept,
+}
+
+/// Actions that share a cell of the ACTION table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub state: usize,
+    pub terminal: usize,
+    pub actions: Vec<Action>,
+}
+
+impl Conflict {
+    /// `shift/reduce` or `reduce/reduce`.
+    pub fn kind(&self) -> &'static str {
+        if self
+            .actions
+            .iter()
+            .any(|action| matches!(action, Action::Shift(_)))
+        {
+            "shift/reduce"
+        } else {
+            "reduce/reduce"
+        }
+    }
+}
+
+/// ACTION and GOTO tables, indexed by state. Cells with a conflict keep the
+/// first action, shifts before reductions.
+#[derive(Debug, Clone)]
+pub struct ParseTable {
+    pub action: Vec<BTreeMap<usize, Action>>,
+    pub goto: Vec<BTreeMap<usize, usize>>,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// The LR(0) automaton of the augmented grammar.
+#[derive(Debug, Clone)]
+pub struct Automaton {
+    /// The grammar with `<start'> ::= <start>` as its last production.
+    pub grammar: Grammar,
+    pub states: Vec<State>,
+}
+
+impl Automaton {
+    pub fn build(grammar: &Grammar) -> Self {
+        let grammar = grammar.augmented();
+        let start = Item {
+            production: grammar.productions.len() - 1,
+            dot: 0,
+        };
+        let mut automaton = Automaton {
+            grammar,
+            states: Vec::new(),
+        };
+        let mut index: HashMap<Vec<Item>, usize> = HashMap::new();
+        index.insert(vec![start], 0);
+        automaton.states.push(automaton.state(vec![start]));
+
+        let mut next = 0;
+        while next < automaton.states.len() {
+            let mut kernels: BTreeMap<Symbol, Vec<Item>> = BTreeMap::new();
+            for &item in &automaton.states[next].items {
+                if let Some(&symbol) = automaton.after_dot(item) {
+                    kernels.entry(symbol).or_default().push(Item {
+                        production: item.production,
+                        dot: item.dot + 1,
+                    });
+                }
+            }
+
+            for (symbol, mut kernel) in kernels {
+                kernel.sort();
+                let target = match index.get(&kernel) {
+                    Some(&target) => target,
+                    None => {
+                        let target = automaton.states.len();
+                        index.insert(kernel.clone(), target);
+                        let state = automaton.state(kernel);
+                        automaton.states.push(state);
+                        target
+                    }
+                };
+                automaton.states[next].transitions.insert(symbol, target);
+            }
+            next += 1;
+        }
+
+        automaton
+    }
+
+    /// Index of the augmented start production.
+    pub fn accept_production(&self) -> usize {
+        self.grammar.productions.len() - 1
+    }
+
+    /// The symbol after the dot, if the item isn't complete.
+    pub fn after_dot(&self, item: Item) -> Option<&Symbol> {
+        self.grammar.productions[item.production].rhs.get(item.dot)
+    }
+
+    /// An item as text, e.g. `<term> ::= <term> · "*" <factor>`.
+    pub fn item_text(&self, item: Item) -> String {
+        let production = &self.grammar.productions[item.production];
+        let mut symbols: Vec<String> = production
+            .rhs
+            .iter()
+            .map(|&symbol| self.grammar.symbol_name(symbol))
+            .collect();
+        symbols.insert(item.dot, "·".to_string());
+        format!(
+            "<{}> ::= {}",
+            self.grammar.nonterminals[production.lhs],
+            symbols.join(" ")
+        )
+    }
+
+    /// Lookaheads of the complete items of each state under LALR(1).
+    pub fn lalr_lookaheads(&self) -> Vec<BTreeMap<usize, BTreeSet<usize>>> {
+        let first = self.grammar.first_sets();
+        // A terminal past `$` stands for "whatever follows the kernel item"
+        let dummy = self.grammar.end() + 1;
+
+        let mut lookaheads: Vec<BTreeMap<Item, BTreeSet<usize>>> = self
+            .states
+            .iter()
+            .map(|state| {
+                state
+                    .kernel
+                    .iter()
+                    .map(|&item| (item, BTreeSet::new()))
+                    .collect()
+            })
+            .collect();
+        for set in lookaheads[0].values_mut() {
+            set.insert(self.grammar.end());
+        }
+
+        let mut propagate: Vec<((usize, Item), (usize, Item))> = Vec::new();
+        for (index, state) in self.states.iter().enumerate() {
+            for &kernel in &state.kernel {
+                let closure = self.closure(&first, [(kernel, BTreeSet::from([dummy]))]);
+                for (item, set) in closure {
+                    let Some(symbol) = self.after_dot(item) else {
+                        continue;
+                    };
+                    let target = state.transitions[symbol];
+                    let moved = Item {
+                        production: item.production,
+                        dot: item.dot + 1,
+                    };
+                    for terminal in set {
+                        if terminal == dummy {
+                            propagate.push(((index, kernel), (target, moved)));
+                        } else {
+                            lookaheads[target].get_mut(&moved).unwrap().insert(terminal);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &((from, item), (to, moved)) in &propagate {
+                let set = lookaheads[from][&item].clone();
+                let target = lookaheads[to].get_mut(&moved).unwrap();
+                let before = target.len();
+                target.extend(set);
+                changed |= target.len() != before;
+            }
+        }
+
+        // Complete items outside the kernel (empty productions) get their
+        // lookaheads from the closure of the kernel
+        lookaheads
+            .into_iter()
+            .map(|kernel| {
+                self.closure(&first, kernel)
+                    .into_iter()
+                    .filter(|&(item, _)| self.after_dot(item).is_none())
+                    .map(|(item, set)| (item.production, set))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The LALR(1) ACTION and GOTO tables.
+    pub fn lalr_table(&self) -> ParseTable {
+        self.table(&self.lalr_lookaheads())
+    }
+
+    /// The SLR ACTION and GOTO tables: each complete item is reduced on the
+    /// FOLLOW set of its rule.
+    pub fn slr_table(&self) -> ParseTable {
+        let follow = self.grammar.follow_sets(&self.grammar.first_sets());
+        let reductions: Vec<BTreeMap<usize, BTreeSet<usize>>> = self
+            .states
+            .iter()
+            .map(|state| {
+                state
+                    .items
+                    .iter()
+                    .filter(|&&item| self.after_dot(item).is_none())
+                    .map(|item| {
+                        // Nothing follows the augmented start symbol but `$`
+                        let lookaheads = if item.production == self.accept_production() {
+                            BTreeSet::from([self.grammar.end()])
+                        } else {
+                            follow[self.grammar.productions[item.production].lhs].clone()
+                        };
+                        (item.production, lookaheads)
+                    })
+                    .collect()
+            })
+            .collect();
+        self.table(&reductions)
+    }
+
+    /// Each state with its items, kernel items first, and its transitions.
+    pub fn item_sets_text(&self) -> String {
+        let mut text = String::new();
+        for (index, state) in self.states.iter().enumerate() {
+            text.push_str(&format!("I{}\n", index));
+            for &item in &state.items {
+                let marker = if state.kernel.contains(&item) {
+                    ' '
+                } else {
+                    '+'
+                };
+                text.push_str(&format!("  {} {}\n", marker, self.item_text(item)));
+            }
+            for (&symbol, &target) in &state.transitions {
+                text.push_str(&format!(
+                    "    on {} go to I{}\n",
+                    self.grammar.symbol_name(symbol),
+                    target
+                ));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// The automaton as a Graphviz DOT graph, one box of items per state.
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut dot = format!(
+            "digraph \"{} LR(0)\" {{\n    rankdir=LR;\n    node [shape=box, fontname=\"monospace\"];\n",
+            escape(name)
+        );
+        for (index, state) in self.states.iter().enumerate() {
+            let items: String = state
+                .items
+                .iter()
+                .map(|&item| format!("{}\\l", escape(&self.item_text(item))))
+                .collect();
+            let accepts = state.items.iter().any(|&item| {
+                item.production == self.accept_production() && self.after_dot(item).is_none()
+            });
+            dot.push_str(&format!(
+                "    {} [label=\"I{}\\n{}\"{}];\n",
+                index,
+                index,
+                items,
+                if accepts { ", peripheries=2" } else { "" }
+            ));
+        }
+        for (index, state) in self.states.iter().enumerate() {
+            for (&symbol, &target) in &state.transitions {
+                dot.push_str(&format!(
+                    "    {} -> {} [label=\"{}\"];\n",
+                    index,
+                    target,
+                    escape(&self.grammar.symbol_name(symbol))
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// The ACTION and GOTO tables as text: `s4` shifts to state 4, `r7`
+    /// reduces by production 7 and conflicting cells list every action.
+    pub fn table_text(&self, table: &ParseTable) -> String {
+        let (header, rows) = self.table_cells(table);
+        text_table(&header, &rows)
+    }
+
+    /// The ACTION and GOTO tables as CSV, with the cells of
+    /// [`Automaton::table_text`].
+    pub fn table_csv(&self, table: &ParseTable) -> String {
+        let (header, rows) = self.table_cells(table);
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(header).expect("writing to memory");
+        for row in rows {
+            writer.write_record(row).expect("writing to memory");
+        }
+        String::from_utf8(writer.into_inner().expect("writing to memory")).unwrap()
+    }
+
+    /// One line per conflicting cell, e.g. `state 5 on "+": shift/reduce
+    /// conflict between s7 and r3 (<e> ::= <e> "+" <e>)`.
+    pub fn conflicts_text(&self, table: &ParseTable) -> String {
+        let mut text = String::new();
+        for conflict in &table.conflicts {
+            let actions: Vec<String> = conflict
+                .actions
+                .iter()
+                .map(|&action| match action {
+                    Action::Reduce(production) => format!(
+                        "{} ({})",
+                        action_text(action),
+                        self.grammar.production_text(production)
+                    ),
+                    _ => action_text(action),
+                })
+                .collect();
+            text.push_str(&format!(
+                "state {} on {}: {} conflict between {}\n",
+                conflict.state,
+                self.grammar.terminal_name(conflict.terminal),
+                conflict.kind(),
+                actions.join(" and ")
+            ));
+        }
+        text
+    }
+
+    // Header and rows of the tables: a column per terminal, `$` included,
+    // then one per nonterminal but the augmented start symbol
+    fn table_cells(&self, table: &ParseTable) -> (Vec<String>, Vec<Vec<String>>) {
+        let nonterminals = self.grammar.nonterminals.len() - 1;
+        let header: Vec<String> = std::iter::once("state".to_string())
+            .chain((0..=self.grammar.end()).map(|t| self.grammar.terminal_name(t).to_string()))
+            .chain((0..nonterminals).map(|n| self.grammar.symbol_name(Symbol::NonTerminal(n))))
+            .collect();
+
+        let rows = (0..self.states.len())
+            .map(|state| {
+                let actions = (0..=self.grammar.end()).map(|terminal| {
+                    let conflict = table
+                        .conflicts
+                        .iter()
+                        .find(|c| c.state == state && c.terminal == terminal);
+                    match (conflict, table.action[state].get(&terminal)) {
+                        (Some(conflict), _) => {
+                            let actions: Vec<String> =
+                                conflict.actions.iter().map(|&a| action_text(a)).collect();
+                            actions.join("/")
+                        }
+                        (None, Some(&action)) => action_text(action),
+                        (None, None) => String::new(),
+                    }
+                });
+                let gotos = (0..nonterminals).map(|nonterminal| {
+                    table.goto[state]
+                        .get(&nonterminal)
+                        .map_or(String::new(), usize::to_string)
+                });
+                std::iter::once(state.to_string())
+                    .chain(actions)
+                    .chain(gotos)
+                    .collect()
+            })
+            .collect();
+
+        (header, rows)
+    }
+
+    // Builds the tables, reducing each production of a state on its lookaheads
+    fn table(&self, reductions: &[BTreeMap<usize, BTreeSet<usize>>]) -> ParseTable {
+        let mut table = ParseTable {
+            action: vec![BTreeMap::new(); self.states.len()],
+            goto: vec![BTreeMap::new(); self.states.len()],
+            conflicts: Vec::new(),
+        };
+
+        for (index, state) in self.states.iter().enumerate() {
+            let mut cells: BTreeMap<usize, Vec<Action>> = BTreeMap::new();
+            for (&symbol, &target) in &state.transitions {
+                match symbol {
+                    Symbol::Terminal(terminal) => cells
+                        .entry(terminal)
+                        .or_default()
+                        .push(Action::Shift(target)),
+                    Symbol::NonTerminal(nonterminal) => {
+                        table.goto[index].insert(nonterminal, target);
+                    }
+                }
+            }
+            for (&production, terminals) in &reductions[index] {
+                let action = if production == self.accept_production() {
+                    Action::Accept
+                } else {
+                    Action::Reduce(production)
+                };
+                for &terminal in terminals {
+                    cells.entry(terminal).or_default().push(action);
+                }
+            }
+
+            for (terminal, actions) in cells {
+                table.action[index].insert(terminal, actions[0]);
+                if actions.len() > 1 {
+                    table.conflicts.push(Conflict {
+                        state: index,
+                        terminal,
+                        actions,
+                    });
+                }
+            }
+        }
+
+        table
+    }
+
+    fn state(&self, kernel: Vec<Item>) -> State {
+        let mut items = kernel.clone();
+        let mut next = 0;
+        while next < items.len() {
+            if let Some(&Symbol::NonTerminal(nonterminal)) = self.after_dot(items[next]) {
+                for (production, _) in self.productions_of(nonterminal) {
+                    let item = Item { production, dot: 0 };
+                    if !items.contains(&item) {
+                        items.push(item);
+                    }
+                }
+            }
+            next += 1;
+        }
+
+        State {
+            kernel,
+            items,
+            transitions: BTreeMap::new(),
+        }
+    }
+
+    // LR(1) closure of items with sets of lookaheads
+    fn closure(
+        &self,
+        first: &FirstSets,
+        items: impl IntoIterator<Item = (Item, BTreeSet<usize>)>,
+    ) -> BTreeMap<Item, BTreeSet<usize>> {
+        let mut closure: BTreeMap<Item, BTreeSet<usize>> = items.into_iter().collect();
+        let mut work: Vec<Item> = closure.keys().copied().collect();
+
+        while let Some(item) = work.pop() {
+            let Some(&Symbol::NonTerminal(nonterminal)) = self.after_dot(item) else {
+                continue;
+            };
+            let rest = &self.grammar.productions[item.production].rhs[item.dot + 1..];
+            let (mut lookahead, nullable) = first.of(rest);
+            if nullable {
+                lookahead.extend(&closure[&item]);
+            }
+
+            for (production, _) in self.productions_of(nonterminal) {
+                let item = Item { production, dot: 0 };
+                let new = !closure.contains_key(&item);
+                let set = closure.entry(item).or_default();
+                let before = set.len();
+                set.extend(&lookahead);
+                if new || set.len() != before {
+                    work.push(item);
+                }
+            }
+        }
+
+        closure
+    }
+
+    fn productions_of(&self, nonterminal: usize) -> impl Iterator<Item = (usize, &Production)> {
+        self.grammar
+            .productions
+            .iter()
+            .enumerate()
+            .filter(move |(_, production)| production.lhs == nonterminal)
+    }
+}
+
+/// `s4`, `r7` or `acc`.
+fn action_text(action: Action) -> String {
+    match action {
+        Action::Shift(state) => format!("s{}", state),
+        Action::Reduce(production) => format!("r{}", production),
+        Action::Accept => "acc".to_string(),
+    }
+}
+
+/// The LR(0) item sets, SLR and LALR(1) tables and their conflicts of a
+/// grammar, named `{name}.lr.txt`, `{name}.lr0.dot`, `{name}.slr.csv` and
+/// `{name}.lalr.csv`. Productions are numbered as in the grammar, the
+/// augmented start production last.
+pub fn export(name: &str, grammar: &Grammar) -> Vec<(String, String)> {
+    let automaton = Automaton::build(grammar);
+    let slr = automaton.slr_table();
+    let lalr = automaton.lalr_table();
+
+    let mut report = String::from("Productions\n\n");
+    for index in 0..automaton.grammar.productions.len() {
+        report.push_str(&format!(
+            "{:>4}  {}\n",
+            index,
+            automaton.grammar.production_text(index)
+        ));
+    }
+    report.push_str(&format!(
+        "\nLR(0) item sets (+ marks items added by the closure)\n\n{}",
+        automaton.item_sets_text()
+    ));
+    for (title, table) in [("SLR", &slr), ("LALR(1)", &lalr)] {
+        report.push_str(&format!(
+            "{} table\n\n{}\n{} conflicts\n\n",
+            title,
+            automaton.table_text(table),
+            title
+        ));
+        if table.conflicts.is_empty() {
+            report.push_str(&format!("none, the grammar is {}\n\n", title));
+        } else {
+            report.push_str(&format!("{}\n", automaton.conflicts_text(table)));
+        }
+    }
+
+    let files = [
+        ("lr.txt", report),
+        ("lr0.dot", automaton.to_dot(name)),
+        ("slr.csv", automaton.table_csv(&slr)),
+        ("lalr.csv", automaton.table_csv(&lalr)),
+    ];
+    files
+        .into_iter()
+        .map(|(suffix, contents)| (format!("{}.{}", name, suffix), contents))
+        .collect()
+}
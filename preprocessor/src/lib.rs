@@ -0,0 +1,67 @@
+//! Turns the `.lex` token definitions into the Rust source of the scanner,
+//! and the BNF of a `.grammar` file into the parser.
+//!
+//! The scanner's build script calls [`generate_from_file`] so the generated
+//! tokenizer always matches `hiwkhao.lex`, and reports the warnings of
+//! [`check_file`] as build warnings. The parser's build script calls
+//! [`generate_parser_from_files`] the same way for `hiwkhao.grammar`.
+
+use std::fs;
+use std::path::Path;
+
+pub mod automaton;
+pub mod bnf;
+pub mod export;
+mod generate;
+mod generate_parser;
+pub mod lalr;
+pub mod ll;
+mod native;
+pub mod spec;
+pub mod validate;
+
+pub use bnf::{parse_grammar, Grammar, GrammarError};
+pub use generate::generate;
+pub use generate_parser::generate_parser;
+pub use spec::{parse_spec, Rule, Spec, SpecError};
+pub use validate::{validate, Diagnostic, Report, Severity};
+
+/// Reads and parses a `.lex` file. Errors name the file and line.
+pub fn read_spec<P: AsRef<Path>>(lex_file: P) -> Result<Spec, String> {
+    let lex_file = lex_file.as_ref();
+    let source = fs::read_to_string(lex_file)
+        .map_err(|err| format!("Failed to read {}: {}", lex_file.display(), err))?;
+    parse_spec(&source).map_err(|err| format!("{}:{}", lex_file.display(), err))
+}
+
+/// Reads a `.lex` file and returns the generated tokenizer source.
+pub fn generate_from_file<P: AsRef<Path>>(lex_file: P) -> Result<String, String> {
+    let spec = read_spec(&lex_file)?;
+    generate(&spec).map_err(|err| format!("{}:{}", lex_file.as_ref().display(), err))
+}
+
+/// Reads a `.lex` file and validates its rules.
+pub fn check_file<P: AsRef<Path>>(lex_file: P) -> Result<Report, String> {
+    let spec = read_spec(&lex_file)?;
+    validate(&spec).map_err(|err| format!("{}:{}", lex_file.as_ref().display(), err))
+}
+
+/// Reads and parses a `.grammar` file. Errors name the file and line.
+pub fn read_grammar<P: AsRef<Path>>(grammar_file: P) -> Result<Grammar, String> {
+    let grammar_file = grammar_file.as_ref();
+    let source = fs::read_to_string(grammar_file)
+        .map_err(|err| format!("Failed to read {}: {}", grammar_file.display(), err))?;
+    parse_grammar(&source).map_err(|err| format!("{}:{}", grammar_file.display(), err))
+}
+
+/// Reads a `.grammar` file and the `.lex` file of its tokens and returns the
+/// generated parser source.
+pub fn generate_parser_from_files<P: AsRef<Path>, Q: AsRef<Path>>(
+    grammar_file: P,
+    lex_file: Q,
+) -> Result<String, String> {
+    let grammar = read_grammar(&grammar_file)?;
+    let spec = read_spec(lex_file)?;
+    generate_parser(&grammar, &spec)
+        .map_err(|err| format!("{}:{}", grammar_file.as_ref().display(), err))
+}
@@ -0,0 +1,640 @@
+//! LL(1) analysis of a grammar: nullable nonterminals, FIRST and FOLLOW
+//! sets, the predictive parse table with its conflicts, and left recursion.
+//!
+//! [`rewrite`] removes left recursion and left-factors common prefixes, the
+//! usual first steps towards an LL(1) grammar. It only looks at the first
+//! symbol of each alternative, so left recursion hidden behind a nullable
+//! nonterminal is reported but not removed.
+
+use crate::bnf::{FirstSets, Grammar, Production, Symbol};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// The sets and LL(1) table of a grammar.
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub grammar: Grammar,
+    pub first: FirstSets,
+    pub follow: Vec<BTreeSet<usize>>,
+    /// Productions predicted for each nonterminal on each lookahead
+    /// terminal. A cell with more than one production is a conflict.
+    pub table: Vec<BTreeMap<usize, Vec<usize>>>,
+    /// Left-recursive cycles, as the productions they go through.
+    pub left_recursion: Vec<Vec<usize>>,
+}
+
+/// Productions of a nonterminal predicted on the same lookahead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub nonterminal: usize,
+    pub terminal: usize,
+    pub productions: Vec<usize>,
+}
+
+impl Analysis {
+    pub fn new(grammar: &Grammar) -> Self {
+        let first = grammar.first_sets();
+        let follow = grammar.follow_sets(&first);
+
+        let mut table = vec![BTreeMap::new(); grammar.nonterminals.len()];
+        for (index, production) in grammar.productions.iter().enumerate() {
+            let (mut lookaheads, nullable) = first.of(&production.rhs);
+            if nullable {
+                lookaheads.extend(&follow[production.lhs]);
+            }
+            for terminal in lookaheads {
+                table[production.lhs]
+                    .entry(terminal)
+                    .or_insert_with(Vec::new)
+                    .push(index);
+            }
+        }
+
+        Analysis {
+            grammar: grammar.clone(),
+            left_recursion: left_recursion(grammar, &first),
+            first,
+            follow,
+            table,
+        }
+    }
+
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        for (nonterminal, row) in self.table.iter().enumerate() {
+            for (&terminal, productions) in row {
+                if productions.len() > 1 {
+                    conflicts.push(Conflict {
+                        nonterminal,
+                        terminal,
+                        productions: productions.clone(),
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// The productions, sets, table, conflicts and left recursion as text
+    /// tables.
+    pub fn report(&self) -> String {
+        let grammar = &self.grammar;
+        let mut report = String::from("Productions\n\n");
+        for index in 0..grammar.productions.len() {
+            report.push_str(&format!(
+                "{:>4}  {}\n",
+                index,
+                grammar.production_text(index)
+            ));
+        }
+
+        let nullable: Vec<String> = (0..grammar.nonterminals.len())
+            .filter(|&nonterminal| self.first.nullable[nonterminal])
+            .map(|nonterminal| self.nonterminal_name(nonterminal))
+            .collect();
+        report.push_str(&format!(
+            "\nNullable: {}\n",
+            if nullable.is_empty() {
+                "none".to_string()
+            } else {
+                nullable.join(" ")
+            }
+        ));
+
+        report.push_str("\nFIRST and FOLLOW\n\n");
+        let header = ["nonterminal", "nullable", "FIRST", "FOLLOW"].map(String::from);
+        let rows: Vec<Vec<String>> = self
+            .sets_rows()
+            .map(|mut row| {
+                row[1] = if row[1] == "true" { "yes" } else { "no" }.to_string();
+                row
+            })
+            .collect();
+        report.push_str(&text_table(&header, &rows));
+
+        report.push_str("\nLL(1) table (production numbers)\n\n");
+        let terminals = self.used_terminals();
+        let header: Vec<String> = std::iter::once("nonterminal".to_string())
+            .chain(
+                terminals
+                    .iter()
+                    .map(|&t| grammar.terminal_name(t).to_string()),
+            )
+            .collect();
+        let rows: Vec<Vec<String>> = self
+            .table
+            .iter()
+            .enumerate()
+            .map(|(nonterminal, row)| {
+                std::iter::once(self.nonterminal_name(nonterminal))
+                    .chain(terminals.iter().map(|terminal| {
+                        row.get(terminal).map_or(String::new(), |productions| {
+                            let numbers: Vec<String> =
+                                productions.iter().map(usize::to_string).collect();
+                            numbers.join(",")
+                        })
+                    }))
+                    .collect()
+            })
+            .collect();
+        report.push_str(&text_table(&header, &rows));
+
+        report.push_str("\nLeft recursion\n\n");
+        if self.left_recursion.is_empty() {
+            report.push_str("none\n");
+        }
+        for cycle in &self.left_recursion {
+            let steps: Vec<String> = cycle
+                .iter()
+                .map(|&production| self.production_with_line(production))
+                .collect();
+            report.push_str(&format!("{}\n", steps.join(" -> ")));
+        }
+
+        let conflicts = self.conflicts();
+        report.push_str("\nConflicts\n\n");
+        if conflicts.is_empty() {
+            report.push_str("none, the grammar is LL(1)\n");
+        }
+        for conflict in &conflicts {
+            let productions: Vec<String> =
+                conflict.productions.iter().map(usize::to_string).collect();
+            report.push_str(&format!(
+                "{} on {}: productions {}\n",
+                self.nonterminal_name(conflict.nonterminal),
+                grammar.terminal_name(conflict.terminal),
+                productions.join(", ")
+            ));
+        }
+        if !conflicts.is_empty() {
+            report.push_str(&format!(
+                "the grammar is not LL(1): {} conflicting cells\n",
+                conflicts.len()
+            ));
+        }
+
+        report
+    }
+
+    /// One row per nonterminal: whether it is nullable, its FIRST and its
+    /// FOLLOW set, terminals separated by spaces.
+    pub fn sets_csv(&self) -> String {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer
+            .write_record(["nonterminal", "nullable", "first", "follow"])
+            .expect("writing to memory");
+        for row in self.sets_rows() {
+            writer.write_record(row).expect("writing to memory");
+        }
+        String::from_utf8(writer.into_inner().expect("writing to memory")).unwrap()
+    }
+
+    /// The LL(1) table with a column per terminal. Each cell holds the
+    /// predicted productions, separated by ` / ` when they conflict.
+    pub fn table_csv(&self) -> String {
+        let terminals = self.used_terminals();
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        let header = std::iter::once("nonterminal".to_string()).chain(
+            terminals
+                .iter()
+                .map(|&terminal| self.grammar.terminal_name(terminal).to_string()),
+        );
+        writer.write_record(header).expect("writing to memory");
+        for (nonterminal, row) in self.table.iter().enumerate() {
+            let cells = terminals.iter().map(|terminal| {
+                row.get(terminal).map_or(String::new(), |productions| {
+                    let texts: Vec<String> = productions
+                        .iter()
+                        .map(|&production| self.grammar.production_text(production))
+                        .collect();
+                    texts.join(" / ")
+                })
+            });
+            writer
+                .write_record(std::iter::once(self.nonterminal_name(nonterminal)).chain(cells))
+                .expect("writing to memory");
+        }
+        String::from_utf8(writer.into_inner().expect("writing to memory")).unwrap()
+    }
+
+    fn sets_rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        (0..self.grammar.nonterminals.len()).map(|nonterminal| {
+            vec![
+                self.nonterminal_name(nonterminal),
+                self.first.nullable[nonterminal].to_string(),
+                self.set_text(&self.first.first[nonterminal]),
+                self.set_text(&self.follow[nonterminal]),
+            ]
+        })
+    }
+
+    // Terminals with at least one prediction, `$` last
+    fn used_terminals(&self) -> Vec<usize> {
+        let used: BTreeSet<usize> = self
+            .table
+            .iter()
+            .flat_map(|row| row.keys().copied())
+            .collect();
+        used.into_iter().collect()
+    }
+
+    fn nonterminal_name(&self, nonterminal: usize) -> String {
+        self.grammar.symbol_name(Symbol::NonTerminal(nonterminal))
+    }
+
+    fn set_text(&self, set: &BTreeSet<usize>) -> String {
+        let names: Vec<&str> = set
+            .iter()
+            .map(|&terminal| self.grammar.terminal_name(terminal))
+            .collect();
+        names.join(" ")
+    }
+
+    fn production_with_line(&self, production: usize) -> String {
+        match self.grammar.productions[production].line {
+            0 => self.grammar.production_text(production),
+            line => format!(
+                "{} (line {})",
+                self.grammar.production_text(production),
+                line
+            ),
+        }
+    }
+}
+
+/// The report and CSV tables of a grammar and of its [`rewrite`], named
+/// `{name}.ll1.txt`, `{name}.first_follow.csv`, `{name}.ll1.csv`,
+/// `{name}.rewritten.grammar` and so on.
+pub fn export(name: &str, grammar: &Grammar) -> Vec<(String, String)> {
+    let analysis = Analysis::new(grammar);
+    let rewritten = Analysis::new(&rewrite(grammar));
+
+    let mut report = analysis.report();
+    report.push_str(&format!(
+        "\nWithout left recursion, left-factored\n\n{}\n{}",
+        rewritten.grammar,
+        rewritten.report()
+    ));
+
+    let files = [
+        ("ll1.txt", report),
+        ("first_follow.csv", analysis.sets_csv()),
+        ("ll1.csv", analysis.table_csv()),
+        ("rewritten.grammar", rewritten.grammar.to_string()),
+        ("rewritten.first_follow.csv", rewritten.sets_csv()),
+        ("rewritten.ll1.csv", rewritten.table_csv()),
+    ];
+    files
+        .into_iter()
+        .map(|(suffix, contents)| (format!("{}.{}", name, suffix), contents))
+        .collect()
+}
+
+/// Finds left-recursive cycles: each production whose lhs can begin its own
+/// rhs, and for indirect recursion the shortest cycle through other rules,
+/// once per cycle.
+pub fn left_recursion(grammar: &Grammar, first: &FirstSets) -> Vec<Vec<usize>> {
+    // `(production, nonterminal)` for each nonterminal that can begin a
+    // production of the rule, looking past nullable symbols
+    let mut corners: Vec<Vec<(usize, usize)>> = vec![Vec::new(); grammar.nonterminals.len()];
+    for (index, production) in grammar.productions.iter().enumerate() {
+        for symbol in &production.rhs {
+            let Symbol::NonTerminal(nonterminal) = *symbol else {
+                break;
+            };
+            corners[production.lhs].push((index, nonterminal));
+            if !first.nullable[nonterminal] {
+                break;
+            }
+        }
+    }
+
+    let mut cycles: Vec<Vec<usize>> = Vec::new();
+    for (lhs, edges) in corners.iter().enumerate() {
+        for &(production, nonterminal) in edges {
+            if nonterminal == lhs {
+                cycles.push(vec![production]);
+            }
+        }
+    }
+
+    // A cycle is reported from its lowest nonterminal, so the search only
+    // visits higher ones
+    for start in 0..corners.len() {
+        let mut previous: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+        let mut queue = VecDeque::from([start]);
+        let mut closing = None;
+        'search: while let Some(current) = queue.pop_front() {
+            for &(production, next) in &corners[current] {
+                if next == current {
+                    continue;
+                }
+                if next == start {
+                    closing = Some((production, current));
+                    break 'search;
+                }
+                if next > start && !previous.contains_key(&next) {
+                    previous.insert(next, (production, current));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if let Some((production, mut current)) = closing {
+            let mut cycle = vec![production];
+            while current != start {
+                let (production, from) = previous[&current];
+                cycle.push(production);
+                current = from;
+            }
+            cycle.reverse();
+            cycles.push(cycle);
+        }
+    }
+
+    cycles
+}
+
+/// The grammar without left recursion, then left-factored. New rules are
+/// named after the rule they come from with a `'`, e.g. `<expression'>`,
+/// and written right after it.
+pub fn rewrite(grammar: &Grammar) -> Grammar {
+    left_factor(&remove_left_recursion(grammar))
+}
+
+/// Removes direct and indirect left recursion. A rule that can begin with an
+/// earlier rule leading back to it gets that rule's alternatives inlined,
+/// then `A ::= A α | β` becomes `A ::= β A'` and `A' ::= α A' | ε`.
+pub fn remove_left_recursion(grammar: &Grammar) -> Grammar {
+    let mut rules = Rules::new(grammar);
+    for rule in 0..rules.names.len() {
+        // Inlining can bring up another earlier rule, so repeat until the
+        // rule only begins with terminals, itself, later rules or rules that
+        // don't lead back to it
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for earlier in 0..rule {
+                if rules.begins_with(rule, earlier) && rules.leads_to(earlier, rule) {
+                    rules.inline(rule, earlier);
+                    changed = true;
+                }
+            }
+        }
+        rules.remove_direct_recursion(rule);
+    }
+    rules.into_grammar(grammar)
+}
+
+/// Pulls the longest prefix shared by alternatives that start with the same
+/// symbol into a new rule: `A ::= α β | α γ` becomes `A ::= α A'` and
+/// `A' ::= β | γ`, until no two alternatives of a rule start alike.
+pub fn left_factor(grammar: &Grammar) -> Grammar {
+    let mut rules = Rules::new(grammar);
+    // New rules are added at the end, so they are factored too
+    let mut next = 0;
+    while next < rules.names.len() {
+        if !rules.factor(next) {
+            next += 1;
+        }
+    }
+    rules.into_grammar(grammar)
+}
+
+// Rules being rewritten: the alternatives of each nonterminal with the line
+// they came from, and the order the rules are written in
+struct Rules {
+    names: Vec<String>,
+    alternatives: Vec<Vec<(Vec<Symbol>, usize)>>,
+    order: Vec<usize>,
+}
+
+impl Rules {
+    fn new(grammar: &Grammar) -> Self {
+        let mut alternatives = vec![Vec::new(); grammar.nonterminals.len()];
+        for production in &grammar.productions {
+            alternatives[production.lhs].push((production.rhs.clone(), production.line));
+        }
+        Rules {
+            names: grammar.nonterminals.clone(),
+            alternatives,
+            order: (0..grammar.nonterminals.len()).collect(),
+        }
+    }
+
+    // Adds an empty rule named after `rule` with primes, written after it
+    // and the rules added for it before
+    fn add(&mut self, rule: usize) -> usize {
+        let mut name = format!("{}'", self.names[rule]);
+        while self.names.contains(&name) {
+            name.push('\'');
+        }
+        let base = &self.names[rule];
+        let mut position = self.order.iter().position(|&r| r == rule).unwrap() + 1;
+        while position < self.order.len()
+            && self.names[self.order[position]]
+                .strip_prefix(base.as_str())
+                .is_some_and(|primes| !primes.is_empty() && primes.chars().all(|c| c == '\''))
+        {
+            position += 1;
+        }
+
+        self.names.push(name);
+        self.alternatives.push(Vec::new());
+        self.order.insert(position, self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    fn begins_with(&self, rule: usize, first: usize) -> bool {
+        self.alternatives[rule]
+            .iter()
+            .any(|(rhs, _)| rhs.first() == Some(&Symbol::NonTerminal(first)))
+    }
+
+    // Whether `from` can derive a sequence beginning with `to`
+    fn leads_to(&self, from: usize, to: usize) -> bool {
+        let mut seen = BTreeSet::from([from]);
+        let mut work = vec![from];
+        while let Some(rule) = work.pop() {
+            for (rhs, _) in &self.alternatives[rule] {
+                if let Some(&Symbol::NonTerminal(next)) = rhs.first() {
+                    if next == to {
+                        return true;
+                    }
+                    if seen.insert(next) {
+                        work.push(next);
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    // Replaces `rule ::= first γ` with `rule ::= δ γ` for each `first ::= δ`
+    fn inline(&mut self, rule: usize, first: usize) {
+        let mut alternatives = Vec::new();
+        for (rhs, line) in std::mem::take(&mut self.alternatives[rule]) {
+            if rhs.first() != Some(&Symbol::NonTerminal(first)) {
+                alternatives.push((rhs, line));
+                continue;
+            }
+            for (replacement, _) in &self.alternatives[first] {
+                let mut inlined = replacement.clone();
+                inlined.extend_from_slice(&rhs[1..]);
+                alternatives.push((inlined, line));
+            }
+        }
+        self.alternatives[rule] = alternatives;
+    }
+
+    fn remove_direct_recursion(&mut self, rule: usize) {
+        let recursive = Symbol::NonTerminal(rule);
+        let (recursions, others): (Vec<_>, Vec<_>) = std::mem::take(&mut self.alternatives[rule])
+            .into_iter()
+            // `A ::= A` derives nothing new
+            .filter(|(rhs, _)| rhs.as_slice() != [recursive])
+            .partition(|(rhs, _)| rhs.first() == Some(&recursive));
+        if recursions.is_empty() {
+            self.alternatives[rule] = others;
+            return;
+        }
+
+        let tail = self.add(rule);
+        let line = recursions[0].1;
+        self.alternatives[rule] = others
+            .into_iter()
+            .map(|(mut rhs, line)| {
+                rhs.push(Symbol::NonTerminal(tail));
+                (rhs, line)
+            })
+            .collect();
+        self.alternatives[tail] = recursions
+            .into_iter()
+            .map(|(rhs, line)| {
+                let mut rest = rhs[1..].to_vec();
+                rest.push(Symbol::NonTerminal(tail));
+                (rest, line)
+            })
+            .chain(std::iter::once((Vec::new(), line)))
+            .collect();
+    }
+
+    // Factors out the first shared prefix of the rule, if it has one
+    fn factor(&mut self, rule: usize) -> bool {
+        let alternatives = &self.alternatives[rule];
+        let group: Vec<usize> =
+            match alternatives
+                .iter()
+                .enumerate()
+                .find_map(|(index, (rhs, _))| {
+                    let first = rhs.first()?;
+                    let group: Vec<usize> = (index..alternatives.len())
+                        .filter(|&other| alternatives[other].0.first() == Some(first))
+                        .collect();
+                    (group.len() > 1).then_some(group)
+                }) {
+                Some(group) => group,
+                None => return false,
+            };
+
+        let first = &alternatives[group[0]].0;
+        let len = group
+            .iter()
+            .map(|&index| {
+                first
+                    .iter()
+                    .zip(&alternatives[index].0)
+                    .take_while(|(a, b)| a == b)
+                    .count()
+            })
+            .min()
+            .unwrap_or_default();
+        let prefix = first[..len].to_vec();
+        let line = alternatives[group[0]].1;
+
+        let mut suffixes: Vec<(Vec<Symbol>, usize)> = Vec::new();
+        for &index in &group {
+            let (rhs, line) = &alternatives[index];
+            let suffix = rhs[len..].to_vec();
+            if !suffixes.iter().any(|(other, _)| *other == suffix) {
+                suffixes.push((suffix, *line));
+            }
+        }
+
+        let tail = self.add(rule);
+        self.alternatives[tail] = suffixes;
+        let mut factored = prefix;
+        factored.push(Symbol::NonTerminal(tail));
+        let mut kept = Vec::new();
+        for (index, alternative) in std::mem::take(&mut self.alternatives[rule])
+            .into_iter()
+            .enumerate()
+        {
+            if index == group[0] {
+                kept.push((factored.clone(), line));
+            } else if !group.contains(&index) {
+                kept.push(alternative);
+            }
+        }
+        self.alternatives[rule] = kept;
+        true
+    }
+
+    fn into_grammar(self, grammar: &Grammar) -> Grammar {
+        let mut position = vec![0; self.names.len()];
+        for (index, &rule) in self.order.iter().enumerate() {
+            position[rule] = index;
+        }
+        let renumber = |symbol: &Symbol| match *symbol {
+            Symbol::NonTerminal(rule) => Symbol::NonTerminal(position[rule]),
+            terminal => terminal,
+        };
+
+        let mut productions = Vec::new();
+        for &rule in &self.order {
+            for (rhs, line) in &self.alternatives[rule] {
+                productions.push(Production {
+                    lhs: position[rule],
+                    rhs: rhs.iter().map(renumber).collect(),
+                    line: *line,
+                });
+            }
+        }
+
+        Grammar {
+            nonterminals: self
+                .order
+                .iter()
+                .map(|&rule| self.names[rule].clone())
+                .collect(),
+            terminals: grammar.terminals.clone(),
+            productions,
+        }
+    }
+}
+
+/// Lays rows out in columns under a header line.
+pub(crate) fn text_table(header: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|cell| cell.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{}{}", cell, " ".repeat(width - cell.chars().count())))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+
+    let mut table = line(header);
+    let rule: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+    table.push_str(&line(&rule));
+    for row in rows {
+        table.push_str(&line(row));
+    }
+    table
+}
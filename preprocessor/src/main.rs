@@ -1,119 +1,225 @@
-use std::fs;
-use std::io::{self, BufRead};
-use std::path::Path;
+use preprocessor::Grammar;
+
+const DEFAULT_LEX_FILE: &str = "hiwkhao.lex";
+const DEFAULT_GRAMMAR_FILE: &str = "hiwkhao.grammar";
+const DEFAULT_EXPORT_DIR: &str = "automata";
+const DEFAULT_ANALYSIS_DIR: &str = ".";
 
 fn main() {
-    let lex_file = "hiwkhao.lex";
-    let output_file = "./scanner/src/grammar.rs";
-
-    let lines = read_lines(lex_file).expect("Failed to read the .lex file");
-
-    let mut rust_code = String::from(
-        r#"// This file is auto-generated by the preprocessor
-// The preprocessor reads the .lex file and generates the Rust code for logos
-// To regenerate this file, run the preprocessor with the following command:
-// cargo run -p preprocessor
-        
-use logos::Logos;
-use std::fmt;
-
-#[derive(Logos, Clone, Debug, PartialEq)]
-pub enum Token {
-"#,
-    );
-
-    for line in lines {
-        if let Ok(entry) = line {
-            let parts: Vec<&str> = entry.splitn(2, ' ').collect();
-            if parts.len() == 2 {
-                let token_type = parts[0];
-                let regex = parts[1].trim();
-
-                // Handle special cases where we want to generate a function-like enum variant
-                if ["REAL", "INT"].contains(&token_type) {
-                    rust_code.push_str(&format!(
-                        r#"    #[regex(r"{regex}", |lex| lex.slice().parse().map_err(|_| ()))]
-        {token_type}(String),"#,
-                        regex = regex,
-                        token_type = token_type
-                    ));
-                } else if ["VAR"].contains(&token_type) {
-                    rust_code.push_str(&format!(
-                        r#"    #[regex(r"{regex}", |lex| lex.slice().to_string(), priority = 2)]
-        {token_type}(String),"#,
-                        regex = regex,
-                        token_type = token_type
-                    ));
-                } else if token_type == "WHITESPACE" {
-                    rust_code.push_str(&format!(
-                        r#"    #[regex(r"{regex}", logos::skip, priority = 1)]
-        {token_type},"#,
-                        regex = regex,
-                        token_type = token_type
-                    ));
-                } else {
-                    rust_code.push_str(&format!(
-                        r#"    #[regex(r"{regex}")]
-        {token_type},"#,
-                        regex = regex,
-                        token_type = token_type
-                    ));
-                }
-                rust_code.push_str("\n");
-            }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("check") {
+        check(&args[1..]);
+        return;
+    }
+    if args.first().map(String::as_str) == Some("export") {
+        export(&args[1..]);
+        return;
+    }
+    if args.first().map(String::as_str) == Some("parser") {
+        parser(&args[1..]);
+        return;
+    }
+    if args.first().map(String::as_str) == Some("ll") {
+        ll(&args[1..]);
+        return;
+    }
+    if args.first().map(String::as_str) == Some("lr") {
+        lr(&args[1..]);
+        return;
+    }
+
+    let lex_file = args
+        .first()
+        .cloned()
+        .unwrap_or(DEFAULT_LEX_FILE.to_string());
+
+    let rust_code = preprocessor::generate_from_file(&lex_file).unwrap_or_else(|err| exit(err));
+
+    // The scanner regenerates its tokenizer at build time, so this only
+    // writes the code somewhere for inspection.
+    match args.get(1) {
+        Some(output_file) => {
+            std::fs::write(output_file, rust_code)
+                .expect("Failed to write the generated Rust file");
+            println!("Tokenizer Rust code generated in: {}", output_file);
         }
+        None => print!("{}", rust_code),
     }
+}
+
+/// `check [lex_file] [sample ...]`: reports conflicting and shadowed rules,
+/// then which rule wins for each sample string.
+fn check(args: &[String]) {
+    let lex_file = args
+        .first()
+        .cloned()
+        .unwrap_or(DEFAULT_LEX_FILE.to_string());
+    let spec = preprocessor::read_spec(&lex_file).unwrap_or_else(|err| exit(err));
+    let report =
+        preprocessor::validate(&spec).unwrap_or_else(|err| exit(format!("{}:{}", lex_file, err)));
+
+    for diagnostic in &report.diagnostics {
+        println!("{}:{}", lex_file, diagnostic);
+    }
+    if report.diagnostics.is_empty() {
+        println!("{}: no conflicts", lex_file);
+    }
+
+    let name = |rule: usize| spec.rules[rule].name.as_str();
+    for sample in args.iter().skip(1) {
+        let resolution = report.resolve(sample);
+        let matching: Vec<&str> = resolution.matching.iter().map(|&rule| name(rule)).collect();
+        let token = match resolution.token {
+            Some((rule, len)) if len == sample.len() => name(rule).to_string(),
+            Some((rule, len)) => format!("{} for {:?}", name(rule), &sample[..len]),
+            None => "no token".to_string(),
+        };
+        let matching = if matching.is_empty() {
+            "no rule".to_string()
+        } else {
+            matching.join(", ")
+        };
+        println!(
+            "{:?}: matched by {}; lexer reads {}",
+            sample, matching, token
+        );
+    }
+}
+
+/// `export [lex_file] [output_dir] [RULE ...]`: writes the NFA, DFA and
+/// minimized DFA as DOT and CSV, for all rules together (named after the
+/// `.lex` file) and for each rule on its own, or only for the listed rules.
+/// A keyword is exported with the identifier rule that reads it.
+fn export(args: &[String]) {
+    let lex_file = args
+        .first()
+        .cloned()
+        .unwrap_or(DEFAULT_LEX_FILE.to_string());
+    let output_dir = args
+        .get(1)
+        .cloned()
+        .unwrap_or(DEFAULT_EXPORT_DIR.to_string());
+    let selected = args.get(2..).unwrap_or_default();
+    let spec = preprocessor::read_spec(&lex_file).unwrap_or_else(|err| exit(err));
 
-    rust_code.push_str("    #[allow(dead_code)]\n    ERR,\n");
-
-    rust_code.push_str("}\n");
-
-    let display = String::from(
-        r#"
-impl fmt::Display for Token {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Self::REAL(s) => write!(f, "{}", s),
-            Self::INT(s) => write!(f, "{}", s),
-            Self::VAR(s) => write!(f, "{}", s),
-            Self::ADD => write!(f, "+"),
-            Self::SUB => write!(f, "-"),
-            Self::MUL => write!(f, "*"),
-            Self::DIV => write!(f, "/"),
-            Self::INTDIV => write!(f, "//"),
-            Self::POW => write!(f, "^"),
-            Self::LPAREN => write!(f, "("),
-            Self::RPAREN => write!(f, ")"),
-            Self::LBRACKET => write!(f, "["),
-            Self::RBRACKET => write!(f, "]"),
-            Self::EQ => write!(f, "=="),
-            Self::NE => write!(f, "!="),
-            Self::LE => write!(f, "<="),
-            Self::GE => write!(f, ">="),
-            Self::LT => write!(f, "<"),
-            Self::GT => write!(f, ">"),
-            Self::ASSIGN => write!(f, "="),
-            Self::LIST => write!(f, "list"),
-            Self::WHITESPACE => write!(f, "<whitespace>"),
-            Self::ERR => write!(f, "<error>"),
-            Self::NEWLINE => write!(f, "<newline>"),
+    let mut automata = Vec::new();
+    if selected.is_empty() {
+        let stem = std::path::Path::new(&lex_file)
+            .file_stem()
+            .map_or("lexer".to_string(), |stem| {
+                stem.to_string_lossy().to_string()
+            });
+        automata.push((stem, spec.rules.clone()));
+    }
+    for rule in &spec.rules {
+        if !selected.is_empty() && !selected.contains(&rule.name) {
+            continue;
+        }
+        // A keyword has no automaton of its own; it is read by its
+        // identifier rule, so that rule's automata are exported with it
+        let host = rule
+            .keyword
+            .as_ref()
+            .and_then(|name| spec.rules.iter().find(|other| &other.name == name));
+        let rules = match host {
+            Some(host) => vec![host.clone(), rule.clone()],
+            None => vec![rule.clone()],
+        };
+        automata.push((rule.name.clone(), rules));
+    }
+    if let Some(missing) = selected
+        .iter()
+        .find(|name| !spec.rules.iter().any(|rule| &rule.name == *name))
+    {
+        exit(format!("{}: no rule named `{}`", lex_file, missing));
+    }
+
+    std::fs::create_dir_all(&output_dir).expect("Failed to create the output directory");
+    for (name, rules) in automata {
+        let files = preprocessor::export::export(&name, &rules)
+            .unwrap_or_else(|err| exit(format!("{}:{}", lex_file, err)));
+        for (file_name, contents) in files {
+            let path = std::path::Path::new(&output_dir).join(file_name);
+            std::fs::write(&path, contents).expect("Failed to write an automaton file");
         }
     }
+    println!("Automata written to: {}", output_dir);
 }
-"#,
-    );
 
-    rust_code.push_str(&display);
+/// `parser [grammar_file] [lex_file] [output_file]`: generates the LALR(1)
+/// parser of the grammar, whose terminals are tokens of the `.lex` file.
+fn parser(args: &[String]) {
+    let grammar_file = args
+        .first()
+        .cloned()
+        .unwrap_or(DEFAULT_GRAMMAR_FILE.to_string());
+    let lex_file = args.get(1).cloned().unwrap_or(DEFAULT_LEX_FILE.to_string());
 
-    fs::write(output_file, rust_code).expect("Failed to write the generated Rust file");
+    let rust_code = preprocessor::generate_parser_from_files(&grammar_file, &lex_file)
+        .unwrap_or_else(|err| exit(err));
+
+    // The parser regenerates its tables at build time as well
+    match args.get(2) {
+        Some(output_file) => {
+            std::fs::write(output_file, rust_code)
+                .expect("Failed to write the generated Rust file");
+            println!("Parser Rust code generated in: {}", output_file);
+        }
+        None => print!("{}", rust_code),
+    }
+}
 
-    println!("Logos-compatible Rust code generated in: {}", output_file);
+/// `ll [grammar_file] [output_dir]`: prints the FIRST and FOLLOW sets, LL(1)
+/// table, conflicts and left recursion of the grammar and of its rewriting
+/// without left recursion, and writes them as text and CSV files named after
+/// the grammar, next to the parser's `.csv` symbol table by default.
+fn ll(args: &[String]) {
+    write_analysis(args, preprocessor::ll::export, "LL(1)");
+}
+
+/// `lr [grammar_file] [output_dir]`: prints the LR(0) item sets, the SLR and
+/// LALR(1) tables and their conflicts, and writes them with the automaton as
+/// DOT to files named after the grammar.
+fn lr(args: &[String]) {
+    write_analysis(args, preprocessor::lalr::export, "LR");
+}
+
+// Reads `[grammar_file] [output_dir]`, prints the text files of the analysis
+// `files` makes of the grammar and writes all of them, named after the
+// grammar, to the output directory
+fn write_analysis(
+    args: &[String],
+    files: fn(&str, &Grammar) -> Vec<(String, String)>,
+    label: &str,
+) {
+    let grammar_file = args
+        .first()
+        .cloned()
+        .unwrap_or(DEFAULT_GRAMMAR_FILE.to_string());
+    let output_dir = args
+        .get(1)
+        .cloned()
+        .unwrap_or(DEFAULT_ANALYSIS_DIR.to_string());
+    let grammar = preprocessor::read_grammar(&grammar_file).unwrap_or_else(|err| exit(err));
+
+    let stem = std::path::Path::new(&grammar_file)
+        .file_stem()
+        .map_or("grammar".to_string(), |stem| {
+            stem.to_string_lossy().to_string()
+        });
+
+    std::fs::create_dir_all(&output_dir).expect("Failed to create the output directory");
+    for (file_name, contents) in files(&stem, &grammar) {
+        if file_name.ends_with(".txt") {
+            print!("{}", contents);
+        }
+        let path = std::path::Path::new(&output_dir).join(file_name);
+        std::fs::write(&path, contents).expect("Failed to write an analysis file");
+    }
+    println!("\n{} analysis written to: {}", label, output_dir);
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<fs::File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = fs::File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn exit(err: String) -> ! {
+    eprintln!("{}", err);
+    std::process::exit(1);
 }
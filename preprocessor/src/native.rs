@@ -0,0 +1,207 @@
+use crate::automaton::Dfa;
+use crate::generate::read_identifier;
+use crate::spec::Spec;
+
+/// Generates the `native` module: a table-driven lexer that runs the
+/// minimized DFA of the rules and produces the same `Token`s as logos.
+pub fn generate_native(spec: &Spec, dfa: &Dfa) -> String {
+    let rules = &spec.rules;
+    let error = spec.error.as_deref().unwrap_or("()");
+    let imports = if rules
+        .iter()
+        .any(|rule| rule.callback.is_some() && !rule.skip)
+    {
+        "{Outcome, Token}"
+    } else {
+        "Token"
+    };
+    let mut rust_code = format!(
+        r#"
+/// Table-driven lexer generated from the minimized DFA of the rules, an
+/// alternative to the logos-derived `Token::lexer` with the same output.
+pub mod native {{
+    use super::{};
+
+    type Error = {};
+"#,
+        imports, error
+    );
+
+    rust_code.push_str(&format!(
+        "\n    /// Rule accepted in each DFA state.\n    static ACCEPT: [Option<u16>; {}] = [\n",
+        dfa.len()
+    ));
+    for accept in &dfa.accept {
+        match accept {
+            Some(rule) => rust_code.push_str(&format!("        Some({}),\n", rule)),
+            None => rust_code.push_str("        None,\n"),
+        }
+    }
+    rust_code.push_str("    ];\n");
+
+    rust_code.push_str(&format!(
+        "\n    /// Transitions of each DFA state as sorted `(first, last, target)` character ranges.\n    static TRANSITIONS: [&[(char, char, u16)]; {}] = [\n",
+        dfa.len()
+    ));
+    for state in 0..dfa.len() {
+        let ranges: Vec<String> = dfa
+            .transitions(state)
+            .into_iter()
+            .map(|(first, last, target)| format!("({:?}, {:?}, {})", first, last, target))
+            .collect();
+        rust_code.push_str(&format!("        &[{}],\n", ranges.join(", ")));
+    }
+    rust_code.push_str("    ];\n");
+
+    rust_code.push_str(
+        r#"
+    /// Iterator over the tokens of a source string, like `logos::Lexer<Token>`.
+    pub struct Lexer<'s> {
+        source: &'s str,
+        start: usize,
+        end: usize,
+        lookahead: usize,
+    }
+
+    impl<'s> Lexer<'s> {
+        pub fn new(source: &'s str) -> Self {
+            Self {
+                source,
+                start: 0,
+                end: 0,
+                lookahead: 0,
+            }
+        }
+
+        pub fn source(&self) -> &'s str {
+            self.source
+        }
+
+        /// Byte range of the current token.
+        pub fn span(&self) -> std::ops::Range<usize> {
+            self.start..self.end
+        }
+
+        pub fn slice(&self) -> &'s str {
+            &self.source[self.start..self.end]
+        }
+
+        /// The input after the current token.
+        pub fn remainder(&self) -> &'s str {
+            &self.source[self.end..]
+        }
+
+        /// Extends the current token by `n` bytes.
+        pub fn bump(&mut self, n: usize) {
+            self.end += n;
+        }
+
+        /// Offset just past the last character looked at to read the current
+        /// token, counting skipped text before it. Callbacks are assumed to
+        /// look at most one character past the text they consume. Only text
+        /// before this offset decides the token; when it is the length of the
+        /// source, so does where the source ends.
+        pub fn lookahead(&self) -> usize {
+            self.lookahead
+        }
+
+        // Runs the DFA from the end of the last token and returns the rule
+        // and end offset of the longest match
+        fn longest_match(&mut self) -> Option<(u16, usize)> {
+            let mut state = 0;
+            let mut longest = None;
+            let mut seen = self.source.len();
+
+            for (offset, c) in self.remainder().char_indices() {
+                let transitions = TRANSITIONS[state];
+                let index = transitions.partition_point(|&(_, last, _)| last < c);
+                match transitions.get(index) {
+                    Some(&(first, _, target)) if first <= c => state = target as usize,
+                    _ => {
+                        seen = self.end + offset + c.len_utf8();
+                        break;
+                    }
+                }
+                if let Some(rule) = ACCEPT[state] {
+                    longest = Some((rule, self.end + offset + c.len_utf8()));
+                }
+            }
+
+            self.lookahead = self.lookahead.max(seen);
+            longest
+        }
+
+        // Counts the character after the current token as looked at
+        fn look_past_end(&mut self) {
+            let next = self.remainder().chars().next().map_or(0, char::len_utf8);
+            self.lookahead = self.lookahead.max(self.end + next);
+        }
+
+        // Builds the token for the text matched by `rule`, or None for skipped text
+        fn token(&mut self, rule: u16) -> Option<Result<Token, Error>> {
+            Some(match rule {
+"#,
+    );
+    for (index, rule) in rules.iter().enumerate() {
+        let build = if rule.skip {
+            "return None".to_string()
+        } else if spec.keywords_of(&rule.name).next().is_some() {
+            read_identifier(rule, "self")
+        } else {
+            match (&rule.callback, &rule.payload) {
+                (Some(callback), Some(payload)) => format!(
+                    "Outcome::<{}>::into_result({}(self)).map(Token::{})",
+                    payload, callback, rule.name
+                ),
+                (Some(callback), None) => format!(
+                    "Outcome::<()>::into_result({}(self)).map(|()| Token::{})",
+                    callback, rule.name
+                ),
+                (None, Some(_)) => format!(
+                    "self.slice().parse().map(Token::{}).map_err(|_| Default::default())",
+                    rule.name
+                ),
+                (None, None) => format!("Ok(Token::{})", rule.name),
+            }
+        };
+        rust_code.push_str(&format!("                {} => {},\n", index, build));
+    }
+    rust_code.push_str(
+        r#"                _ => unreachable!("no rule {}", rule),
+            })
+        }
+    }
+
+    impl Iterator for Lexer<'_> {
+        type Item = Result<Token, Error>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.lookahead = self.end;
+            loop {
+                self.start = self.end;
+                let c = self.remainder().chars().next()?;
+
+                match self.longest_match() {
+                    Some((rule, end)) => {
+                        self.end = end;
+                        if let Some(token) = self.token(rule) {
+                            self.look_past_end();
+                            return Some(token);
+                        }
+                    }
+                    // Text no rule matches is rejected one character at a time
+                    None => {
+                        self.end += c.len_utf8();
+                        self.look_past_end();
+                        return Some(Err(Default::default()));
+                    }
+                }
+            }
+        }
+    }
+}
+"#,
+    );
+
+    rust_code
+}
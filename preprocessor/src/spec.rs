@@ -0,0 +1,339 @@
+use std::fmt;
+
+/// A single token rule read from the `.lex` file.
+#[derive(Debug, Default, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub regex: String,
+    pub payload: Option<String>,
+    pub callback: Option<String>,
+    pub priority: Option<u32>,
+    pub skip: bool,
+    /// Matched text is kept in the token stream but ignored by the parser.
+    pub trivia: bool,
+    pub display: Option<String>,
+    /// Format string used by `Display` for the payload (default `{}`).
+    pub format: Option<String>,
+    /// Name written for the token in `.tok` output (default: the rule name).
+    pub label: Option<String>,
+    /// For a keyword, the identifier rule whose matches are looked up in the
+    /// keyword table (`@keyword(VAR)`). Keywords have no automaton of their own.
+    pub keyword: Option<String>,
+    /// Line of the `.lex` file the rule was read from.
+    pub line: usize,
+}
+
+impl Rule {
+    /// The text matched by the regex when it only matches a single fixed string.
+    pub fn literal(&self) -> Option<String> {
+        let mut literal = String::new();
+        let mut chars = self.regex.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(escaped) if escaped.is_ascii_punctuation() => literal.push(escaped),
+                    _ => return None,
+                },
+                '.' | '^' | '$' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' => {
+                    return None
+                }
+                _ => literal.push(c),
+            }
+        }
+
+        Some(literal)
+    }
+
+    /// Name of the token in `.tok` output.
+    pub fn label_text(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
+
+    pub fn display_text(&self) -> String {
+        self.display
+            .clone()
+            .or_else(|| self.literal())
+            .unwrap_or_else(|| format!("<{}>", self.name.to_lowercase()))
+    }
+}
+
+/// The contents of a `.lex` file: its rules and `%` directives.
+#[derive(Debug, Default, Clone)]
+pub struct Spec {
+    pub rules: Vec<Rule>,
+    /// Error type of the lexer, set with `%error Type`. The `ERR` token
+    /// carries a value of this type.
+    pub error: Option<String>,
+    /// Extra derives for `Token`, from `%derive Path, ...`.
+    pub derives: Vec<String>,
+    /// Extra attributes for `Token`, from `%attribute name(...)`.
+    pub attributes: Vec<String>,
+}
+
+impl Spec {
+    /// The keywords looked up after the identifier rule `name` matches.
+    pub fn keywords_of<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Rule> + 'a {
+        self.rules
+            .iter()
+            .filter(move |rule| rule.keyword.as_deref() == Some(name))
+    }
+}
+
+/// A problem found while reading the `.lex` file.
+#[derive(Debug, Clone)]
+pub struct SpecError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+/// Reads every rule and directive of a `.lex` file.
+pub fn parse_spec(source: &str) -> Result<Spec, SpecError> {
+    let mut spec = Spec::default();
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        if let Some(directive) = line.trim().strip_prefix('%') {
+            parse_directive(&mut spec, directive).map_err(|message| SpecError {
+                line: line_number,
+                message,
+            })?;
+            continue;
+        }
+
+        match parse_rule(line) {
+            Ok(Some(mut rule)) => {
+                rule.line = line_number;
+                check_name(&spec, &rule)?;
+                spec.rules.push(rule);
+            }
+            Ok(None) => {}
+            Err(message) => {
+                return Err(SpecError {
+                    line: line_number,
+                    message,
+                })
+            }
+        }
+    }
+
+    for rule in &spec.rules {
+        check_keyword(&spec, rule)?;
+    }
+    Ok(spec)
+}
+
+/// A keyword is a fixed word read by an identifier rule with a payload to
+/// look up.
+fn check_keyword(spec: &Spec, rule: &Rule) -> Result<(), SpecError> {
+    let Some(name) = &rule.keyword else {
+        return Ok(());
+    };
+    let identifier = spec.rules.iter().find(|other| &other.name == name);
+
+    let message = if rule.literal().is_none() {
+        format!("keyword `{}` must be a fixed word", rule.name)
+    } else if rule.payload.is_some() || rule.callback.is_some() || rule.skip {
+        format!(
+            "keyword `{}` can't have a payload, callback or @skip",
+            rule.name
+        )
+    } else if identifier.is_none() {
+        format!("keyword `{}` names unknown rule `{}`", rule.name, name)
+    } else if identifier.is_some_and(|identifier| {
+        identifier.keyword.is_some() || identifier.payload.is_none() || identifier.skip
+    }) {
+        format!(
+            "keyword `{}` needs an identifier rule with a payload, not `{}`",
+            rule.name, name
+        )
+    } else {
+        return Ok(());
+    };
+
+    Err(SpecError {
+        line: rule.line,
+        message,
+    })
+}
+
+/// Token names become `Token` variants, so each may only be defined once
+/// and `ERR` is taken by the error token.
+fn check_name(spec: &Spec, rule: &Rule) -> Result<(), SpecError> {
+    let message = if rule.name == "ERR" {
+        "token name `ERR` is reserved for lexical errors".to_string()
+    } else if let Some(first) = spec.rules.iter().find(|other| other.name == rule.name) {
+        format!(
+            "duplicate token name `{}` (first defined on line {})",
+            rule.name, first.line
+        )
+    } else {
+        return Ok(());
+    };
+
+    Err(SpecError {
+        line: rule.line,
+        message,
+    })
+}
+
+/// Applies a `%name value` directive line (without its `%`).
+fn parse_directive(spec: &mut Spec, directive: &str) -> Result<(), String> {
+    let (name, value) = directive
+        .split_once(char::is_whitespace)
+        .map(|(name, value)| (name, value.trim()))
+        .unwrap_or((directive, ""));
+
+    match name {
+        "error" if value.is_empty() => Err("%error needs a type".to_string()),
+        "error" => {
+            spec.error = Some(value.to_string());
+            Ok(())
+        }
+        "derive" if value.is_empty() => Err("%derive needs a trait".to_string()),
+        "derive" => {
+            spec.derives.extend(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|derive| !derive.is_empty())
+                    .map(str::to_string),
+            );
+            Ok(())
+        }
+        "attribute" if value.is_empty() => Err("%attribute needs an attribute".to_string()),
+        "attribute" => {
+            spec.attributes.push(value.to_string());
+            Ok(())
+        }
+        _ => Err(format!("unknown directive %{}", name)),
+    }
+}
+
+/// Parses one line of the `.lex` file. Blank lines and `#` comments yield `None`.
+fn parse_rule(line: &str) -> Result<Option<Rule>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let (name, rest) = line
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| format!("rule `{}` has no regex", line))?;
+    let rest = rest.trim_start();
+    let (regex, mut attributes) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+    let mut rule = Rule {
+        name: name.to_string(),
+        regex: regex.to_string(),
+        ..Rule::default()
+    };
+
+    loop {
+        attributes = attributes.trim_start();
+        if attributes.is_empty() {
+            break;
+        }
+
+        let body = attributes
+            .strip_prefix('@')
+            .ok_or_else(|| format!("expected an attribute, found `{}`", attributes))?;
+        let name_len = body
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(body.len());
+        let (attribute, after) = body.split_at(name_len);
+
+        let (argument, after) = match after.strip_prefix('(') {
+            Some(after) => {
+                let (argument, after) = split_argument(after)
+                    .ok_or_else(|| format!("unterminated argument for @{}", attribute))?;
+                (Some(argument), after)
+            }
+            None => (None, after),
+        };
+        attributes = after;
+
+        let require = |argument: Option<String>| {
+            argument.ok_or_else(|| format!("@{} needs an argument", attribute))
+        };
+        match attribute {
+            "payload" => rule.payload = Some(require(argument)?),
+            "callback" => rule.callback = Some(require(argument)?),
+            "priority" => {
+                let value = require(argument)?;
+                rule.priority = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid priority `{}`", value))?,
+                );
+            }
+            "skip" => rule.skip = true,
+            "trivia" => rule.trivia = true,
+            "display" => rule.display = Some(unquote(&require(argument)?)?),
+            "format" => rule.format = Some(unquote(&require(argument)?)?),
+            "label" => rule.label = Some(unquote(&require(argument)?)?),
+            "keyword" => rule.keyword = Some(require(argument)?),
+            _ => return Err(format!("unknown attribute @{}", attribute)),
+        }
+    }
+
+    Ok(Some(rule))
+}
+
+/// Splits `input` at the `)` closing an attribute argument, honouring nested
+/// parentheses and quoted strings.
+fn split_argument(input: &str) -> Option<(String, &str)> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, c) in input.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                return Some((input[..index].trim().to_string(), &input[index + 1..]))
+            }
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn unquote(argument: &str) -> Result<String, String> {
+    let inner = argument
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a quoted string, found `{}`", argument))?;
+
+    let mut text = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => text.push(chars.next().ok_or("dangling escape in string")?),
+            _ => text.push(c),
+        }
+    }
+
+    Ok(text)
+}
@@ -0,0 +1,254 @@
+//! Checks the `.lex` rules against each other.
+//!
+//! Every state of the (unminimized) DFA knows which rules accept the text
+//! leading to it. When several do, the rules conflict and the one with the
+//! highest priority wins; a rule that never wins anywhere is shadowed and can
+//! never produce a token.
+
+use crate::automaton::{Automata, CharRange};
+use crate::spec::{Spec, SpecError};
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt;
+
+/// Two rules that both match `sample`, resolved in favour of `winner`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub winner: usize,
+    pub loser: usize,
+    /// A shortest text both rules match.
+    pub sample: String,
+    /// The rules have the same priority, so only rule order decides. Logos
+    /// refuses to compile such rules.
+    pub tie: bool,
+}
+
+/// A rule that never produces a token because `by` win everything it matches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shadowed {
+    pub rule: usize,
+    pub by: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Expected behaviour worth knowing about, such as a keyword beating an identifier.
+    Note,
+    /// Almost certainly a mistake in the `.lex` file.
+    Warning,
+}
+
+/// A finding of the validator, reported at a `.lex` line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+        };
+        write!(f, "line {}: {}: {}", self.line, severity, self.message)
+    }
+}
+
+/// How the rules treat a sample string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    /// Rules whose regex matches the whole sample.
+    pub matching: Vec<usize>,
+    /// Rule and byte length of the token the lexer reads from the start of
+    /// the sample.
+    pub token: Option<(usize, usize)>,
+}
+
+/// Everything the validator found in a specification.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub automata: Automata,
+    pub conflicts: Vec<Conflict>,
+    pub shadowed: Vec<Shadowed>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    /// Which rules match `sample` and which one the lexer picks.
+    pub fn resolve(&self, sample: &str) -> Resolution {
+        Resolution {
+            matching: self.automata.nfa.matching_rules(sample),
+            token: self.automata.longest_match(sample),
+        }
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+    }
+}
+
+/// Builds the automata for `spec` and looks for conflicting and shadowed
+/// rules. A regex that can't be compiled is an error.
+pub fn validate(spec: &Spec) -> Result<Report, SpecError> {
+    let automata = Automata::build(&spec.rules)?;
+    let dfa = &automata.dfa;
+    let priorities = &automata.priorities;
+
+    let mut conflicts: Vec<Conflict> = Vec::new();
+    let mut candidates: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); spec.rules.len()];
+    let mut winners: BTreeSet<usize> = BTreeSet::new();
+
+    // Visiting states breadth first finds the shortest sample of each conflict
+    for (state, sample) in shortest_samples(&automata) {
+        let Some(winner) = dfa.accept[state] else {
+            continue;
+        };
+        winners.insert(winner);
+
+        let accepting: BTreeSet<usize> = dfa.sources[state]
+            .iter()
+            .filter_map(|&nfa_state| automata.nfa.states[nfa_state].accept)
+            .collect();
+        for &loser in accepting.iter().filter(|&&rule| rule != winner) {
+            candidates[loser].insert(winner);
+            let known = conflicts
+                .iter()
+                .any(|conflict| conflict.winner == winner && conflict.loser == loser);
+            if !known {
+                conflicts.push(Conflict {
+                    winner,
+                    loser,
+                    sample: sample.clone(),
+                    tie: priorities[winner] == priorities[loser],
+                });
+            }
+        }
+    }
+
+    let shadowed: Vec<Shadowed> = (0..spec.rules.len())
+        .filter(|&rule| !winners.contains(&rule) && spec.rules[rule].keyword.is_none())
+        .map(|rule| Shadowed {
+            rule,
+            by: candidates[rule].iter().copied().collect(),
+        })
+        .collect();
+
+    let rules = &spec.rules;
+    let mut diagnostics = Vec::new();
+    for conflict in &conflicts {
+        let winner = &rules[conflict.winner];
+        let loser = &rules[conflict.loser];
+        let (severity, resolution) = if conflict.tie {
+            (
+                Severity::Warning,
+                format!(
+                    " with equal priority {}; logos rejects this and the native lexer picks the earlier `{}`",
+                    priorities[conflict.winner], winner.name
+                ),
+            )
+        } else {
+            (
+                Severity::Note,
+                format!(
+                    "; `{}` wins with priority {} over {}",
+                    winner.name, priorities[conflict.winner], priorities[conflict.loser]
+                ),
+            )
+        };
+        diagnostics.push(Diagnostic {
+            severity,
+            line: loser.line,
+            message: format!(
+                "`{}` and `{}` (line {}) both match {:?}{}",
+                loser.name, winner.name, winner.line, conflict.sample, resolution
+            ),
+        });
+    }
+    for shadowed in &shadowed {
+        let rule = &rules[shadowed.rule];
+        let message = if shadowed.by.is_empty() {
+            format!("`{}` matches no text and never produces a token", rule.name)
+        } else {
+            let by: Vec<String> = shadowed
+                .by
+                .iter()
+                .map(|&by| format!("`{}` (line {})", rules[by].name, rules[by].line))
+                .collect();
+            format!(
+                "`{}` is shadowed: everything it matches goes to {}",
+                rule.name,
+                by.join(", ")
+            )
+        };
+        diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            line: rule.line,
+            message,
+        });
+    }
+    // A keyword only exists if its identifier rule reads the whole word
+    for (identifier, word, keyword) in &automata.keywords {
+        let read = automata.min_dfa.longest_match(word);
+        if read != Some((*identifier, word.len())) {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                line: rules[*keyword].line,
+                message: format!(
+                    "keyword `{}` is never produced: `{}` does not read {:?} as one token",
+                    rules[*keyword].name, rules[*identifier].name, word
+                ),
+            });
+        }
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+
+    Ok(Report {
+        automata,
+        conflicts,
+        shadowed,
+        diagnostics,
+    })
+}
+
+/// Each reachable DFA state with a shortest text leading to it, in
+/// breadth-first order.
+fn shortest_samples(automata: &Automata) -> Vec<(usize, String)> {
+    let dfa = &automata.dfa;
+    let mut samples: Vec<Option<String>> = vec![None; dfa.len()];
+    let mut order = Vec::new();
+    let mut queue = VecDeque::from([0]);
+    samples[0] = Some(String::new());
+
+    while let Some(state) = queue.pop_front() {
+        let sample = samples[state].clone().unwrap_or_default();
+        for (class, target) in dfa.table[state].iter().enumerate() {
+            if let Some(target) = *target {
+                if samples[target].is_none() {
+                    let mut next = sample.clone();
+                    next.push(representative(dfa.classes[class]));
+                    samples[target] = Some(next);
+                    queue.push_back(target);
+                }
+            }
+        }
+        order.push((state, sample));
+    }
+
+    order
+}
+
+/// A readable character from a class: printable ASCII if there is any.
+fn representative((start, end): CharRange) -> char {
+    let printable = start.max(0x21);
+    let code = if printable <= end.min(0x7E) {
+        printable
+    } else if start <= 0x20 && 0x20 <= end {
+        0x20
+    } else {
+        start
+    };
+    char::from_u32(code).unwrap_or('\u{E000}')
+}
@@ -0,0 +1,52 @@
+use preprocessor::automaton::Automata;
+use preprocessor::parse_spec;
+
+fn build(source: &str) -> Automata {
+    Automata::build(&parse_spec(source).unwrap().rules).unwrap()
+}
+
+#[test]
+fn longest_match_wins() {
+    let automata = build("INT [0-9]+\nREAL [0-9]+\\.[0-9]*\n");
+    assert_eq!(automata.min_dfa.longest_match("12.5+"), Some((1, 4)));
+    assert_eq!(automata.min_dfa.longest_match("12+"), Some((0, 2)));
+    assert_eq!(automata.min_dfa.longest_match("+"), None);
+}
+
+#[test]
+fn priority_settles_ties() {
+    // `list` is a longer literal than one identifier character, so it wins
+    let automata = build("VAR [a-z]+\nLIST list\n");
+    assert_eq!(automata.priorities, vec![2, 8]);
+    assert_eq!(automata.min_dfa.longest_match("list"), Some((1, 4)));
+    assert_eq!(automata.min_dfa.longest_match("lists"), Some((0, 5)));
+
+    let automata = build("VAR [a-z]+ @priority(9)\nLIST list\n");
+    assert_eq!(automata.min_dfa.longest_match("list"), Some((0, 4)));
+}
+
+#[test]
+fn minimization_merges_states() {
+    let automata = build("A (a|b)*abb\n");
+    assert!(automata.min_dfa.len() < automata.dfa.len());
+    assert_eq!(automata.min_dfa.len(), 4);
+    assert_eq!(automata.min_dfa.longest_match("ababb"), Some((0, 5)));
+    assert_eq!(automata.min_dfa.longest_match("abab"), None);
+}
+
+#[test]
+fn invalid_regex_reports_line() {
+    let spec = parse_spec("INT [0-9]+\nBAD [0-9\n").unwrap();
+    let err = Automata::build(&spec.rules).unwrap_err();
+    assert_eq!(err.line, 2);
+}
+
+#[test]
+fn lex_file_compiles() {
+    let source = include_str!("../../hiwkhao.lex");
+    let automata = build(source);
+    assert!(automata.min_dfa.len() <= automata.dfa.len());
+    assert!(preprocessor::generate(&parse_spec(source).unwrap())
+        .unwrap()
+        .contains("pub mod native"));
+}
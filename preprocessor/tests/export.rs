@@ -0,0 +1,77 @@
+use preprocessor::export::{dfa_to_csv, dfa_to_dot, export, nfa_to_dot, ranges_label};
+use preprocessor::{automaton::Automata, parse_spec};
+
+#[test]
+fn accepting_states_name_their_token() {
+    let spec = parse_spec("INT [0-9]+\nWS \\x20+ @skip\n").unwrap();
+    let automata = Automata::build(&spec.rules).unwrap();
+
+    let dot = dfa_to_dot("test", &automata.min_dfa, &spec.rules);
+    assert!(dot.starts_with("digraph \"test DFA\" {"));
+    assert!(dot.contains("[shape=doublecircle, label=\"2\\nINT\"]"));
+    assert!(dot.contains("label=\"1\\nWS (skip)\""));
+    assert!(dot.contains("0 -> 2 [label=\"0-9\"];"));
+
+    let nfa = nfa_to_dot("test", &automata.nfa, &spec.rules);
+    assert!(nfa.contains("[label=\"ε\", style=dashed]"));
+}
+
+#[test]
+fn transition_table() {
+    let spec = parse_spec("LIST list\n").unwrap();
+    let automata = Automata::build(&spec.rules).unwrap();
+    let csv = dfa_to_csv(&automata.min_dfa, &spec.rules);
+    assert_eq!(
+        csv,
+        "state,accept,i,l,s,t\n0,,,1,,\n1,,2,,,\n2,,,,3,\n3,,,,,4\n4,LIST,,,,\n"
+    );
+}
+
+#[test]
+fn long_classes_are_shortened() {
+    assert_eq!(ranges_label(&[(0x61, 0x7A), (0x5F, 0x5F)]), "a-z _");
+    assert_eq!(
+        ranges_label(&[
+            (0x30, 0x39),
+            (0x41, 0x46),
+            (0x0A, 0x0A),
+            (0x20, 0x20),
+            (0xE01, 0xE30)
+        ]),
+        "0-9 A-F \\n … +2 ranges"
+    );
+}
+
+#[test]
+fn one_file_per_automaton() {
+    let spec = parse_spec("ADD \\+\n").unwrap();
+    let names: Vec<String> = export("ADD", &spec.rules)
+        .unwrap()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "ADD.nfa.dot",
+            "ADD.nfa.csv",
+            "ADD.dfa.dot",
+            "ADD.dfa.csv",
+            "ADD.min.dot",
+            "ADD.min.csv"
+        ]
+    );
+}
+
+#[test]
+fn keywords_label_their_identifier_rule() {
+    let spec = parse_spec("VAR [a-z]+ @payload(String)\nLIST list @keyword(VAR)\n").unwrap();
+    let automata = Automata::build(&spec.rules).unwrap();
+    let csv = dfa_to_csv(&automata.min_dfa, &spec.rules);
+    assert_eq!(csv, "state,accept,a-z\n0,,1\n1,VAR (list: LIST),1\n");
+
+    let files = export("LIST", &spec.rules).unwrap();
+    let (name, keywords) = files.last().unwrap();
+    assert_eq!(name, "LIST.keywords.csv");
+    assert_eq!(keywords, "word,rule,token\nlist,VAR,LIST\n");
+}
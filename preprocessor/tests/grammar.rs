@@ -0,0 +1,117 @@
+use preprocessor::bnf::Symbol;
+use preprocessor::lalr::{Action, Automaton};
+use preprocessor::{generate_parser, parse_grammar, parse_spec};
+
+const EXPRESSIONS: &str = "\
+<expr> ::= <expr> \"+\" <term> | <term>
+<term> ::= <term> \"*\" INT
+       | INT
+";
+
+#[test]
+fn rules_and_continuation_lines() {
+    let grammar = parse_grammar(EXPRESSIONS).unwrap();
+    assert_eq!(grammar.nonterminals, vec!["expr", "term"]);
+    assert_eq!(grammar.terminals, vec!["\"+\"", "\"*\"", "INT"]);
+    assert_eq!(grammar.productions.len(), 4);
+    assert_eq!(grammar.productions[3].line, 3);
+    assert_eq!(grammar.productions[3].rhs, vec![Symbol::Terminal(2)]);
+    assert_eq!(grammar.production_text(0), "<expr> ::= <expr> \"+\" <term>");
+}
+
+#[test]
+fn grammar_errors_have_lines() {
+    let err = parse_grammar("<a> ::= <b> INT\n").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 1: rule `<b>` is used but never defined"
+    );
+
+    let err = parse_grammar("<a> ::= INT\n\n<a> ::= REAL\n").unwrap_err();
+    assert_eq!(err.line, 3);
+    assert!(parse_grammar("| INT\n").is_err());
+    assert!(parse_grammar("<a> ::= \"+\n").is_err());
+}
+
+#[test]
+fn nullable_and_first() {
+    let grammar = parse_grammar("<s> ::= <a> \"x\"\n<a> ::= \"y\" | ε\n").unwrap();
+    let first = grammar.first_sets();
+    assert_eq!(first.nullable, vec![false, true]);
+    assert_eq!(
+        first.first[0].iter().copied().collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+}
+
+#[test]
+fn lalr_table_of_left_recursion() {
+    let grammar = parse_grammar(EXPRESSIONS).unwrap();
+    let automaton = Automaton::build(&grammar);
+    let table = automaton.lalr_table();
+    assert!(table.conflicts.is_empty());
+    assert!(matches!(table.action[0].get(&2), Some(Action::Shift(_))));
+    assert!(table
+        .action
+        .iter()
+        .any(|row| row.values().any(|&a| a == Action::Accept)));
+}
+
+#[test]
+fn ambiguous_grammar_is_rejected() {
+    let spec = parse_spec("INT [0-9]+ @payload(i64)\nADD \\+\n").unwrap();
+    let grammar = parse_grammar("<e> ::= <e> \"+\" <e> | INT\n").unwrap();
+    let err = generate_parser(&grammar, &spec).unwrap_err();
+    assert_eq!(err.line, 1);
+    assert!(err.message.contains("not LALR(1)"), "{}", err);
+}
+
+#[test]
+fn terminals_must_be_tokens() {
+    let spec = parse_spec("INT [0-9]+ @payload(i64)\nADD \\+\n").unwrap();
+    let grammar = parse_grammar("<e> ::= INT \"-\" INT\n").unwrap();
+    let err = generate_parser(&grammar, &spec).unwrap_err();
+    assert_eq!(err.message, "terminal \"-\" is not a token of the lexer");
+
+    let grammar = parse_grammar("<e> ::= INT \"+\" INT\n").unwrap();
+    let code = generate_parser(&grammar, &spec).unwrap();
+    assert!(code.contains("Token::INT(_) => Some(0),"));
+    assert!(code.contains("Token::ADD => Some(1),"));
+}
+
+#[test]
+fn slr_is_weaker_than_lalr() {
+    // The classic assignment grammar: SLR reduces `<r> ::= <l>` on "=" too
+    let grammar =
+        parse_grammar("<s> ::= <l> \"=\" <r> | <r>\n<l> ::= \"*\" <r> | ID\n<r> ::= <l>\n")
+            .unwrap();
+    let automaton = Automaton::build(&grammar);
+    assert!(automaton.lalr_table().conflicts.is_empty());
+
+    let slr = automaton.slr_table();
+    assert_eq!(slr.conflicts.len(), 1);
+    assert_eq!(slr.conflicts[0].kind(), "shift/reduce");
+    assert!(automaton
+        .conflicts_text(&slr)
+        .contains("on \"=\": shift/reduce conflict between s"));
+    assert!(automaton.table_csv(&slr).contains("/r4"));
+}
+
+#[test]
+fn reports_and_dot() {
+    let grammar = parse_grammar("<e> ::= <e> \"+\" <e> | INT\n").unwrap();
+    let automaton = Automaton::build(&grammar);
+    let dot = automaton.to_dot("e");
+    assert!(dot.starts_with("digraph \"e LR(0)\" {"));
+    assert!(dot.contains("label=\"I0\\n<e'> ::= · <e>\\l<e> ::= · <e> \\\"+\\\" <e>\\l"));
+    assert!(dot.contains("peripheries=2"));
+    assert!(dot.contains("0 -> 2 [label=\"<e>\"];"));
+    assert!(automaton
+        .item_sets_text()
+        .starts_with("I0\n    <e'> ::= · <e>\n  + "));
+
+    let files = preprocessor::lalr::export("e", &grammar);
+    let report = &files.iter().find(|(name, _)| name == "e.lr.txt").unwrap().1;
+    assert!(report.contains("SLR table"));
+    assert!(report.contains("shift/reduce conflict between"));
+}
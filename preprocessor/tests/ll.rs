@@ -0,0 +1,102 @@
+use preprocessor::ll::{export, left_factor, remove_left_recursion, rewrite, Analysis};
+use preprocessor::parse_grammar;
+
+const EXPRESSIONS: &str = "\
+<expr> ::= <expr> \"+\" <term> | <term>
+<term> ::= <term> \"*\" INT | INT
+";
+
+fn names(analysis: &Analysis, set: &std::collections::BTreeSet<usize>) -> Vec<String> {
+    set.iter()
+        .map(|&terminal| analysis.grammar.terminal_name(terminal).to_string())
+        .collect()
+}
+
+#[test]
+fn follow_sets() {
+    let grammar = parse_grammar("<s> ::= <a> \"x\" | \"y\"\n<a> ::= \"z\" <a> | ε\n").unwrap();
+    let analysis = Analysis::new(&grammar);
+    assert_eq!(analysis.first.nullable, vec![false, true]);
+    assert_eq!(names(&analysis, &analysis.follow[0]), vec!["$"]);
+    assert_eq!(names(&analysis, &analysis.follow[1]), vec!["\"x\""]);
+    assert!(analysis.conflicts().is_empty());
+    // `<a> ::= ε` is predicted on what follows `<a>`
+    assert_eq!(analysis.table[1][&0], vec![3]);
+}
+
+#[test]
+fn left_recursion_is_reported() {
+    let grammar = parse_grammar(EXPRESSIONS).unwrap();
+    let analysis = Analysis::new(&grammar);
+    assert_eq!(analysis.left_recursion, vec![vec![0], vec![2]]);
+    assert!(!analysis.conflicts().is_empty());
+    assert!(analysis
+        .report()
+        .contains("<expr> ::= <expr> \"+\" <term> (line 1)\n"));
+
+    let indirect = parse_grammar("<a> ::= <b> \"x\" | \"y\"\n<b> ::= <a> \"z\"\n").unwrap();
+    assert_eq!(Analysis::new(&indirect).left_recursion, vec![vec![0, 2]]);
+}
+
+#[test]
+fn rewriting_removes_left_recursion() {
+    let grammar = parse_grammar(EXPRESSIONS).unwrap();
+    let rewritten = rewrite(&grammar);
+    assert_eq!(
+        rewritten.to_string(),
+        "<expr> ::= <term> <expr'>\n\
+         <expr'> ::= \"+\" <term> <expr'> | ε\n\
+         <term> ::= INT <term'>\n\
+         <term'> ::= \"*\" INT <term'> | ε\n"
+    );
+    let analysis = Analysis::new(&rewritten);
+    assert!(analysis.left_recursion.is_empty());
+    assert!(analysis.conflicts().is_empty());
+
+    let indirect = parse_grammar("<a> ::= <b> \"x\" | \"y\"\n<b> ::= <a> \"z\"\n").unwrap();
+    let rewritten = remove_left_recursion(&indirect);
+    assert!(Analysis::new(&rewritten).left_recursion.is_empty());
+    assert_eq!(
+        parse_grammar(&rewritten.to_string()).unwrap().to_string(),
+        rewritten.to_string()
+    );
+}
+
+#[test]
+fn common_prefixes_are_factored() {
+    let grammar =
+        parse_grammar("<s> ::= \"if\" <e> \"then\" | \"if\" <e> \"else\" | <e>\n<e> ::= INT\n")
+            .unwrap();
+    assert_eq!(
+        left_factor(&grammar).to_string(),
+        "<s> ::= \"if\" <e> <s'> | <e>\n<s'> ::= \"then\" | \"else\"\n<e> ::= INT\n"
+    );
+}
+
+#[test]
+fn csv_files_are_named_after_the_grammar() {
+    let grammar = parse_grammar(EXPRESSIONS).unwrap();
+    let files = export("expr", &grammar);
+    let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+    assert!(names.contains(&"expr.ll1.txt"));
+    assert!(names.contains(&"expr.rewritten.grammar"));
+
+    let sets = &files
+        .iter()
+        .find(|(name, _)| name == "expr.first_follow.csv")
+        .unwrap()
+        .1;
+    assert_eq!(
+        sets,
+        r#"nonterminal,nullable,first,follow
+<expr>,false,INT,"""+"" $"
+<term>,false,INT,"""+"" ""*"" $"
+"#
+    );
+    let table = &files
+        .iter()
+        .find(|(name, _)| name == "expr.ll1.csv")
+        .unwrap()
+        .1;
+    assert!(table.starts_with("nonterminal,INT\n"));
+}
@@ -0,0 +1,66 @@
+use preprocessor::{generate, parse_spec};
+
+#[test]
+fn derive_and_attribute_directives() {
+    let spec = parse_spec(
+        "%derive serde::Serialize, serde::Deserialize\n%attribute serde(tag = \"kind\")\nINT [0-9]+\n",
+    )
+    .unwrap();
+    assert_eq!(spec.derives, vec!["serde::Serialize", "serde::Deserialize"]);
+    assert_eq!(spec.attributes, vec!["serde(tag = \"kind\")"]);
+
+    let code = generate(&spec).unwrap();
+    assert!(code.contains("serde::Serialize, serde::Deserialize)]"));
+    assert!(code.contains("#[serde(tag = \"kind\")]"));
+}
+
+#[test]
+fn empty_directives_are_errors() {
+    let err = parse_spec("INT [0-9]+\n%derive\n").err().unwrap();
+    assert_eq!(err.line, 2);
+    assert!(parse_spec("%attribute\n").is_err());
+}
+
+#[test]
+fn keywords_belong_to_an_identifier_rule() {
+    let spec =
+        parse_spec("VAR [a-z]+ @payload(String)\nIF if @keyword(VAR)\nLIST list @keyword(VAR)\n")
+            .unwrap();
+    let names: Vec<&str> = spec
+        .keywords_of("VAR")
+        .map(|rule| rule.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["IF", "LIST"]);
+
+    for (source, message) in [
+        (
+            "VAR [a-z]+ @payload(String)\nIF i[fn] @keyword(VAR)\n",
+            "must be a fixed word",
+        ),
+        (
+            "VAR [a-z]+ @payload(String)\nIF if @keyword(VAR) @skip\n",
+            "can't have a payload",
+        ),
+        (
+            "VAR [a-z]+ @payload(String)\nIF if @keyword(NAME)\n",
+            "unknown rule `NAME`",
+        ),
+        (
+            "VAR [a-z]+\nIF if @keyword(VAR)\n",
+            "needs an identifier rule",
+        ),
+    ] {
+        let err = parse_spec(source).unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(err.message.contains(message), "{}", err);
+    }
+}
+
+#[test]
+fn keywords_are_read_by_their_identifier() {
+    let spec = parse_spec("VAR [a-z]+ @payload(String)\nIF if @keyword(VAR)\n").unwrap();
+    let code = generate(&spec).unwrap();
+    assert!(code.contains("Token::VAR(word).resolve_keyword()"));
+    assert!(code.contains("\"if\" => Self::IF,"));
+    assert!(code.contains("matches!(self, Self::IF)"));
+}
@@ -0,0 +1,95 @@
+use preprocessor::{parse_spec, validate, Severity};
+
+#[test]
+fn keyword_is_looked_up_after_identifier() {
+    let spec = parse_spec(include_str!("../../hiwkhao.lex")).unwrap();
+    let report = validate(&spec).unwrap();
+    assert_eq!(report.warnings().count(), 0);
+    assert!(report.shadowed.is_empty());
+
+    let var = spec
+        .rules
+        .iter()
+        .position(|rule| rule.name == "VAR")
+        .unwrap();
+    let list = spec
+        .rules
+        .iter()
+        .position(|rule| rule.name == "LIST")
+        .unwrap();
+    // Only VAR reads the text; the keyword table turns it into LIST
+    let resolution = report.resolve("list");
+    assert_eq!(resolution.matching, vec![var]);
+    assert_eq!(resolution.token, Some((list, 4)));
+    assert_eq!(report.resolve("lists").token, Some((var, 5)));
+}
+
+#[test]
+fn unreadable_keyword_is_a_warning() {
+    let spec = parse_spec(
+        "VAR [a-z]+ @payload(String)
+IF if @keyword(VAR)
+NEXT next2 @keyword(VAR)
+",
+    )
+    .unwrap();
+    let report = validate(&spec).unwrap();
+    let warnings: Vec<_> = report.warnings().collect();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].to_string(),
+        "line 3: warning: keyword `NEXT` is never produced: `VAR` does not read \"next2\" as one token"
+    );
+}
+
+#[test]
+fn shadowed_rule() {
+    let spec = parse_spec("VAR [a-z]+ @priority(10)\nLIST list\n").unwrap();
+    let report = validate(&spec).unwrap();
+    assert_eq!(report.shadowed.len(), 1);
+    assert_eq!(report.shadowed[0].rule, 1);
+    assert_eq!(report.shadowed[0].by, vec![0]);
+
+    let warnings: Vec<_> = report.warnings().collect();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].line, 2);
+    assert_eq!(
+        warnings[0].to_string(),
+        "line 2: warning: `LIST` is shadowed: everything it matches goes to `VAR` (line 1)"
+    );
+}
+
+#[test]
+fn equal_priority_is_a_warning() {
+    let spec = parse_spec("A a+\nB [ab]\n").unwrap();
+    let report = validate(&spec).unwrap();
+    assert_eq!(report.conflicts.len(), 1);
+    assert!(report.conflicts[0].tie);
+    assert_eq!(report.conflicts[0].sample, "a");
+    assert_eq!(report.diagnostics[0].severity, Severity::Warning);
+    assert_eq!(report.diagnostics[0].line, 2);
+}
+
+#[test]
+fn invalid_regex_is_fatal() {
+    let spec = parse_spec("INT [0-9]+\n\nBAD (a\n").unwrap();
+    let err = validate(&spec).unwrap_err();
+    assert_eq!(err.line, 3);
+    assert!(err.message.contains("`BAD`"));
+}
+
+#[test]
+fn duplicate_token_name() {
+    let err = parse_spec("INT [0-9]+\nADD \\+\nINT 0x[0-9a-f]+\n").unwrap_err();
+    assert_eq!(err.line, 3);
+    assert_eq!(
+        err.to_string(),
+        "line 3: duplicate token name `INT` (first defined on line 1)"
+    );
+}
+
+#[test]
+fn err_is_reserved() {
+    let err = parse_spec("ERR .\n").unwrap_err();
+    assert_eq!(err.line, 1);
+}
@@ -0,0 +1,22 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let lex_file = Path::new(&manifest_dir).join("..").join("hiwkhao.lex");
+    println!("cargo:rerun-if-changed={}", lex_file.display());
+
+    let rust_code =
+        preprocessor::generate_from_file(&lex_file).unwrap_or_else(|err| panic!("{}", err));
+
+    // Conflicts settled by priority are expected; shadowed rules and ties are not
+    let report = preprocessor::check_file(&lex_file).unwrap_or_else(|err| panic!("{}", err));
+    for warning in report.warnings() {
+        println!("cargo:warning=hiwkhao.lex:{}", warning);
+    }
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("grammar.rs"), rust_code)
+        .expect("Failed to write the generated Rust file");
+}
@@ -0,0 +1,176 @@
+//! Callbacks referenced by `@callback(...)` rules in `hiwkhao.lex`.
+//!
+//! Errors are returned with a default span; [`crate::spanned`] replaces it
+//! with the span of the rejected text. The callbacks are shared by the logos
+//! and native backends through [`LexerState`].
+
+use crate::error::LexError;
+use crate::grammar::{native, Token};
+use crate::Span;
+use std::num::IntErrorKind;
+use unicode_normalization::UnicodeNormalization;
+
+/// The parts of a lexer the callbacks use.
+pub trait LexerState<'s> {
+    /// Text of the current token.
+    fn slice(&self) -> &'s str;
+    /// Input after the current token.
+    fn remainder(&self) -> &'s str;
+    /// Extends the current token by `n` bytes.
+    fn bump(&mut self, n: usize);
+}
+
+impl<'s> LexerState<'s> for logos::Lexer<'s, Token> {
+    fn slice(&self) -> &'s str {
+        logos::Lexer::slice(self)
+    }
+
+    fn remainder(&self) -> &'s str {
+        logos::Lexer::remainder(self)
+    }
+
+    fn bump(&mut self, n: usize) {
+        logos::Lexer::bump(self, n)
+    }
+}
+
+impl<'s> LexerState<'s> for native::Lexer<'s> {
+    fn slice(&self) -> &'s str {
+        native::Lexer::slice(self)
+    }
+
+    fn remainder(&self) -> &'s str {
+        native::Lexer::remainder(self)
+    }
+
+    fn bump(&mut self, n: usize) {
+        native::Lexer::bump(self, n)
+    }
+}
+
+/// Produces the value of an (unsigned) integer literal.
+///
+/// Accepts `0x`, `0b` and `0o` prefixes and `_` digit separators. A value that
+/// does not fit in an `i64` is an error rather than being truncated.
+pub fn integer<'s>(lex: &mut impl LexerState<'s>) -> Result<i64, LexError> {
+    let digits = lex.slice();
+    let (radix, digits) = match digits.get(..2) {
+        Some("0x" | "0X") => (16, &digits[2..]),
+        Some("0b" | "0B") => (2, &digits[2..]),
+        Some("0o" | "0O") => (8, &digits[2..]),
+        _ => (10, digits),
+    };
+
+    let digits: String = digits.chars().filter(|c| *c != '_').collect();
+    i64::from_str_radix(&digits, radix).map_err(|err| match err.kind() {
+        IntErrorKind::PosOverflow => LexError::NumberOutOfRange(Span::default()),
+        _ => LexError::MalformedNumber(Span::default()),
+    })
+}
+
+/// Produces the value of a real literal such as `1_000.5`, `.5`, `5.` or `2E-3`.
+/// Values too large for an `f64` are an error.
+pub fn real<'s>(lex: &mut impl LexerState<'s>) -> Result<f64, LexError> {
+    let text: String = lex.slice().chars().filter(|c| *c != '_').collect();
+    match text.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        Ok(_) => Err(LexError::NumberOutOfRange(Span::default())),
+        Err(_) => Err(LexError::MalformedNumber(Span::default())),
+    }
+}
+
+/// Produces the name of an identifier in Unicode normalization form C, so
+/// differently composed spellings of the same name are one variable.
+pub fn identifier<'s>(lex: &mut impl LexerState<'s>) -> String {
+    lex.slice().nfc().collect()
+}
+
+/// Consumes the rest of a string literal after its opening `"` and produces
+/// its value.
+///
+/// A string must close on the line it starts on. An unterminated string or a
+/// bad escape still consumes the literal, so scanning resumes after it.
+pub fn string<'s>(lex: &mut impl LexerState<'s>) -> Result<String, LexError> {
+    let rest = lex.remainder();
+    let mut escaped = false;
+    let mut end = None;
+
+    for (index, c) in rest.char_indices() {
+        match c {
+            '\r' | '\n' => break,
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => {
+                end = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    match end {
+        Some(end) => {
+            lex.bump(end + 1);
+            unescape(&rest[..end])
+        }
+        None => {
+            lex.bump(rest.find(['\r', '\n']).unwrap_or(rest.len()));
+            Err(LexError::UnterminatedString(Span::default()))
+        }
+    }
+}
+
+/// Resolves `\n`, `\t`, `\r`, `\0`, `\\` and `\"`. Any other escape is an error.
+pub fn unescape(text: &str) -> Result<String, LexError> {
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            Some('0') => value.push('\0'),
+            Some('\\') => value.push('\\'),
+            Some('"') => value.push('"'),
+            _ => return Err(LexError::InvalidEscape(Span::default())),
+        }
+    }
+
+    Ok(value)
+}
+
+/// Consumes the rest of a block comment after its opening `/*`.
+///
+/// Block comments nest, so `/* a /* b */ c */` is a single comment. An
+/// unterminated comment swallows the rest of the input and is an error.
+pub fn block_comment<'s>(lex: &mut impl LexerState<'s>) -> Result<(), LexError> {
+    let rest = lex.remainder().as_bytes();
+    let mut depth = 1;
+    let mut index = 0;
+
+    while index + 1 < rest.len() {
+        match (rest[index], rest[index + 1]) {
+            (b'/', b'*') => {
+                depth += 1;
+                index += 2;
+            }
+            (b'*', b'/') => {
+                depth -= 1;
+                index += 2;
+                if depth == 0 {
+                    lex.bump(index);
+                    return Ok(());
+                }
+            }
+            _ => index += 1,
+        }
+    }
+
+    lex.bump(rest.len());
+    Err(LexError::UnterminatedComment(Span::default()))
+}
@@ -0,0 +1,265 @@
+//! A lexer built from a `.lex` file at runtime.
+//!
+//! Each rule is compiled by a lazy DFA (`regex_automata::hybrid`) instead of
+//! generated code, so a changed or student-written `.lex` file can be tried
+//! without rebuilding. Like logos it takes the longest match at each
+//! position, settling ties by priority and then rule order. Callbacks can't
+//! be compiled at runtime; the ones in [`crate::callbacks`] are looked up by
+//! path, which keeps strings and block comments working. Keywords have no
+//! DFA: the text of their identifier rule is looked up in a keyword table.
+
+use crate::callbacks::{self, LexerState};
+use crate::{bom_len, LexError, LineTracker, Span};
+use preprocessor::automaton::{parse_regex, priority};
+use preprocessor::{parse_spec, Rule, SpecError};
+use regex_automata::hybrid::dfa::{Cache, DFA};
+use regex_automata::{Anchored, Input, MatchKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// A token of a runtime-loaded lexer, named after the rule that matched it.
+///
+/// Text no rule matches, or that a callback rejects, is named `ERR` and
+/// carries the error.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DynamicToken {
+    #[serde(rename = "kind")]
+    pub name: String,
+    pub lexeme: String,
+    pub span: Span,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<LexError>,
+}
+
+type Callback = fn(&mut Cursor<'_>) -> Result<(), LexError>;
+
+struct DynamicRule {
+    rule: Rule,
+    dfa: DFA,
+    priority: usize,
+    callback: Option<Callback>,
+}
+
+/// The rules of a `.lex` file, compiled for scanning.
+pub struct DynamicLexer {
+    rules: Vec<DynamicRule>,
+    // Keyword named by each identifier rule and word
+    keywords: HashMap<(usize, String), String>,
+    by_name: HashMap<String, Rule>,
+}
+
+impl DynamicLexer {
+    pub fn from_file<P: AsRef<Path>>(lex_file: P) -> Result<Self, String> {
+        let lex_file = lex_file.as_ref();
+        let source = fs::read_to_string(lex_file)
+            .map_err(|err| format!("Failed to read {}: {}", lex_file.display(), err))?;
+        Self::from_source(&source).map_err(|err| format!("{}:{}", lex_file.display(), err))
+    }
+
+    /// Compiles the rules of `.lex` source text.
+    pub fn from_source(source: &str) -> Result<Self, SpecError> {
+        let spec = parse_spec(source)?;
+        let mut rules = Vec::new();
+        let by_name = spec
+            .rules
+            .iter()
+            .map(|rule| (rule.name.clone(), rule.clone()))
+            .collect();
+
+        for rule in spec.rules.iter().filter(|rule| rule.keyword.is_none()) {
+            let error = |message: String| SpecError {
+                line: rule.line,
+                message,
+            };
+            let hir = parse_regex(rule)?;
+            let dfa = DFA::builder()
+                .configure(DFA::config().match_kind(MatchKind::All))
+                .build(&rule.regex)
+                .map_err(|err| error(format!("invalid regex for `{}`: {}", rule.name, err)))?;
+            let callback = match &rule.callback {
+                Some(path) => Some(
+                    callback(path).ok_or_else(|| error(format!("unknown callback `{}`", path)))?,
+                ),
+                None => None,
+            };
+
+            rules.push(DynamicRule {
+                priority: rule.priority.map_or_else(|| priority(&hir), |p| p as usize),
+                rule: rule.clone(),
+                dfa,
+                callback,
+            });
+        }
+
+        let mut keywords = HashMap::new();
+        for keyword in &spec.rules {
+            let Some(name) = &keyword.keyword else {
+                continue;
+            };
+            let identifier = rules
+                .iter()
+                .position(|rule: &DynamicRule| &rule.rule.name == name)
+                .expect("keywords name a rule");
+            let word = keyword.literal().expect("keywords are fixed words");
+            keywords.insert((identifier, word), keyword.name.clone());
+        }
+
+        Ok(DynamicLexer {
+            rules,
+            keywords,
+            by_name,
+        })
+    }
+
+    /// The rule a token was read by, or `None` for `ERR`.
+    pub fn rule(&self, name: &str) -> Option<&Rule> {
+        self.by_name.get(name)
+    }
+
+    /// Scans `input`, dropping text matched by `@skip` rules. Scanning
+    /// continues after rejected text.
+    pub fn tokenize(&self, input: &str) -> Vec<DynamicToken> {
+        let mut caches: Vec<Cache> = self
+            .rules
+            .iter()
+            .map(|rule| rule.dfa.create_cache())
+            .collect();
+        let mut tracker = LineTracker::new(input);
+        let mut tokens = Vec::new();
+        let mut cursor = Cursor {
+            source: input,
+            start: 0,
+            end: bom_len(input),
+        };
+
+        while cursor.end < input.len() {
+            cursor.start = cursor.end;
+            let (name, error) = match self.longest_match(&mut caches, input, cursor.start) {
+                Some((index, end)) => {
+                    let rule = &self.rules[index];
+                    cursor.end = end;
+                    let result = rule
+                        .callback
+                        .map_or(Ok(()), |callback| callback(&mut cursor));
+                    if rule.rule.skip && result.is_ok() {
+                        continue;
+                    }
+                    match result {
+                        Ok(()) => {
+                            let word = (index, cursor.slice().to_string());
+                            let name = self.keywords.get(&word).unwrap_or(&rule.rule.name);
+                            (name.clone(), None)
+                        }
+                        Err(error) => ("ERR".to_string(), Some(error)),
+                    }
+                }
+                None => {
+                    let c = input[cursor.start..].chars().next().unwrap_or_default();
+                    cursor.end += c.len_utf8();
+                    ("ERR".to_string(), Some(LexError::default()))
+                }
+            };
+
+            let span = tracker.span(cursor.start..cursor.end);
+            tokens.push(DynamicToken {
+                name,
+                lexeme: cursor.slice().to_string(),
+                span,
+                error: error.map(|error| error.with_span(span)),
+            });
+        }
+
+        tokens
+    }
+
+    /// Scans `input` into the `.tok` format of [`crate::run_scanner`]: one line
+    /// of `lexeme/NAME` words per source line, without newlines and trivia.
+    pub fn run_scanner(&self, input: &str) -> Vec<String> {
+        let words = self.tokenize(input).into_iter().filter_map(|token| {
+            let label = match self.rule(&token.name) {
+                Some(rule) if rule.trivia || rule.name == "NEWLINE" => return None,
+                Some(rule) => rule.label_text(),
+                None => "ERR",
+            };
+            Some((token.span.line, format!("{}/{}", token.lexeme, label)))
+        });
+
+        crate::tok_lines(input, words)
+    }
+
+    // The rule and end offset of the longest non-empty match at `start`
+    fn longest_match(
+        &self,
+        caches: &mut [Cache],
+        input: &str,
+        start: usize,
+    ) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+
+        for (index, (rule, cache)) in self.rules.iter().zip(caches.iter_mut()).enumerate() {
+            let search = Input::new(input).range(start..).anchored(Anchored::Yes);
+            let Ok(Some(found)) = rule.dfa.try_search_fwd(cache, &search) else {
+                continue;
+            };
+            let end = found.offset();
+            if end == start {
+                continue;
+            }
+            let better = match best {
+                None => true,
+                Some((best_index, best_end)) => {
+                    end > best_end
+                        || (end == best_end && rule.priority > self.rules[best_index].priority)
+                }
+            };
+            if better {
+                best = Some((index, end));
+            }
+        }
+
+        best
+    }
+}
+
+// The current token of a scan, handed to callbacks
+struct Cursor<'s> {
+    source: &'s str,
+    start: usize,
+    end: usize,
+}
+
+impl<'s> LexerState<'s> for Cursor<'s> {
+    fn slice(&self) -> &'s str {
+        &self.source[self.start..self.end]
+    }
+
+    fn remainder(&self) -> &'s str {
+        &self.source[self.end..]
+    }
+
+    fn bump(&mut self, n: usize) {
+        self.end += n;
+    }
+}
+
+// Callbacks of the scanner a `.lex` file may name, with or without the crate prefix
+fn callback(path: &str) -> Option<Callback> {
+    let name = path
+        .strip_prefix("crate::")
+        .or_else(|| path.strip_prefix("scanner::"))
+        .unwrap_or(path);
+
+    match name {
+        "callbacks::integer" => Some(|lex| callbacks::integer(lex).map(drop)),
+        "callbacks::real" => Some(|lex| callbacks::real(lex).map(drop)),
+        "callbacks::identifier" => Some(|lex| {
+            callbacks::identifier(lex);
+            Ok(())
+        }),
+        "callbacks::string" => Some(|lex| callbacks::string(lex).map(drop)),
+        "callbacks::block_comment" => Some(|lex| callbacks::block_comment(lex)),
+        _ => None,
+    }
+}
@@ -0,0 +1,71 @@
+use crate::Span;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Why a piece of the input could not be turned into a token.
+///
+/// Callbacks create these with a default span; [`crate::spanned`] fills in
+/// the span of the offending text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LexError {
+    /// No token starts with this character.
+    UnexpectedCharacter(Span),
+    /// A number literal without digits, such as `0x_`.
+    MalformedNumber(Span),
+    /// A number literal too large for its type.
+    NumberOutOfRange(Span),
+    /// A string literal with an unknown `\` escape.
+    InvalidEscape(Span),
+    /// A string literal missing its closing quote on the same line.
+    UnterminatedString(Span),
+    /// A block comment missing its closing `*/`.
+    UnterminatedComment(Span),
+}
+
+impl Default for LexError {
+    fn default() -> Self {
+        LexError::UnexpectedCharacter(Span::default())
+    }
+}
+
+impl LexError {
+    pub fn span(&self) -> Span {
+        match self {
+            LexError::UnexpectedCharacter(span)
+            | LexError::MalformedNumber(span)
+            | LexError::NumberOutOfRange(span)
+            | LexError::InvalidEscape(span)
+            | LexError::UnterminatedString(span)
+            | LexError::UnterminatedComment(span) => *span,
+        }
+    }
+
+    pub fn with_span(mut self, new_span: Span) -> Self {
+        match &mut self {
+            LexError::UnexpectedCharacter(span)
+            | LexError::MalformedNumber(span)
+            | LexError::NumberOutOfRange(span)
+            | LexError::InvalidEscape(span)
+            | LexError::UnterminatedString(span)
+            | LexError::UnterminatedComment(span) => *span = new_span,
+        }
+        self
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            LexError::UnexpectedCharacter(_) => "Unexpected character",
+            LexError::MalformedNumber(_) => "Malformed number",
+            LexError::NumberOutOfRange(_) => "Number out of range",
+            LexError::InvalidEscape(_) => "Invalid escape sequence",
+            LexError::UnterminatedString(_) => "Unterminated string",
+            LexError::UnterminatedComment(_) => "Unterminated comment",
+        };
+        let span = self.span();
+        write!(f, "{} at line {}, pos {}", message, span.line, span.column)
+    }
+}
+
+impl std::error::Error for LexError {}
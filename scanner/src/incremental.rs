@@ -0,0 +1,199 @@
+//! Re-lexing after an edit, for editors that would otherwise rescan the whole
+//! document on every keystroke.
+//!
+//! A [`TokenBuffer`] keeps the source, its tokens and how far the lexer looked
+//! ahead to read each of them. An edit is re-lexed from the first token that
+//! looked at the edited text, and re-lexing stops as soon as a token ends
+//! where an old token ended after the edit: from there the lexer would read
+//! the same tokens as before, so they are only moved.
+//!
+//! The native backend is used since it knows its lookahead, and the result is
+//! always the same as scanning the new source with it from scratch.
+
+use crate::grammar::{native, Token};
+use crate::{bom_len, LineTracker, SpannedToken};
+use std::ops::Range;
+
+/// A source text and its tokens, kept up to date through edits.
+#[derive(Debug, Clone)]
+pub struct TokenBuffer {
+    source: String,
+    tokens: Vec<SpannedToken>,
+    // For each token, the offset just past the text the lexer looked at
+    lookahead: Vec<usize>,
+}
+
+/// The tokens an edit replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenChange {
+    /// Indices of the replaced tokens in the buffer before the edit.
+    pub old: Range<usize>,
+    /// Indices of the tokens replacing them in the buffer after the edit.
+    pub new: Range<usize>,
+}
+
+impl TokenBuffer {
+    pub fn new(source: impl Into<String>) -> Self {
+        let mut buffer = TokenBuffer {
+            source: source.into(),
+            tokens: Vec::new(),
+            lookahead: Vec::new(),
+        };
+        let (tokens, lookahead, _) = buffer.lex(0, (1, 1), None);
+        buffer.tokens = tokens;
+        buffer.lookahead = lookahead;
+        buffer
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn tokens(&self) -> &[SpannedToken] {
+        &self.tokens
+    }
+
+    pub fn into_tokens(self) -> Vec<SpannedToken> {
+        self.tokens
+    }
+
+    /// Replaces the bytes in `range` of the source with `text` and re-lexes
+    /// the tokens the edit can change.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is out of bounds or does not lie on character boundaries.
+    pub fn edit(&mut self, range: Range<usize>, text: &str) -> TokenChange {
+        let old_len = self.source.len();
+        self.source.replace_range(range.clone(), text);
+
+        // The first token whose reading looked at the edited text, or reached
+        // the end of the source an insertion there would extend
+        let first = self
+            .lookahead
+            .iter()
+            .position(|&seen| seen > range.start || seen >= old_len)
+            .unwrap_or(self.tokens.len());
+        let (start, position) = match first.checked_sub(1) {
+            Some(previous) => {
+                let span = self.tokens[previous].span;
+                (span.end, (span.end_line, span.end_column))
+            }
+            None => (0, (1, 1)),
+        };
+
+        let delta = text.len() as isize - range.len() as isize;
+        let edit_end = range.start + text.len();
+        let (tokens, lookahead, resume) = self.lex(start, position, Some((first, edit_end, delta)));
+
+        // The tokens after the re-lexed ones are the same, only moved
+        let rest = match resume {
+            Some(rest) => {
+                let old = self.tokens[rest - 1].span;
+                let new = tokens.last().expect("lexing stops after a token").span;
+                let moved = Move {
+                    bytes: delta,
+                    lines: new.end_line as isize - old.end_line as isize,
+                    columns: new.end_column as isize - old.end_column as isize,
+                    line: old.end_line,
+                };
+                for token in &mut self.tokens[rest..] {
+                    moved.apply(token);
+                }
+                for seen in &mut self.lookahead[rest..] {
+                    *seen = seen.saturating_add_signed(delta);
+                }
+                rest
+            }
+            None => self.tokens.len(),
+        };
+
+        let count = tokens.len();
+        self.tokens.splice(first..rest, tokens);
+        self.lookahead.splice(first..rest, lookahead);
+
+        TokenChange {
+            old: first..rest,
+            new: first..first + count,
+        }
+    }
+
+    // Lexes the source from `start`, a token boundary at line and column
+    // `position`. After an edit, `resume` holds the index of the first old
+    // token that may have changed, where the edited text now ends and how
+    // far the text after it moved. Lexing stops once a token ends where an
+    // old token ended after the edit, and the index of the old token after
+    // it is returned.
+    fn lex(
+        &self,
+        start: usize,
+        (line, column): (usize, usize),
+        resume: Option<(usize, usize, isize)>,
+    ) -> (Vec<SpannedToken>, Vec<usize>, Option<usize>) {
+        let start = if start == 0 {
+            bom_len(&self.source)
+        } else {
+            start
+        };
+        let mut lexer = native::Lexer::new(&self.source[start..]);
+        let mut tracker = LineTracker::resume(&self.source, start, line, column);
+        let mut tokens = Vec::new();
+        let mut lookahead = Vec::new();
+
+        while let Some(token) = lexer.next() {
+            let range = lexer.span();
+            let range = start + range.start..start + range.end;
+            let span = tracker.span(range.clone());
+            tokens.push(SpannedToken {
+                token: token.unwrap_or_else(|error| Token::ERR(error.with_span(span))),
+                lexeme: self.source[range.clone()].to_string(),
+                span,
+            });
+            lookahead.push(start + lexer.lookahead());
+
+            if let Some((first, edit_end, delta)) = resume {
+                if range.end >= edit_end {
+                    let old_end = (range.end as isize - delta) as usize;
+                    let old = &self.tokens[first..];
+                    if let Ok(index) = old.binary_search_by_key(&old_end, |token| token.span.end) {
+                        return (tokens, lookahead, Some(first + index + 1));
+                    }
+                }
+            }
+        }
+
+        (tokens, lookahead, None)
+    }
+}
+
+// How the tokens after an edit moved: by `bytes`, by `lines`, and by
+// `columns` for positions on the old `line` where re-lexing stopped
+struct Move {
+    bytes: isize,
+    lines: isize,
+    columns: isize,
+    line: usize,
+}
+
+impl Move {
+    fn apply(&self, token: &mut SpannedToken) {
+        let span = &mut token.span;
+        span.start = span.start.saturating_add_signed(self.bytes);
+        span.end = span.end.saturating_add_signed(self.bytes);
+        (span.line, span.column) = self.position(span.line, span.column);
+        (span.end_line, span.end_column) = self.position(span.end_line, span.end_column);
+
+        if let Token::ERR(error) = &mut token.token {
+            *error = error.clone().with_span(*span);
+        }
+    }
+
+    fn position(&self, line: usize, column: usize) -> (usize, usize) {
+        let column = if line == self.line {
+            column.saturating_add_signed(self.columns)
+        } else {
+            column
+        };
+        (line.saturating_add_signed(self.lines), column)
+    }
+}
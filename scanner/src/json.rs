@@ -0,0 +1,67 @@
+//! The token stream as JSON Lines, one token object per line.
+//!
+//! Unlike the `lexeme/NAME` `.tok` format this keeps every token, including
+//! newlines, comments and errors, with its payload and span, so a file written
+//! by the scanner can be read back and handed straight to the parser:
+//!
+//! ```text
+//! {"kind":"VAR","value":"x","lexeme":"x","span":{"start":0,"end":1,...}}
+//! {"kind":"ASSIGN","lexeme":"=","span":{...}}
+//! ```
+
+use crate::{tokenize_spanned_with, Backend, SpannedToken};
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// A line of a token file that isn't a valid token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonlError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for JsonlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for JsonlError {}
+
+/// Writes each token as one line of JSON.
+pub fn to_jsonl<T: Serialize>(tokens: &[T]) -> Vec<String> {
+    tokens
+        .iter()
+        .map(|token| serde_json::to_string(token).expect("tokens serialize to JSON"))
+        .collect()
+}
+
+/// Scans `input` into JSON Lines, the machine-readable counterpart of
+/// [`crate::run_scanner_with`].
+pub fn run_scanner_jsonl(input: &str, backend: Backend) -> Vec<String> {
+    to_jsonl(&tokenize_spanned_with(input, backend))
+}
+
+/// Reads tokens written by [`to_jsonl`]. Blank lines are ignored.
+pub fn from_jsonl(text: &str) -> Result<Vec<SpannedToken>, JsonlError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|err| JsonlError {
+                line: index + 1,
+                message: err.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Reads a token file written by the scanner's `--format jsonl`.
+pub fn read_jsonl<P: AsRef<Path>>(path: P) -> Result<Vec<SpannedToken>, String> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+    from_jsonl(&text).map_err(|err| format!("{}:{}", path.display(), err))
+}
@@ -1,78 +1,276 @@
 use grammar::Token;
 use logos::Logos;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::str::FromStr;
 
+mod callbacks;
+pub mod dynamic;
+pub mod error;
 pub mod grammar;
+pub mod incremental;
+pub mod json;
+pub mod stream;
+
+pub use error::LexError;
+
+/// Which generated lexer turns the source into tokens.
+///
+/// Both are built from `hiwkhao.lex` and produce the same tokens: `Logos`
+/// uses the logos derive, `Native` runs the preprocessor's own minimized DFA
+/// tables (`grammar::native`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    #[default]
+    Logos,
+    Native,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "logos" => Ok(Backend::Logos),
+            "native" => Ok(Backend::Native),
+            _ => Err(format!(
+                "Unknown backend `{}`, expected `logos` or `native`",
+                name
+            )),
+        }
+    }
+}
+
+/// Location of a token in the source text.
+///
+/// `start` and `end` are byte offsets, lines and columns start at 1 and the
+/// end position points just past the last character of the token. Columns
+/// count characters, not bytes, and a leading byte order mark is not counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// A token together with the text it was read from and where it was found.
+///
+/// Serialized as one flat object: the token's `kind` and `value`, then the
+/// `lexeme` and `span`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpannedToken {
+    #[serde(flatten)]
+    pub token: Token,
+    pub lexeme: String,
+    pub span: Span,
+}
+
+impl SpannedToken {
+    /// Length of the token in characters, as it is written in the source.
+    pub fn token_length(&self) -> usize {
+        self.lexeme.chars().count()
+    }
+}
+
+// Tracks the line and character column of a byte offset while walking forward through the source
+pub(crate) struct LineTracker<'a> {
+    source: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> LineTracker<'a> {
+    // Starts after the byte order mark, if the source has one
+    pub(crate) fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            offset: bom_len(source),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    // Starts at `offset`, which is known to be at `line` and `column`
+    pub(crate) fn resume(source: &'a str, offset: usize, line: usize, column: usize) -> Self {
+        Self {
+            source,
+            offset,
+            line,
+            column,
+        }
+    }
+
+    fn advance_to(&mut self, offset: usize) -> (usize, usize) {
+        for c in self.source[self.offset..offset].chars() {
+            match c {
+                '\n' => {
+                    self.line += 1;
+                    self.column = 1;
+                }
+                _ => self.column += 1,
+            }
+        }
+        self.offset = offset;
+        (self.line, self.column)
+    }
+
+    // Ranges must be passed in source order
+    pub(crate) fn span(&mut self, range: Range<usize>) -> Span {
+        let (line, column) = self.advance_to(range.start);
+        let (end_line, end_column) = self.advance_to(range.end);
+        Span {
+            start: range.start,
+            end: range.end,
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+/// Length in bytes of the byte order mark at the start of `source`, or 0.
+///
+/// A byte order mark is only passed over at the start of the source; anywhere
+/// else no rule matches it and it is rejected.
+pub(crate) fn bom_len(source: &str) -> usize {
+    if source.starts_with('\u{FEFF}') {
+        '\u{FEFF}'.len_utf8()
+    } else {
+        0
+    }
+}
 
 pub fn tokenize(input: &str) -> logos::Lexer<'_, Token> {
     println!("DEBUG [Scanner]: Starting tokenization of input: {}", input);
-    let lexer = grammar::Token::lexer(input);
+    let mut lexer = grammar::Token::lexer(input);
+    lexer.bump(bom_len(input));
     lexer
 }
 
+/// Collects the tokens of a lexer together with their positions in its source.
+///
+/// Text the lexer rejects becomes a [`Token::ERR`] holding a [`LexError`] with
+/// its span, and scanning continues after it.
+pub fn spanned(lexer: logos::Lexer<'_, Token>) -> Vec<SpannedToken> {
+    spanned_iter(lexer).collect()
+}
+
+/// Like [`spanned`], yielding each token as it is read instead of collecting
+/// them all.
+pub fn spanned_iter(mut lexer: logos::Lexer<'_, Token>) -> impl Iterator<Item = SpannedToken> + '_ {
+    let source = lexer.source();
+    if lexer.span().end == 0 {
+        lexer.bump(bom_len(source));
+    }
+    with_spans(
+        source,
+        std::iter::from_fn(move || Some((lexer.next()?, lexer.span()))),
+    )
+}
+
+/// Like [`spanned`], for the native backend's lexer.
+pub fn spanned_native(mut lexer: grammar::native::Lexer<'_>) -> Vec<SpannedToken> {
+    let source = lexer.source();
+    if lexer.span().end == 0 {
+        lexer.bump(bom_len(source));
+    }
+    with_spans(
+        source,
+        std::iter::from_fn(|| Some((lexer.next()?, lexer.span()))),
+    )
+    .collect()
+}
+
+fn with_spans<'s>(
+    source: &'s str,
+    tokens: impl Iterator<Item = (Result<Token, LexError>, Range<usize>)> + 's,
+) -> impl Iterator<Item = SpannedToken> + 's {
+    let mut tracker = LineTracker::new(source);
+
+    tokens.map(move |(token, range)| {
+        let span = tracker.span(range.clone());
+        SpannedToken {
+            token: token.unwrap_or_else(|error| Token::ERR(error.with_span(span))),
+            lexeme: source[range].to_string(),
+            span,
+        }
+    })
+}
+
+pub fn tokenize_spanned(input: &str) -> Vec<SpannedToken> {
+    tokenize_spanned_with(input, Backend::Logos)
+}
+
+pub fn tokenize_spanned_with(input: &str, backend: Backend) -> Vec<SpannedToken> {
+    match backend {
+        Backend::Logos => spanned(Token::lexer(input)),
+        Backend::Native => spanned_native(grammar::native::Lexer::new(input)),
+    }
+}
+
 pub fn tokenize_vector(input: &str) -> Vec<(String, grammar::Token)> {
+    tokenize_vector_with(input, Backend::Logos)
+}
+
+pub fn tokenize_vector_with(input: &str, backend: Backend) -> Vec<(String, grammar::Token)> {
     println!("DEBUG [Scanner]: Starting vector tokenization");
-    let mut lexer = grammar::Token::lexer(input);
     let mut tokens = Vec::new();
 
-    while let Some(token) = lexer.next() {
-        let slice = lexer.slice().to_string();
+    for SpannedToken { token, lexeme, .. } in tokenize_spanned_with(input, backend) {
         match token {
-            Ok(tok) => {
-                println!("DEBUG [Scanner]: Generated token: {:?} from slice: {}", tok, slice);
-                tokens.push((slice, tok))
-            },
-            Err(_) => {
-                println!("DEBUG [Scanner]: Error generating token for slice: {}", slice);
-                tokens.push((slice, grammar::Token::ERR))
-            },
+            Token::ERR(ref error) => {
+                println!("DEBUG [Scanner]: {} for slice: {}", error, lexeme);
+            }
+            _ => {
+                println!(
+                    "DEBUG [Scanner]: Generated token: {:?} from slice: {}",
+                    token, lexeme
+                );
+            }
         }
+        tokens.push((lexeme, token));
     }
 
-    println!("DEBUG [Scanner]: Completed tokenization, total tokens: {}", tokens.len());
+    println!(
+        "DEBUG [Scanner]: Completed tokenization, total tokens: {}",
+        tokens.len()
+    );
     tokens
 }
 
 pub fn run_scanner(input: &str) -> Vec<String> {
-    let lines: Vec<&str> = input.lines().collect();
-    let mut final_output: Vec<String> = vec![];
-
-    for line in lines {
-        let tokens = tokenize_vector(line);
-        let formatted_output: Vec<String> = tokens
-            .into_iter()
-            .map(|(word, token)| {
-                let token_name = match token {
-                    grammar::Token::REAL(_) => "REAL",
-                    grammar::Token::INT(_) => "INT",
-                    grammar::Token::VAR(_) => "VAR",
-                    grammar::Token::ADD => "+",
-                    grammar::Token::SUB => "-",
-                    grammar::Token::MUL => "*",
-                    grammar::Token::DIV => "/",
-                    grammar::Token::INTDIV => "//",
-                    grammar::Token::POW => "POW",
-                    grammar::Token::LPAREN => "LPAREN",
-                    grammar::Token::RPAREN => "RPAREN",
-                    grammar::Token::LBRACKET => "LBRACKET",
-                    grammar::Token::RBRACKET => "RBRACKET",
-                    grammar::Token::EQ => "==",
-                    grammar::Token::NE => "!=",
-                    grammar::Token::LT => "<",
-                    grammar::Token::GT => ">",
-                    grammar::Token::LE => "<=",
-                    grammar::Token::GE => ">=",
-                    grammar::Token::ASSIGN => "=",
-                    grammar::Token::LIST => "list",
-                    grammar::Token::ERR => "ERR",
-                    _ => "UNKNOWN",
-                };
-
-                format!("{}/{}", word, token_name)
-            })
-            .collect();
-
-        final_output.push(formatted_output.join(" "));
+    run_scanner_with(input, Backend::Logos)
+}
+
+pub fn run_scanner_with(input: &str, backend: Backend) -> Vec<String> {
+    let words = tokenize_spanned_with(input, backend)
+        .into_iter()
+        .filter(|spanned| spanned.token != Token::NEWLINE && !spanned.token.is_trivia())
+        .map(|spanned| {
+            let word = format!("{}/{}", spanned.lexeme, spanned.token.label());
+            (spanned.span.line, word)
+        });
+
+    tok_lines(input, words)
+}
+
+// Joins `lexeme/NAME` words into one output line per source line
+pub(crate) fn tok_lines(input: &str, words: impl Iterator<Item = (usize, String)>) -> Vec<String> {
+    let mut final_output: Vec<Vec<String>> = vec![Vec::new(); input.lines().count()];
+
+    for (line, word) in words {
+        if let Some(words) = final_output.get_mut(line - 1) {
+            words.push(word);
+        }
     }
 
     final_output
+        .into_iter()
+        .map(|words| words.join(" "))
+        .collect()
 }
@@ -1,23 +1,99 @@
-use scanner;
-
-mod grammar;
+use scanner::dynamic::DynamicLexer;
+use scanner::stream;
+use scanner::{json, Backend};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 
 const DEFAULT_OUTPUT_FILE: &str = "hiwkhao.tok";
+const DEFAULT_JSONL_OUTPUT_FILE: &str = "hiwkhao.jsonl";
+
+// Removes `--name value` from the arguments and returns the value
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let index = args.iter().position(|arg| arg == name)?;
+    args.remove(index);
+    if index >= args.len() {
+        eprintln!("{} needs a value", name);
+        std::process::exit(1);
+    }
+    Some(args.remove(index))
+}
 
 fn main() {
-    let input = if let Some(file_path) = std::env::args().nth(1) {
-        std::fs::read_to_string(file_path).unwrap()
-    } else {
+    // `--backend logos|native`, `--lex file` and `--format tok|jsonl` may
+    // appear anywhere; the rest are positional
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let backend: Backend = take_option(&mut args, "--backend").map_or(Backend::default(), |name| {
+        name.parse().unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        })
+    });
+    let lex_file = take_option(&mut args, "--lex");
+    let jsonl = match take_option(&mut args, "--format").as_deref() {
+        None | Some("tok") => false,
+        Some("jsonl") => true,
+        Some(format) => {
+            eprintln!("Unknown format `{}`, expected `tok` or `jsonl`", format);
+            std::process::exit(1);
+        }
+    };
+
+    let Some(file_path) = args.first() else {
         eprintln!("No input file provided.");
         std::process::exit(1);
     };
+    let default_output = if jsonl {
+        DEFAULT_JSONL_OUTPUT_FILE
+    } else {
+        DEFAULT_OUTPUT_FILE
+    };
+    let output_file = args.get(1).cloned().unwrap_or(default_output.to_string());
 
-    let result = scanner::run_scanner(&input);
-    println!("{}", result.join("\n"));
+    // `.tok` output from the compiled-in lexer is written as the input is
+    // read
+    if !jsonl && lex_file.is_none() {
+        let output = File::create(output_file).unwrap();
+        let mut out = Tee(io::stdout().lock(), BufWriter::new(output));
+        let input = File::open(file_path).unwrap();
+        let result = stream::stream_scanner_with(input, backend, &mut out).map(drop);
+        result.and_then(|()| out.0.write_all(b"\n")).unwrap();
+        return;
+    }
+
+    let input = std::fs::read_to_string(file_path).unwrap();
 
-    let output_file = std::env::args()
-        .nth(2)
-        .unwrap_or(DEFAULT_OUTPUT_FILE.to_string());
+    // A `.lex` file given at runtime replaces the compiled-in lexer
+    let result = match lex_file {
+        Some(lex_file) => {
+            let lexer = DynamicLexer::from_file(&lex_file).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            });
+            if jsonl {
+                json::to_jsonl(&lexer.tokenize(&input))
+            } else {
+                lexer.run_scanner(&input)
+            }
+        }
+        None => json::run_scanner_jsonl(&input, backend),
+    };
+    println!("{}", result.join("\n"));
 
     std::fs::write(output_file, result.join("\n")).unwrap();
 }
+
+// Writes everything to both writers
+struct Tee<A, B>(A, B);
+
+impl<A: Write, B: Write> Write for Tee<A, B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_all(buf)?;
+        self.1.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()?;
+        self.1.flush()
+    }
+}
@@ -0,0 +1,244 @@
+//! Scanning input that does not fit in memory.
+//!
+//! [`TokenReader`] reads from any [`io::Read`] in chunks and yields the same
+//! tokens as scanning the whole input with either backend. A token is only
+//! yielded once the native lexer has read all the text that decides it, so a
+//! token crossing a chunk boundary is read again with more text. Logos
+//! doesn't tell how far it looked, so with [`Backend::Logos`] the native
+//! lexer still decides when a token is complete and logos then reads it.
+//! [`TokWriter`] writes the `.tok` format a token at a time.
+
+use crate::grammar::{native, Token};
+use crate::{bom_len, Backend, LineTracker, SpannedToken};
+use logos::Logos;
+use std::io::{self, Read, Write};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Iterator over the tokens of a reader.
+///
+/// Only the text of the token being read is kept, so an unterminated block
+/// comment holds the rest of the input in memory. While a token is still
+/// undecided, each read asks for at least as much text as is held, so a long
+/// token is scanned a bounded number of times.
+pub struct TokenReader<R> {
+    reader: R,
+    chunk_size: usize,
+    backend: Backend,
+    // Text read but not yet scanned starts at `consumed`
+    text: String,
+    consumed: usize,
+    // Bytes of a character split by the end of the last chunk
+    partial: Vec<u8>,
+    eof: bool,
+    // Byte offset, line and column of `consumed` in the input
+    offset: usize,
+    line: usize,
+    column: usize,
+    newlines: usize,
+    last: Option<char>,
+}
+
+impl<R: Read> TokenReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_chunk_size(reader, CHUNK_SIZE)
+    }
+
+    pub fn with_chunk_size(reader: R, chunk_size: usize) -> Self {
+        TokenReader {
+            reader,
+            chunk_size: chunk_size.max(1),
+            backend: Backend::Native,
+            text: String::new(),
+            consumed: 0,
+            partial: Vec::new(),
+            eof: false,
+            offset: 0,
+            line: 1,
+            column: 1,
+            newlines: 0,
+            last: None,
+        }
+    }
+
+    /// Reads the tokens with `backend` instead of the native lexer.
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Lines of input read so far, counted like [`str::lines`].
+    pub fn lines(&self) -> usize {
+        self.newlines + usize::from(self.last.is_some_and(|c| c != '\n'))
+    }
+
+    // Appends the next chunk to the unscanned text, dropping what was scanned
+    fn fill(&mut self) -> io::Result<()> {
+        self.text.drain(..self.consumed);
+        self.consumed = 0;
+
+        let mut bytes = std::mem::take(&mut self.partial);
+        let start = bytes.len();
+        bytes.resize(start + self.chunk_size.max(self.text.len()), 0);
+        let read = loop {
+            match self.reader.read(&mut bytes[start..]) {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                result => break result?,
+            }
+        };
+        bytes.truncate(start + read);
+        self.eof = read == 0;
+
+        let valid = match std::str::from_utf8(&bytes) {
+            Ok(text) => text,
+            // A character cut off by the chunk is finished by the next one
+            Err(err) if err.error_len().is_none() && !self.eof => {
+                let (valid, partial) = bytes.split_at(err.valid_up_to());
+                self.partial = partial.to_vec();
+                std::str::from_utf8(valid).expect("valid up to here")
+            }
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        };
+
+        self.newlines += valid.bytes().filter(|&b| b == b'\n').count();
+        self.last = valid.chars().next_back().or(self.last);
+        self.text.push_str(valid);
+        Ok(())
+    }
+}
+
+impl<R> TokenReader<R> {
+    // Moves past the first `len` bytes of the unscanned text
+    fn advance(&mut self, len: usize) {
+        let rest = &self.text[self.consumed..];
+        let mut tracker = LineTracker::resume(rest, 0, self.line, self.column);
+        let span = tracker.span(0..len);
+        (self.line, self.column) = (span.end_line, span.end_column);
+        self.consumed += len;
+        self.offset += len;
+    }
+}
+
+impl<R: Read> Iterator for TokenReader<R> {
+    type Item = io::Result<SpannedToken>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // A byte order mark is passed over at the start of the input only
+            if self.offset == 0 {
+                let bom = bom_len(&self.text[self.consumed..]);
+                self.consumed += bom;
+                self.offset += bom;
+            }
+
+            let rest = &self.text[self.consumed..];
+            let mut lexer = native::Lexer::new(rest);
+            let token = lexer.next();
+
+            // Until the input ends, a token whose lexer looked at the end of
+            // the text read so far may still grow. The skipped text before it
+            // is decided, so scanning resumes after that
+            if !self.eof && (token.is_none() || lexer.lookahead() >= rest.len()) {
+                if token.is_some() {
+                    self.advance(lexer.span().start);
+                }
+                if let Err(err) = self.fill() {
+                    return Some(Err(err));
+                }
+                continue;
+            }
+
+            let (token, range) = match self.backend {
+                Backend::Native => (token, lexer.span()),
+                Backend::Logos => {
+                    let mut lexer = Token::lexer(rest);
+                    (lexer.next(), lexer.span())
+                }
+            };
+            let mut tracker = LineTracker::resume(rest, 0, self.line, self.column);
+            let mut span = tracker.span(range.clone());
+            let lexeme = rest[range.clone()].to_string();
+            span.start += self.offset;
+            span.end += self.offset;
+            self.advance(range.end);
+
+            return token.map(|token| {
+                Ok(SpannedToken {
+                    token: token.unwrap_or_else(|error| Token::ERR(error.with_span(span))),
+                    lexeme,
+                    span,
+                })
+            });
+        }
+    }
+}
+
+/// Writes the `.tok` format of [`crate::run_scanner`] a token at a time.
+///
+/// Tokens must be written in source order. Newlines and trivia are left out.
+pub struct TokWriter<W: Write> {
+    out: W,
+    line: usize,
+    empty: bool,
+}
+
+impl<W: Write> TokWriter<W> {
+    pub fn new(out: W) -> Self {
+        TokWriter {
+            out,
+            line: 1,
+            empty: true,
+        }
+    }
+
+    pub fn write(&mut self, token: &SpannedToken) -> io::Result<()> {
+        if token.token == Token::NEWLINE || token.token.is_trivia() {
+            return Ok(());
+        }
+        if token.span.line > self.line {
+            self.end_lines(token.span.line)?;
+        } else if !self.empty {
+            self.out.write_all(b" ")?;
+        }
+        write!(self.out, "{}/{}", token.lexeme, token.token.label())?;
+        self.empty = false;
+        Ok(())
+    }
+
+    /// Ends the output after `lines` lines of input and returns the writer.
+    pub fn finish(mut self, lines: usize) -> io::Result<W> {
+        if lines > self.line {
+            self.end_lines(lines)?;
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    // Moves on to `line`, leaving the lines in between empty
+    fn end_lines(&mut self, line: usize) -> io::Result<()> {
+        for _ in self.line..line {
+            self.out.write_all(b"\n")?;
+        }
+        self.line = line;
+        self.empty = true;
+        Ok(())
+    }
+}
+
+/// Scans `input` a chunk at a time and writes its `.tok` output to `out`.
+///
+/// The output is the same as [`crate::run_scanner_with`] on the whole input
+/// with [`Backend::Native`], joined with newlines.
+pub fn stream_scanner<R: Read, W: Write>(input: R, out: W) -> io::Result<W> {
+    stream_scanner_with(input, Backend::Native, out)
+}
+
+/// Like [`stream_scanner`], reading the tokens with `backend`.
+pub fn stream_scanner_with<R: Read, W: Write>(input: R, backend: Backend, out: W) -> io::Result<W> {
+    let mut tokens = TokenReader::new(input).with_backend(backend);
+    let mut writer = TokWriter::new(out);
+    for token in &mut tokens {
+        writer.write(&token?)?;
+    }
+    writer.finish(tokens.lines())
+}
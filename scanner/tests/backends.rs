@@ -0,0 +1,107 @@
+use scanner::grammar::Token;
+use scanner::Backend;
+
+// The native DFA lexer has to produce exactly what the logos lexer does
+fn assert_same_tokens(input: &str) {
+    assert_eq!(
+        scanner::tokenize_spanned_with(input, Backend::Native),
+        scanner::tokenize_spanned_with(input, Backend::Logos),
+        "backends disagree on {:?}",
+        input
+    );
+}
+
+#[test]
+fn sample_lines() {
+    let input = include_str!("../../sample.txt");
+    assert_same_tokens(input);
+    for line in input.lines() {
+        assert_same_tokens(line);
+    }
+}
+
+#[test]
+fn operators() {
+    for input in [
+        "1+2-3*4/5//6^7",
+        "a==b != c<=d>=e<f>g",
+        "x = list[10]",
+        "(( ))[[ ]]",
+        "5 // 2 / 1",
+    ] {
+        assert_same_tokens(input);
+    }
+}
+
+#[test]
+fn numbers() {
+    for input in [
+        "0x1F 0b1010 0o17 1_000_000",
+        ".5 5. 2.5E-3 1e10 1e 1.e5",
+        "9223372036854775807 9223372036854775808",
+        "1e400 0x 0b2 12abc",
+        "-1 - 2.0",
+    ] {
+        assert_same_tokens(input);
+    }
+}
+
+#[test]
+fn strings_and_comments() {
+    for input in [
+        r#"x = "hello world""#,
+        r#""a\tb\n\"c\"\\" "\q""#,
+        "x = \"abc\ny = 1",
+        "x = 1 # comment\n# only a comment",
+        "/* a /* b */ c */ x /* unterminated",
+    ] {
+        assert_same_tokens(input);
+    }
+}
+
+#[test]
+fn unicode_and_whitespace() {
+    for input in [
+        "\u{FEFF}ผลรวม = 5",
+        "café = cafe\u{301}",
+        "x\r\ny\t=\u{A0}1\n\n",
+        "  \n  ",
+        "",
+    ] {
+        assert_same_tokens(input);
+    }
+}
+
+#[test]
+fn errors() {
+    for input in ["@ $ x", "x = 5 ? 3", "1 & 2 | 3", "!x", "€"] {
+        assert_same_tokens(input);
+    }
+}
+
+#[test]
+fn same_scanner_output() {
+    let input = include_str!("../../sample.txt");
+    assert_eq!(
+        scanner::run_scanner_with(input, Backend::Native),
+        scanner::run_scanner(input)
+    );
+}
+
+#[test]
+fn native_lexer() {
+    let mut lexer = scanner::grammar::native::Lexer::new("x = 0x10");
+    assert_eq!(lexer.next(), Some(Ok(Token::VAR("x".to_string()))));
+    assert_eq!(lexer.slice(), "x");
+    assert_eq!(lexer.next(), Some(Ok(Token::ASSIGN)));
+    assert_eq!(lexer.span(), 2..3);
+    assert_eq!(lexer.next(), Some(Ok(Token::INT(16))));
+    assert_eq!(lexer.next(), None);
+}
+
+#[test]
+fn backend_names() {
+    assert_eq!("logos".parse(), Ok(Backend::Logos));
+    assert_eq!("native".parse(), Ok(Backend::Native));
+    assert!("regex".parse::<Backend>().is_err());
+}
@@ -0,0 +1,46 @@
+use scanner::grammar::Token;
+use scanner::LexError;
+
+#[test]
+fn line_comment() {
+    let input = "x = 1 # the answer\ny // 2";
+    let expected_output = vec!["x/VAR =/= 1/INT", "y/VAR ///// 2/INT"];
+    let output = scanner::run_scanner(input);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn block_comment() {
+    let input = "x /* first\nsecond */ + 1";
+    let expected_output = vec!["x/VAR", "+/+ 1/INT"];
+    let output = scanner::run_scanner(input);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn nested_block_comment() {
+    let tokens = scanner::tokenize_spanned("/* a /* b */ c */ 5");
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[0].token, Token::BLOCK_COMMENT);
+    assert_eq!(tokens[0].lexeme, "/* a /* b */ c */");
+    assert_eq!(tokens[1].token, Token::INT(5));
+    assert_eq!(tokens[1].span.column, 19);
+}
+
+#[test]
+fn comments_are_trivia() {
+    let tokens = scanner::tokenize_spanned("# note\n/* doc */");
+    assert_eq!(tokens[0].token, Token::LINE_COMMENT);
+    assert_eq!(tokens[0].lexeme, "# note");
+    assert!(tokens[0].token.is_trivia());
+    assert!(tokens[2].token.is_trivia());
+    assert!(!tokens[1].token.is_trivia());
+}
+
+#[test]
+fn unterminated_block_comment() {
+    let tokens = scanner::tokenize_spanned("1 /* open /* */");
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[1].token, Token::ERR(LexError::UnterminatedComment(tokens[1].span)));
+    assert_eq!(tokens[1].lexeme, "/* open /* */");
+}
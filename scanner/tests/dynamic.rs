@@ -0,0 +1,102 @@
+use scanner::dynamic::DynamicLexer;
+use scanner::LexError;
+
+fn hiwkhao() -> DynamicLexer {
+    DynamicLexer::from_source(include_str!("../../hiwkhao.lex")).unwrap()
+}
+
+#[test]
+fn same_output_as_run_scanner() {
+    let lexer = hiwkhao();
+    let inputs = [
+        include_str!("../../sample.txt"),
+        "x = list[10]\nx[0] = 0x1F + 1_000 // 2.5E-3",
+        "y = \"a\\tb\" # comment\n/* a /* b */ c */ z",
+        "\u{FEFF}ผลรวม = 5\ncafe\u{301} != 1e5",
+        "x = @ 5\n\"unterminated\ny = 1e400 /* open",
+        "1e 1.e5 0x 12abc",
+    ];
+
+    for input in inputs {
+        assert_eq!(
+            lexer.run_scanner(input),
+            scanner::run_scanner(input),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn generic_tokens() {
+    let tokens = hiwkhao().tokenize("x = 10\n");
+    let names: Vec<&str> = tokens.iter().map(|token| token.name.as_str()).collect();
+    assert_eq!(names, vec!["VAR", "ASSIGN", "INT", "NEWLINE"]);
+    assert_eq!(tokens[2].lexeme, "10");
+    assert_eq!(tokens[2].span.column, 5);
+    assert_eq!(tokens[2].span.end, 6);
+}
+
+#[test]
+fn errors_keep_their_kind() {
+    let tokens = hiwkhao().tokenize("x = \"abc\ny = $");
+    assert_eq!(tokens[2].name, "ERR");
+    assert_eq!(
+        tokens[2].error,
+        Some(LexError::UnterminatedString(tokens[2].span))
+    );
+    let last = tokens.last().unwrap();
+    assert_eq!(last.lexeme, "$");
+    assert_eq!(last.error, Some(LexError::UnexpectedCharacter(last.span)));
+}
+
+#[test]
+fn longest_match_within_a_rule() {
+    // A backtracking engine would stop at the first alternative
+    let lexer = DynamicLexer::from_source("AB (a|ab)c?\nWS \\s+ @skip\n").unwrap();
+    let tokens = lexer.tokenize("abc a");
+    let lexemes: Vec<&str> = tokens.iter().map(|token| token.lexeme.as_str()).collect();
+    assert_eq!(lexemes, vec!["abc", "a"]);
+}
+
+#[test]
+fn grammar_variant() {
+    // A student variant with `**` for powers and `let` as a keyword
+    let lexer = DynamicLexer::from_source(
+        "LET let @label(\"let\")\nVAR [a-z]+ @priority(1)\nPOW \\*\\*\nMUL \\*\nINT [0-9]+\nWS \\x20+ @skip\n",
+    )
+    .unwrap();
+    assert_eq!(
+        lexer.run_scanner("let x = 2 ** 3 * y"),
+        vec!["let/let x/VAR =/ERR 2/INT **/POW 3/INT */MUL y/VAR"]
+    );
+}
+
+#[test]
+fn load_errors_have_lines() {
+    let err = DynamicLexer::from_source("INT [0-9]+\nBAD (\n")
+        .err()
+        .unwrap();
+    assert_eq!(err.line, 2);
+
+    let err = DynamicLexer::from_source("INT [0-9]+ @callback(crate::callbacks::missing)\n")
+        .err()
+        .unwrap();
+    assert_eq!(err.line, 1);
+    assert!(err.message.contains("unknown callback"));
+}
+
+#[test]
+fn keyword_table() {
+    let lexer = DynamicLexer::from_source(
+        "VAR [a-z]+ @payload(String) @callback(crate::callbacks::identifier)\nIF if @keyword(VAR)\nWS \\x20+ @skip\n",
+    )
+    .unwrap();
+    let names: Vec<String> = lexer
+        .tokenize("if iffy x")
+        .into_iter()
+        .map(|token| token.name)
+        .collect();
+    assert_eq!(names, vec!["IF", "VAR", "VAR"]);
+    assert!(lexer.rule("IF").is_some());
+}
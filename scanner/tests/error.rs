@@ -1,9 +1,47 @@
 use scanner;
+use scanner::grammar::Token;
+use scanner::LexError;
 
 #[test]
 fn special_symbols() {
-    let input = "! @ # $ % & |";
-    let expected_output = vec!["!/ERR @/ERR #/ERR $/ERR %/ERR &/ERR |/ERR"];
+    let input = "! @ $ % & |";
+    let expected_output = vec!["!/ERR @/ERR $/ERR %/ERR &/ERR |/ERR"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
+
+#[test]
+fn scanning_continues_after_error() {
+    let input = "x = 1 $ 2";
+    let expected_output = vec!["x/VAR =/= 1/INT $/ERR 2/INT"];
+    let output = scanner::run_scanner(input);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn typed_errors() {
+    let tokens = scanner::tokenize_spanned("0x_ 99999999999999999999 `");
+    let errors: Vec<String> = tokens.iter().map(|t| match &t.token {
+        Token::ERR(error) => error.to_string(),
+        other => panic!("expected an error, found {:?}", other),
+    }).collect();
+    assert_eq!(
+        errors,
+        vec![
+            "Malformed number at line 1, pos 1",
+            "Number out of range at line 1, pos 5",
+            "Unexpected character at line 1, pos 26",
+        ]
+    );
+}
+
+#[test]
+fn error_span() {
+    let tokens = scanner::tokenize_spanned("\n  @");
+    assert_eq!(
+        tokens[0].token,
+        Token::ERR(LexError::UnexpectedCharacter(tokens[0].span))
+    );
+    assert_eq!(tokens[0].span.line, 2);
+    assert_eq!(tokens[0].span.column, 3);
+}
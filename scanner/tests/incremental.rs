@@ -0,0 +1,91 @@
+use proptest::prelude::*;
+use scanner::incremental::TokenBuffer;
+use scanner::{Backend, SpannedToken};
+
+fn full_scan(source: &str) -> Vec<SpannedToken> {
+    scanner::tokenize_spanned_with(source, Backend::Native)
+}
+
+// Byte offset of the `index`th character, or the end
+fn offset(source: &str, index: usize) -> usize {
+    source
+        .char_indices()
+        .nth(index)
+        .map_or(source.len(), |(offset, _)| offset)
+}
+
+fn apply(buffer: &mut TokenBuffer, start: usize, len: usize, text: &str) {
+    let source = buffer.source();
+    let count = source.chars().count();
+    let start = start % (count + 1);
+    let end = offset(source, start + len % (count - start + 1));
+    let start = offset(source, start);
+
+    let before = buffer.tokens().to_vec();
+    let change = buffer.edit(start..end, text);
+    assert_eq!(buffer.tokens(), full_scan(buffer.source()));
+    assert_eq!(
+        before.len() - change.old.len(),
+        buffer.tokens().len() - change.new.len()
+    );
+}
+
+#[test]
+fn typing_a_line() {
+    let mut buffer = TokenBuffer::new("x = 1\ny = 2\n");
+    for (index, c) in "z = x // 3e+5".chars().enumerate() {
+        apply(&mut buffer, 12 + index, 0, &c.to_string());
+    }
+    assert_eq!(buffer.source(), "x = 1\ny = 2\nz = x // 3e+5");
+}
+
+#[test]
+fn only_the_edited_token_is_relexed() {
+    // `x=5` becomes `x=50`
+    let mut buffer = TokenBuffer::new(include_str!("../../sample.txt"));
+    let tokens = buffer.tokens().len();
+    let at = buffer.source().find("x=5").unwrap() + 3;
+    let change = buffer.edit(at..at, "0");
+    assert_eq!(change.old.len(), 1);
+    assert_eq!(change.new.len(), 1);
+    assert_eq!(buffer.tokens()[change.new.start].lexeme, "50");
+    assert_eq!(buffer.tokens().len(), tokens);
+    assert_eq!(buffer.tokens(), full_scan(buffer.source()));
+}
+
+#[test]
+fn lookahead_reaches_back() {
+    // `1e+` is read as `1`, `e` and `+` until a digit makes it one real
+    let mut buffer = TokenBuffer::new("x = 1e+");
+    let change = buffer.edit(7..7, "5");
+    assert_eq!(change.old, 2..5);
+    assert_eq!(change.new, 2..3);
+    assert_eq!(buffer.tokens(), full_scan("x = 1e+5"));
+}
+
+#[test]
+fn opening_a_comment() {
+    let mut buffer = TokenBuffer::new("a = 1\n/* b */ c = 2\nd = 3");
+    buffer.edit(14..15, "");
+    assert_eq!(buffer.tokens(), full_scan(buffer.source()));
+    buffer.edit(6..8, "/*");
+    buffer.edit(0..0, "\u{FEFF}ผล ");
+    assert_eq!(buffer.tokens(), full_scan(buffer.source()));
+}
+
+proptest! {
+    #[test]
+    fn same_as_a_full_scan(
+        source in "[a-z0-9_.eExX+\\-*/#\"\\\\=\\[\\]() \n\tผ\u{FEFF}$]{0,40}",
+        edits in prop::collection::vec(
+            (any::<usize>(), 0usize..6, "[a-z0-9_.eE+\\-*/#\"\\\\=\\[\\]() \n$]{0,4}"),
+            1..8,
+        ),
+    ) {
+        let mut buffer = TokenBuffer::new(source.as_str());
+        prop_assert_eq!(buffer.tokens(), full_scan(&source));
+        for (start, len, text) in edits {
+            apply(&mut buffer, start, len, &text);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use scanner;
+use scanner::grammar::Token;
 
 #[test]
 fn zero() {
@@ -20,7 +21,7 @@ fn positive_integers() {
 fn negative_integers() {
     let input = "-1 -2 -3 -4 -5 -6 -7 -8 -9 -10";
     let expected_output =
-        vec!["-1/INT -2/INT -3/INT -4/INT -5/INT -6/INT -7/INT -8/INT -9/INT -10/INT"];
+        vec!["-/- 1/INT -/- 2/INT -/- 3/INT -/- 4/INT -/- 5/INT -/- 6/INT -/- 7/INT -/- 8/INT -/- 9/INT -/- 10/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -37,7 +38,7 @@ fn positive_integers_with_whitespace() {
 fn negative_integers_with_whitespace() {
     let input = "-1  -2  -3  -4  -5  -6  -7  -8  -9  -10";
     let expected_output =
-        vec!["-1/INT -2/INT -3/INT -4/INT -5/INT -6/INT -7/INT -8/INT -9/INT -10/INT"];
+        vec!["-/- 1/INT -/- 2/INT -/- 3/INT -/- 4/INT -/- 5/INT -/- 6/INT -/- 7/INT -/- 8/INT -/- 9/INT -/- 10/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -56,9 +57,56 @@ fn positive_integers_with_newline() {
 fn negative_integers_with_newline() {
     let input = "-1\n-2\n-3\n-4\n-5\n-6\n-7\n-8\n-9\n-10";
     let expected_output = vec![
-        "-1/INT", "-2/INT", "-3/INT", "-4/INT", "-5/INT", "-6/INT", "-7/INT", "-8/INT", "-9/INT",
-        "-10/INT",
+        "-/- 1/INT", "-/- 2/INT", "-/- 3/INT", "-/- 4/INT", "-/- 5/INT", "-/- 6/INT", "-/- 7/INT", "-/- 8/INT", "-/- 9/INT",
+        "-/- 10/INT",
     ];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
+
+#[test]
+fn prefixed_integers() {
+    let input = "0x1F 0b1010 0o17 0XfF";
+    let expected_output = vec!["0x1F/INT 0b1010/INT 0o17/INT 0XfF/INT"];
+    let output = scanner::run_scanner(input);
+    assert_eq!(output, expected_output);
+
+    let values: Vec<Token> = scanner::tokenize_spanned(input)
+        .into_iter()
+        .map(|t| t.token)
+        .collect();
+    assert_eq!(
+        values,
+        vec![Token::INT(31), Token::INT(10), Token::INT(15), Token::INT(255)]
+    );
+}
+
+#[test]
+fn digit_separators() {
+    let tokens = scanner::tokenize_spanned("1_000_000 0xFF_FF");
+    assert_eq!(tokens[0].token, Token::INT(1_000_000));
+    assert_eq!(tokens[0].lexeme, "1_000_000");
+    assert_eq!(tokens[1].token, Token::INT(0xFFFF));
+}
+
+#[test]
+fn integer_limits() {
+    let tokens = scanner::tokenize_spanned("9223372036854775807");
+    assert_eq!(tokens[0].token, Token::INT(i64::MAX));
+}
+
+#[test]
+fn minus_is_separate_token() {
+    let input = "x-1 -2.5";
+    let expected_output = vec!["x/VAR -/- 1/INT -/- 2.5/REAL"];
+    let output = scanner::run_scanner(input);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn integer_overflow() {
+    let input = "9223372036854775808 0x1_0000_0000_0000_0000";
+    let expected_output = vec!["9223372036854775808/ERR 0x1_0000_0000_0000_0000/ERR"];
+    let output = scanner::run_scanner(input);
+    assert_eq!(output, expected_output);
+}
@@ -0,0 +1,52 @@
+use scanner::grammar::Token;
+use scanner::json::{from_jsonl, run_scanner_jsonl, to_jsonl};
+use scanner::{Backend, LexError};
+
+#[test]
+fn token_line() {
+    let lines = run_scanner_jsonl("x // 2", Backend::Logos);
+    assert_eq!(
+        lines[0],
+        r#"{"kind":"VAR","value":"x","lexeme":"x","span":{"start":0,"end":1,"line":1,"column":1,"end_line":1,"end_column":2}}"#
+    );
+    assert!(lines[1].starts_with(r#"{"kind":"INTDIV","lexeme":"//","#));
+    assert!(lines[2].starts_with(r#"{"kind":"INT","value":2,"#));
+}
+
+#[test]
+fn round_trip() {
+    let input = "x = 2.5E-3 + 0x1F\ny = \"a\\\"b\" # note\n/* c */ z = list[2] // @ \"open";
+    let tokens = scanner::tokenize_spanned(input);
+    let text = to_jsonl(&tokens).join("\n");
+    assert_eq!(from_jsonl(&text).unwrap(), tokens);
+}
+
+#[test]
+fn reals_round_trip_exactly() {
+    let input = "1278.0275911367673 0.1000000000000000055511151231257827 2.2250738585072014e-308 9.007199254740993e15";
+    let tokens = scanner::tokenize_spanned(input);
+    let read = from_jsonl(&to_jsonl(&tokens).join("\n")).unwrap();
+    assert_eq!(read, tokens);
+    assert_eq!(read[0].token, Token::REAL(1278.0275911367673));
+}
+
+#[test]
+fn errors_round_trip() {
+    let tokens = scanner::tokenize_spanned("1e400 $");
+    let read = from_jsonl(&to_jsonl(&tokens).join("\n")).unwrap();
+    assert_eq!(
+        read[0].token,
+        Token::ERR(LexError::NumberOutOfRange(read[0].span))
+    );
+    assert_eq!(
+        read[1].token,
+        Token::ERR(LexError::UnexpectedCharacter(read[1].span))
+    );
+}
+
+#[test]
+fn bad_line() {
+    let text = "\n{\"kind\":\"ADD\",\"lexeme\":\"+\",\"span\":{\"start\":0,\"end\":1,\"line\":1,\"column\":1,\"end_line\":1,\"end_column\":2}}\n{\"kind\":\"NOPE\"}\n";
+    let err = from_jsonl(text).unwrap_err();
+    assert_eq!(err.line, 3);
+}
@@ -135,7 +135,7 @@ fn positive_assignment() {
 #[test]
 fn negative_addition() {
     let input = "-1 + -2";
-    let expected_output = vec!["-1/INT +/+ -2/INT"];
+    let expected_output = vec!["-/- 1/INT +/+ -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -143,7 +143,7 @@ fn negative_addition() {
 #[test]
 fn negative_subtraction() {
     let input = "-1 - -2";
-    let expected_output = vec!["-1/INT -/- -2/INT"];
+    let expected_output = vec!["-/- 1/INT -/- -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -151,7 +151,7 @@ fn negative_subtraction() {
 #[test]
 fn negative_multiplication() {
     let input = "-1 * -2";
-    let expected_output = vec!["-1/INT */* -2/INT"];
+    let expected_output = vec!["-/- 1/INT */* -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -159,7 +159,7 @@ fn negative_multiplication() {
 #[test]
 fn negative_division() {
     let input = "-1 / -2";
-    let expected_output = vec!["-1/INT /// -2/INT"];
+    let expected_output = vec!["-/- 1/INT /// -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -167,7 +167,7 @@ fn negative_division() {
 #[test]
 fn negative_division_float() {
     let input = "-1.0 / -2.0";
-    let expected_output = vec!["-1.0/REAL /// -2.0/REAL"];
+    let expected_output = vec!["-/- 1.0/REAL /// -/- 2.0/REAL"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -175,7 +175,7 @@ fn negative_division_float() {
 #[test]
 fn negative_division_integers() {
     let input = "-1 // -2";
-    let expected_output = vec!["-1/INT ///// -2/INT"];
+    let expected_output = vec!["-/- 1/INT ///// -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -183,7 +183,7 @@ fn negative_division_integers() {
 #[test]
 fn negative_exponent() {
     let input = "-1 ^ -2";
-    let expected_output = vec!["-1/INT ^/POW -2/INT"];
+    let expected_output = vec!["-/- 1/INT ^/POW -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -191,7 +191,7 @@ fn negative_exponent() {
 #[test]
 fn negative_exponent_float() {
     let input = "-1.0 ^ -2.0";
-    let expected_output = vec!["-1.0/REAL ^/POW -2.0/REAL"];
+    let expected_output = vec!["-/- 1.0/REAL ^/POW -/- 2.0/REAL"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -199,7 +199,7 @@ fn negative_exponent_float() {
 #[test]
 fn negative_greater_than() {
     let input = "-1 > -2";
-    let expected_output = vec!["-1/INT >/> -2/INT"];
+    let expected_output = vec!["-/- 1/INT >/> -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -207,7 +207,7 @@ fn negative_greater_than() {
 #[test]
 fn negative_greater_than_or_equal() {
     let input = "-1 >= -2";
-    let expected_output = vec!["-1/INT >=/>= -2/INT"];
+    let expected_output = vec!["-/- 1/INT >=/>= -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -215,7 +215,7 @@ fn negative_greater_than_or_equal() {
 #[test]
 fn negative_less_than() {
     let input = "-1 < -2";
-    let expected_output = vec!["-1/INT </< -2/INT"];
+    let expected_output = vec!["-/- 1/INT </< -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -223,7 +223,7 @@ fn negative_less_than() {
 #[test]
 fn negative_less_than_or_equal() {
     let input = "-1 <= -2";
-    let expected_output = vec!["-1/INT <=/<= -2/INT"];
+    let expected_output = vec!["-/- 1/INT <=/<= -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -231,7 +231,7 @@ fn negative_less_than_or_equal() {
 #[test]
 fn negative_equal() {
     let input = "-1 == -2";
-    let expected_output = vec!["-1/INT ==/== -2/INT"];
+    let expected_output = vec!["-/- 1/INT ==/== -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -239,7 +239,7 @@ fn negative_equal() {
 #[test]
 fn negative_not_equal() {
     let input = "-1 != -2";
-    let expected_output = vec!["-1/INT !=/!= -2/INT"];
+    let expected_output = vec!["-/- 1/INT !=/!= -/- 2/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -247,7 +247,7 @@ fn negative_not_equal() {
 #[test]
 fn negative_parentheses() {
     let input = "(-1 + -2)";
-    let expected_output = vec!["(/LPAREN -1/INT +/+ -2/INT )/RPAREN"];
+    let expected_output = vec!["(/LPAREN -/- 1/INT +/+ -/- 2/INT )/RPAREN"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -255,7 +255,7 @@ fn negative_parentheses() {
 #[test]
 fn negative_assignment() {
     let input = "a = -1";
-    let expected_output = vec!["a/VAR =/= -1/INT"];
+    let expected_output = vec!["a/VAR =/= -/- 1/INT"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
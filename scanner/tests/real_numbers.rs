@@ -1,4 +1,5 @@
 use scanner;
+use scanner::grammar::Token;
 
 #[test]
 fn zero_real_number() {
@@ -46,7 +47,7 @@ fn positive_real_numbers_with_newline() {
 #[test]
 fn negative_real_numbers() {
     let input = "-1.0 -2.0 -3.0 -4.0 -5.0 -6.0 -7.0 -8.0 -9.0 -10.0";
-    let expected_output = vec!["-1.0/REAL -2.0/REAL -3.0/REAL -4.0/REAL -5.0/REAL -6.0/REAL -7.0/REAL -8.0/REAL -9.0/REAL -10.0/REAL"];
+    let expected_output = vec!["-/- 1.0/REAL -/- 2.0/REAL -/- 3.0/REAL -/- 4.0/REAL -/- 5.0/REAL -/- 6.0/REAL -/- 7.0/REAL -/- 8.0/REAL -/- 9.0/REAL -/- 10.0/REAL"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -54,7 +55,7 @@ fn negative_real_numbers() {
 #[test]
 fn negative_real_numbers_with_whitespace() {
     let input = "-1.0  -2.0  -3.0  -4.0  -5.0  -6.0  -7.0  -8.0  -9.0  -10.0";
-    let expected_output = vec!["-1.0/REAL -2.0/REAL -3.0/REAL -4.0/REAL -5.0/REAL -6.0/REAL -7.0/REAL -8.0/REAL -9.0/REAL -10.0/REAL"];
+    let expected_output = vec!["-/- 1.0/REAL -/- 2.0/REAL -/- 3.0/REAL -/- 4.0/REAL -/- 5.0/REAL -/- 6.0/REAL -/- 7.0/REAL -/- 8.0/REAL -/- 9.0/REAL -/- 10.0/REAL"];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
@@ -63,17 +64,48 @@ fn negative_real_numbers_with_whitespace() {
 fn negative_real_numbers_with_newline() {
     let input = "-1.0\n-2.0\n-3.0\n-4.0\n-5.0\n-6.0\n-7.0\n-8.0\n-9.0\n-10.0";
     let expected_output = vec![
-        "-1.0/REAL",
-        "-2.0/REAL",
-        "-3.0/REAL",
-        "-4.0/REAL",
-        "-5.0/REAL",
-        "-6.0/REAL",
-        "-7.0/REAL",
-        "-8.0/REAL",
-        "-9.0/REAL",
-        "-10.0/REAL",
+        "-/- 1.0/REAL",
+        "-/- 2.0/REAL",
+        "-/- 3.0/REAL",
+        "-/- 4.0/REAL",
+        "-/- 5.0/REAL",
+        "-/- 6.0/REAL",
+        "-/- 7.0/REAL",
+        "-/- 8.0/REAL",
+        "-/- 9.0/REAL",
+        "-/- 10.0/REAL",
     ];
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
+
+#[test]
+fn real_number_forms() {
+    let input = ".5 5. 1E3 2.5E-2 1_000.000_5";
+    let expected_output = vec![".5/REAL 5./REAL 1E3/REAL 2.5E-2/REAL 1_000.000_5/REAL"];
+    let output = scanner::run_scanner(input);
+    assert_eq!(output, expected_output);
+
+    let values: Vec<Token> = scanner::tokenize_spanned(input)
+        .into_iter()
+        .map(|t| t.token)
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            Token::REAL(0.5),
+            Token::REAL(5.0),
+            Token::REAL(1000.0),
+            Token::REAL(0.025),
+            Token::REAL(1000.0005),
+        ]
+    );
+}
+
+#[test]
+fn real_number_overflow() {
+    let input = "1e400";
+    let expected_output = vec!["1e400/ERR"];
+    let output = scanner::run_scanner(input);
+    assert_eq!(output, expected_output);
+}
@@ -0,0 +1,67 @@
+use scanner::grammar::Token;
+use scanner::LexError;
+use scanner::Span;
+
+#[test]
+fn single_token() {
+    let tokens = scanner::tokenize_spanned("x");
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].token, Token::VAR("x".to_string()));
+    assert_eq!(tokens[0].lexeme, "x");
+    assert_eq!(
+        tokens[0].span,
+        Span {
+            start: 0,
+            end: 1,
+            line: 1,
+            column: 1,
+            end_line: 1,
+            end_column: 2,
+        }
+    );
+}
+
+#[test]
+fn multiple_spaces() {
+    let tokens = scanner::tokenize_spanned("x   =  10");
+    let columns: Vec<usize> = tokens.iter().map(|t| t.span.column).collect();
+    assert_eq!(columns, vec![1, 5, 8]);
+    assert_eq!(tokens[2].span.start, 7);
+    assert_eq!(tokens[2].span.end, 9);
+}
+
+#[test]
+fn tabs() {
+    let tokens = scanner::tokenize_spanned("\tx\t=\t1");
+    let columns: Vec<usize> = tokens.iter().map(|t| t.span.column).collect();
+    assert_eq!(columns, vec![2, 4, 6]);
+}
+
+#[test]
+fn lines() {
+    let tokens = scanner::tokenize_spanned("x = 1\n\n  y = 2");
+    let positions: Vec<(usize, usize)> = tokens
+        .iter()
+        .filter(|t| t.token != Token::NEWLINE)
+        .map(|t| (t.span.line, t.span.column))
+        .collect();
+    assert_eq!(positions, vec![(1, 1), (1, 3), (1, 5), (3, 3), (3, 5), (3, 7)]);
+}
+
+#[test]
+fn error_token_span() {
+    let tokens = scanner::tokenize_spanned("x @ 1");
+    assert_eq!(tokens[1].token, Token::ERR(LexError::UnexpectedCharacter(tokens[1].span)));
+    assert_eq!(tokens[1].lexeme, "@");
+    assert_eq!((tokens[1].span.line, tokens[1].span.column), (1, 3));
+}
+
+#[test]
+fn token_length_matches_span_width() {
+    let tokens = scanner::tokenize_spanned("0xFF 1_000 .5 5. 1E3 # note\n/* block */ x");
+    let lengths: Vec<usize> = tokens.iter().map(|t| t.token_length()).collect();
+    assert_eq!(lengths, vec![4, 5, 2, 2, 3, 6, 1, 11, 1]);
+    for token in tokens.iter().filter(|t| t.token != Token::NEWLINE) {
+        assert_eq!(token.token_length(), token.span.end_column - token.span.column);
+    }
+}
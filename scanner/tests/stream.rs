@@ -0,0 +1,111 @@
+use scanner::stream::{stream_scanner, stream_scanner_with, TokWriter, TokenReader};
+use scanner::Backend;
+use std::io;
+
+const INPUTS: [&str; 7] = [
+    include_str!("../../sample.txt"),
+    "x = list[10]\nx[0] = 0x1F + 1_000 // 2.5E-3",
+    "y = \"a\\tb\" # comment\n/* a /* b */ c */ z",
+    "\u{FEFF}ผลรวม = 5\ncafe\u{301} != 1e5\n",
+    "x = @ 5\n\"unterminated\ny = 1e400 /* open",
+    "1e+ 1.e5 0x 12abc\n\n\n",
+    "",
+];
+
+#[test]
+fn tokens_across_chunks() {
+    for input in INPUTS {
+        for backend in [Backend::Native, Backend::Logos] {
+            let expected = scanner::tokenize_spanned_with(input, backend);
+            for chunk_size in [1, 2, 3, 5, 64] {
+                let tokens: Vec<_> = TokenReader::with_chunk_size(input.as_bytes(), chunk_size)
+                    .with_backend(backend)
+                    .collect::<io::Result<_>>()
+                    .unwrap();
+                assert_eq!(
+                    tokens, expected,
+                    "{:?} in chunks of {} with {:?}",
+                    input, chunk_size, backend
+                );
+            }
+        }
+    }
+}
+
+// Counts the calls to `read`
+struct CountingReader<'a>(&'a [u8], usize);
+
+impl io::Read for CountingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.1 += 1;
+        self.0.read(buf)
+    }
+}
+
+#[test]
+fn long_tokens_are_not_read_chunk_by_chunk() {
+    let input = format!("x /* {} */ y", "a".repeat(100_000));
+    let mut reader = CountingReader(input.as_bytes(), 0);
+    let tokens: Vec<_> = TokenReader::with_chunk_size(&mut reader, 16)
+        .collect::<io::Result<_>>()
+        .unwrap();
+    assert_eq!(tokens.len(), 3);
+    assert!(reader.1 < 32, "{} reads", reader.1);
+}
+
+#[test]
+fn lines_are_counted_like_str_lines() {
+    for input in INPUTS.into_iter().chain(["\n", "a\r\n", "a\n\u{FEFF}"]) {
+        let mut reader = TokenReader::with_chunk_size(input.as_bytes(), 3);
+        reader.by_ref().for_each(drop);
+        assert_eq!(reader.lines(), input.lines().count(), "{:?}", input);
+    }
+}
+
+#[test]
+fn same_output_as_run_scanner() {
+    for input in INPUTS
+        .into_iter()
+        .chain(["\n\nx\n", "# only a comment\n\n"])
+    {
+        let out = stream_scanner(input.as_bytes(), Vec::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            scanner::run_scanner_with(input, Backend::Native).join("\n"),
+            "{:?}",
+            input
+        );
+        let out = stream_scanner_with(input.as_bytes(), Backend::Logos, Vec::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            scanner::run_scanner_with(input, Backend::Logos).join("\n"),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn writer_takes_any_tokens() {
+    let input = "x = 1\n\ny = 2 # two\n";
+    let mut writer = TokWriter::new(Vec::new());
+    for token in scanner::tokenize_spanned(input) {
+        writer.write(&token).unwrap();
+    }
+    let out = writer.finish(input.lines().count()).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "x/VAR =/= 1/INT\n\ny/VAR =/= 2/INT"
+    );
+}
+
+#[test]
+fn invalid_utf8() {
+    let input: &[u8] = b"x = 1\ny = \xFF";
+    let result: io::Result<Vec<_>> = TokenReader::with_chunk_size(input, 4).collect();
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+    // A character cut off at the end of the input
+    let result: io::Result<Vec<_>> = TokenReader::new(&"xผ".as_bytes()[..3]).collect();
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
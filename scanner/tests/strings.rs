@@ -0,0 +1,37 @@
+use scanner::grammar::Token;
+use scanner::LexError;
+
+#[test]
+fn string_literal() {
+    let input = r#"x = "hello world""#;
+    let expected_output = vec![r#"x/VAR =/= "hello world"/STRING"#];
+    let output = scanner::run_scanner(input);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn string_escapes() {
+    let tokens = scanner::tokenize_spanned(r#""a\tb\n\"c\"\\""#);
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].token, Token::STRING("a\tb\n\"c\"\\".to_string()));
+    assert_eq!(tokens[0].token.to_string(), r#""a\tb\n\"c\"\\""#);
+}
+
+#[test]
+fn invalid_escape() {
+    let tokens = scanner::tokenize_spanned(r#""\q""#);
+    assert_eq!(tokens[0].token, Token::ERR(LexError::InvalidEscape(tokens[0].span)));
+}
+
+#[test]
+fn unterminated_string() {
+    let input = "x = \"abc\ny = 1";
+    let expected_output = vec!["x/VAR =/= \"abc/ERR", "y/VAR =/= 1/INT"];
+    assert_eq!(scanner::run_scanner(input), expected_output);
+
+    let tokens = scanner::tokenize_spanned(input);
+    assert_eq!(
+        tokens[2].token,
+        Token::ERR(LexError::UnterminatedString(tokens[2].span))
+    );
+}
@@ -0,0 +1,64 @@
+use scanner::grammar::Token;
+use scanner::{Backend, LexError};
+
+#[test]
+fn thai_identifier() {
+    let input = "ผลรวม = 10";
+    let expected_output = vec!["ผลรวม/VAR =/= 10/INT"];
+    let output = scanner::run_scanner(input);
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn identifier_with_combining_marks() {
+    let tokens = scanner::tokenize_spanned("ตัวแปร_1");
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].token, Token::VAR("ตัวแปร_1".to_string()));
+}
+
+#[test]
+fn identifiers_are_nfc_normalized() {
+    // "e" followed by a combining acute accent
+    let tokens = scanner::tokenize_spanned("cafe\u{301}");
+    assert_eq!(tokens[0].token, Token::VAR("caf\u{e9}".to_string()));
+    assert_eq!(tokens[0].lexeme, "cafe\u{301}");
+}
+
+#[test]
+fn columns_count_characters() {
+    let tokens = scanner::tokenize_spanned("ผลรวม = 10");
+    let columns: Vec<usize> = tokens.iter().map(|t| t.span.column).collect();
+    assert_eq!(columns, vec![1, 7, 9]);
+    assert_eq!(tokens[0].span.end_column, 6);
+    assert_eq!(tokens[0].span.end, 15);
+    assert_eq!(tokens[0].token_length(), 5);
+}
+
+#[test]
+fn byte_order_mark() {
+    let input = "\u{FEFF}x = 1";
+    let expected_output = vec!["x/VAR =/= 1/INT"];
+    assert_eq!(scanner::run_scanner(input), expected_output);
+
+    let tokens = scanner::tokenize_spanned(input);
+    assert_eq!(tokens[0].span.start, 3);
+    assert_eq!(tokens[0].span.column, 1);
+}
+
+#[test]
+fn byte_order_mark_only_at_the_start() {
+    let tokens = scanner::tokenize_spanned("x = \u{FEFF}1");
+    assert_eq!(
+        tokens[2].token,
+        Token::ERR(LexError::UnexpectedCharacter(tokens[2].span))
+    );
+    assert_eq!((tokens[2].span.column, tokens[3].span.column), (5, 6));
+
+    let expected_output = vec!["\u{FEFF}/ERR x/VAR"];
+    for backend in [Backend::Logos, Backend::Native] {
+        assert_eq!(
+            scanner::run_scanner_with("\u{FEFF}\u{FEFF}x", backend),
+            expected_output
+        );
+    }
+}
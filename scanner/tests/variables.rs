@@ -39,3 +39,12 @@ fn invalid_characters() {
     let output = scanner::run_scanner(input);
     assert_eq!(output, expected_output);
 }
+
+#[test]
+fn keywords_are_whole_identifiers() {
+    let input = "list lists listx xlist list_ list";
+    let expected_output = vec!["list/list lists/VAR listx/VAR xlist/VAR list_/VAR list/list"];
+    for backend in [scanner::Backend::Logos, scanner::Backend::Native] {
+        assert_eq!(scanner::run_scanner_with(input, backend), expected_output);
+    }
+}
@@ -1,15 +1,13 @@
-use scanner;
 use parser::symbol_table::SymbolTable;
-use parser::{Parser, ParseError};
-use codegen;
+use parser::{LineResult, Parser};
 
 const SCANNER_DEFAULT_OUTPUT_FILE: &str = "hiwkhao.tok";
 const SYMBOL_TABLE_DEFAULT_OUTPUT_FILE: &str = "hiwkhao.csv";
 const PARSER_DEFAULT_OUTPUT_FILE: &str = "hiwkhao.bracket";
 const CODEGEN_DEFAULT_OUTPUT_FILE: &str = "hiwkhao.asm";
 
-fn _scanner(input: &String) {
-    let result = scanner::run_scanner(&input);
+fn _scanner(input: &str) {
+    let result = scanner::run_scanner(input);
     //println!("{}", result.join("\n"));
 
     let output_file = std::env::args()
@@ -19,24 +17,26 @@ fn _scanner(input: &String) {
     std::fs::write(output_file, result.join("\n")).unwrap();
 }
 
-fn generate_code(parsed_data: Vec<Result<parser::Expr, ParseError>>) -> Vec<String> {
+fn generate_code(parsed_data: Vec<LineResult>) -> Vec<String> {
     let mut result: Vec<String> = Vec::new();
     
-    for parsed_expr in parsed_data {
+    for (line, parsed_expr) in parsed_data {
         match parsed_expr {
             Ok(expr) => {
-                let instructions = codegen::generate_assembly(&expr);
+                let instructions = codegen::generate_mapped_assembly(&expr);
                 if !result.is_empty() {
                     result.push(String::new());
                 }
                 result.extend(instructions);
             }
-            Err(err) => {
-                eprintln!("Error during parsing: {:?}", err);
-                if !result.is_empty() && result.last() != Some(&String::new()) {
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("Error during parsing: {}", error);
+                }
+                if !result.is_empty() {
                     result.push(String::new());
                 }
-                result.push("ERROR".to_string());
+                result.extend(codegen::generate_mapped_error(&line));
             }
         }
     }
@@ -58,7 +58,7 @@ fn main() {
     let tokens = scanner::tokenize(&input);
     let mut parser = Parser::new(vec![]);
 
-    let parsed_data = parser.parse_tokens(tokens.clone());
+    let parsed_data = parser.parse_spanned_tokens(scanner::tokenize_spanned(&input));
 
     let result = parser.parse_tokens_fancy(tokens);
 